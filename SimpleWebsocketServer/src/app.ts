@@ -1,10 +1,95 @@
+import {
+    createCipheriv,
+    createECDH,
+    createHmac,
+    createPrivateKey,
+    hkdfSync,
+    randomBytes,
+    randomUUID,
+    scryptSync,
+    sign,
+    timingSafeEqual,
+} from 'crypto';
+import https from 'https';
 import WebSocket, { WebSocketServer } from 'ws';
 
 const PORT = process.env.PORT ? parseInt(process.env.PORT) : 8080;
+// How long a registration stays valid before the client has to sign in again.
+const SESSION_TTL_MS = (process.env.SESSION_TTL_MINUTES ? parseInt(process.env.SESSION_TTL_MINUTES) : 12 * 60) * 60 * 1000;
+// Secret for hCaptcha or Turnstile; when set, registrations need a solved CAPTCHA.
+const CAPTCHA_SECRET = process.env.CAPTCHA_SECRET || '';
+const CAPTCHA_VERIFY_URL =
+    process.env.CAPTCHA_PROVIDER === 'turnstile'
+        ? 'https://challenges.cloudflare.com/turnstile/v0/siteverify'
+        : 'https://hcaptcha.com/siteverify';
+// Comma-separated account usernames that get the admin role, e.g. `ADMINS=alice,bob`.
+// Only granted to a client that signed in to that account with its password.
+const ADMINS = new Set((process.env.ADMINS || '').split(',').map((n) => n.trim()).filter((n) => n.length > 0));
+// VAPID key pair for Web Push, base64url encoded; without them nothing is pushed.
+const VAPID_PUBLIC_KEY = process.env.VAPID_PUBLIC_KEY || '';
+const VAPID_PRIVATE_KEY = process.env.VAPID_PRIVATE_KEY || '';
+// Who push services should contact about this server, a mailto: or https: URL.
+const VAPID_SUBJECT = process.env.VAPID_SUBJECT || 'mailto:admin@localhost';
 interface User {
     ws: WebSocket;
     nick: String;
     isAlive: boolean;
+    bio: string;
+    status: string;
+    avatar: string;
+    displayName: string;
+    role: Role;
+    joinedAt: number;
+    // When the user last posted, for slow mode.
+    lastSentAt: number;
+    sessionToken: string;
+    sessionExpiresAt: number;
+    // Account the user proved with a password sign-in; renewing the session takes its token.
+    account?: string;
+}
+
+type Role = 'admin' | 'owner' | 'moderator' | 'member';
+
+const ROLE_RANK: Record<Role, number> = { member: 0, moderator: 1, owner: 2, admin: 3 };
+
+type AuditAction = 'delete' | 'kick' | 'ban' | 'pin' | 'unpin';
+
+interface AuditEvent {
+    action: AuditAction;
+    actor: string;
+    target?: string;
+    detail?: string;
+    time: number;
+}
+
+interface ChatMessage {
+    id: number;
+    from: string;
+    to?: string;
+    message: string;
+    time: number;
+    expiresAt?: number;
+    [extra: string]: unknown;
+}
+
+interface Account {
+    email: string;
+    username: string;
+    salt: string;
+    passwordHash: string;
+    // Base32 TOTP secret once two-factor is switched on.
+    totpSecret?: string;
+    // Secret handed out by `totpsetup`, waiting for a first code to confirm it.
+    pendingTotpSecret?: string;
+    // Wrong codes in a row for `totpsetup`, and when it stops refusing them after too many.
+    failedCodes: number;
+    codesLockedUntil: number;
+}
+
+// What a browser hands out from `PushManager.subscribe`.
+interface PushSubscription {
+    endpoint: string;
+    keys: { p256dh: string; auth: string };
 }
 
 interface Message {
@@ -13,7 +98,68 @@ interface Message {
     dataArray: String[];
 }
 
+interface PollOption {
+    text: string;
+    votes: string[];
+}
+
+interface Poll {
+    id: number;
+    creator: string;
+    question: string;
+    options: PollOption[];
+    closed: boolean;
+}
+
+const MAX_AVATAR_LENGTH = 300 * 1024;
+const MAX_HISTORY = 500;
+const MAX_AUDIT_LOG = 1000;
+const MAX_DELIVERED_OUTBOX = 1000;
+// Messages sent either side of a permalinked one when a client asks for history.
+const HISTORY_CONTEXT = 25;
+// How long a password sign-in waits for its second factor.
+const TOTP_CHALLENGE_TTL_MS = 5 * 60 * 1000;
+// Wrong codes a challenge, or an account changing its authenticator, takes before giving up.
+const MAX_TOTP_ATTEMPTS = 5;
+// How long a password sign-in vouches for its account when joining the chat.
+const ACCOUNT_TOKEN_TTL_MS = 30 * 24 * 60 * 60 * 1000;
+// The server only has the one room; the name is what clients key room settings by.
+const ROOM = 'general';
+
 let users: User[] = [];
+// When each departed user was last connected, keyed by nick.
+const lastSeen = new Map<string, number>();
+let polls: Poll[] = [];
+let nextPollId = 1;
+// Lifetime of new messages in seconds; 0 keeps them forever.
+let ephemeralSeconds = 0;
+// Minimum gap between posts from the same member in seconds; 0 disables slow mode.
+let slowModeSeconds = 0;
+// Words moderators don't allow; clients mask them and warn before sending.
+let blockedWords: string[] = [];
+// Recent messages, kept so pins and deletes can be checked against their author.
+let history: ChatMessage[] = [];
+let nextMessageId = 1;
+let pinned: ChatMessage | null = null;
+// Nicks that may not register again until the server restarts.
+const banned = new Set<string>();
+let auditLog: AuditEvent[] = [];
+// Registered accounts keyed by lowercased email. Kept in memory, like everything else here.
+const accounts = new Map<string, Account>();
+// Password sign-ins waiting for a TOTP code, keyed by challenge id.
+const totpChallenges = new Map<string, { email: string; expiresAt: number; failures: number }>();
+// Admin dashboards: connected and allowed to moderate, but not chat participants.
+let observers: User[] = [];
+// Browsers to push mentions and direct messages to while their user is offline, keyed by nick.
+const pushSubscriptions = new Map<string, PushSubscription[]>();
+// Session tokens handed out, kept after their user leaves so messages written
+// offline can still be sent as them until the session would have run out.
+// The user stays with them so slow mode still applies once they have gone.
+const sessions = new Map<string, { nick: string; expiresAt: number; user: User }>();
+// Tokens handed out by password sign-ins, proving which account a client signed in to.
+const accountTokens = new Map<string, { username: string; expiresAt: number }>();
+// Ids of offline messages already posted, so a retried outbox doesn't post them twice.
+let deliveredOutbox: string[] = [];
 
 console.log(`Listening on port ${PORT}`);
 const wss = new WebSocketServer({ port: PORT });
@@ -25,25 +171,536 @@ wss.on('connection', (ws: WebSocket) => {
         const raw_data = data.toString();
         try {
             const parsed_data: Message = JSON.parse(raw_data);
+            const current = findActor(ws);
+            if (
+                current &&
+                parsed_data.messageType !== 'reauth' &&
+                Date.now() > current.sessionExpiresAt
+            ) {
+                ws.send(JSON.stringify({ messageType: 'error', data: 'auth_expired' }));
+                return;
+            }
             switch (parsed_data.messageType) {
-                case 'register':
-                    users.push({ ws, nick: parsed_data.data, isAlive: true });
-                    broadcast(JSON.stringify({ messageType: 'users', dataArray: users.map((u) => u.nick) }));
+                case 'register': {
+                    // The CAPTCHA token from the login screen rides along after the avatar.
+                    const captchaToken = parsed_data.dataArray && parsed_data.dataArray[1];
+                    verifyCaptcha(captchaToken).then((ok) => {
+                        if (!ok) {
+                            ws.send(JSON.stringify({ messageType: 'error', data: 'captcha_failed' }));
+                            ws.close();
+                            return;
+                        }
+                        register(ws, parsed_data);
+                    });
+                    break;
+                }
+                case 'admin': {
+                    // The dashboard sends the account token of its password sign-in.
+                    const nick = signedInAs(parsed_data.data);
+                    if (nick === undefined || !ADMINS.has(nick)) {
+                        ws.send(JSON.stringify({ messageType: 'system', data: 'Only admins can open the dashboard.' }));
+                        ws.close();
+                        break;
+                    }
+                    observers.push({
+                        ws,
+                        nick,
+                        isAlive: true,
+                        bio: '',
+                        status: '',
+                        avatar: '',
+                        displayName: '',
+                        role: 'admin',
+                        joinedAt: Date.now(),
+                        lastSentAt: 0,
+                        sessionToken: '',
+                        sessionExpiresAt: 0,
+                        account: nick,
+                    });
+                    startSession(observers[observers.length - 1]);
+                    auditLog.forEach((event) => ws.send(JSON.stringify({ messageType: 'auditevent', data: JSON.stringify(event) })));
+                    sendStats();
+                    break;
+                }
+                case 'createaccount': {
+                    const { email, username, password } = JSON.parse(parsed_data.data as string);
+                    if (
+                        typeof email !== 'string' ||
+                        !/^[^\s@]+@[^\s@]+\.[^\s@]+$/.test(email) ||
+                        typeof username !== 'string' ||
+                        username.trim().length < 2 ||
+                        username.length > 20 ||
+                        typeof password !== 'string' ||
+                        password.length < 8
+                    ) {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'invalid' }));
+                        break;
+                    }
+                    const key = email.toLowerCase();
+                    if (accounts.has(key)) {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'email_taken' }));
+                        break;
+                    }
+                    if (findAccountByName(username)) {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'name_taken' }));
+                        break;
+                    }
+                    const salt = randomBytes(16).toString('hex');
+                    const account: Account = {
+                        email,
+                        username,
+                        salt,
+                        passwordHash: hashPassword(password, salt),
+                        failedCodes: 0,
+                        codesLockedUntil: 0,
+                    };
+                    accounts.set(key, account);
+                    sendAccount(ws, account);
                     break;
+                }
+                case 'login': {
+                    const { email, password } = JSON.parse(parsed_data.data as string);
+                    const account = checkCredentials(email, password);
+                    if (!account) {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'bad_credentials' }));
+                        break;
+                    }
+                    if (account.totpSecret) {
+                        const challenge = randomUUID();
+                        totpChallenges.set(challenge, {
+                            email: account.email.toLowerCase(),
+                            expiresAt: Date.now() + TOTP_CHALLENGE_TTL_MS,
+                            failures: 0,
+                        });
+                        ws.send(JSON.stringify({ messageType: 'totp', data: challenge }));
+                        break;
+                    }
+                    sendAccount(ws, account);
+                    break;
+                }
+                case 'verifytotp': {
+                    const { challenge, code } = JSON.parse(parsed_data.data as string);
+                    const pending = totpChallenges.get(challenge);
+                    const account = pending && pending.expiresAt > Date.now() ? accounts.get(pending.email) : undefined;
+                    if (!pending || !account || !account.totpSecret) {
+                        totpChallenges.delete(challenge);
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'challenge_expired' }));
+                        break;
+                    }
+                    if (!checkTotp(account.totpSecret, code)) {
+                        // Six digits don't take long to guess; make the client sign in again.
+                        pending.failures++;
+                        if (pending.failures >= MAX_TOTP_ATTEMPTS) {
+                            totpChallenges.delete(challenge);
+                            ws.send(JSON.stringify({ messageType: 'error', data: 'challenge_expired' }));
+                        } else {
+                            ws.send(JSON.stringify({ messageType: 'error', data: 'bad_code' }));
+                        }
+                        break;
+                    }
+                    totpChallenges.delete(challenge);
+                    sendAccount(ws, account);
+                    break;
+                }
+                case 'totpsetup': {
+                    const { email, password, code } = JSON.parse(parsed_data.data as string);
+                    const account = checkCredentials(email, password);
+                    if (!account) {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'bad_credentials' }));
+                        break;
+                    }
+                    // Replacing an authenticator takes a code from the current one, so the
+                    // password alone can't get around two-factor.
+                    if (account.totpSecret && typeof code !== 'string') {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'code_required' }));
+                        break;
+                    }
+                    if (account.totpSecret && !checkAccountCode(account, code)) {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'bad_code' }));
+                        break;
+                    }
+                    account.pendingTotpSecret = base32(randomBytes(20));
+                    const label = encodeURIComponent(`YewChat:${account.username}`);
+                    ws.send(
+                        JSON.stringify({
+                            messageType: 'totpsecret',
+                            data: JSON.stringify({
+                                secret: account.pendingTotpSecret,
+                                uri: `otpauth://totp/${label}?secret=${account.pendingTotpSecret}&issuer=YewChat`,
+                            }),
+                        })
+                    );
+                    break;
+                }
+                case 'totpenable': {
+                    const { email, password, code } = JSON.parse(parsed_data.data as string);
+                    const account = checkCredentials(email, password);
+                    if (!account || !account.pendingTotpSecret) {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'bad_credentials' }));
+                        break;
+                    }
+                    if (!checkTotp(account.pendingTotpSecret, code)) {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'bad_code' }));
+                        break;
+                    }
+                    account.totpSecret = account.pendingTotpSecret;
+                    account.pendingTotpSecret = undefined;
+                    // No account token: signing in from here on goes through the code challenge.
+                    ws.send(JSON.stringify({ messageType: 'totpenabled', data: account.username }));
+                    break;
+                }
+                case 'reauth': {
+                    // Users who signed in to an account send its token after their name; a
+                    // plain login has nothing more than the name to show.
+                    const user = findActor(ws);
+                    if (!user || user.nick !== parsed_data.data) {
+                        break;
+                    }
+                    if (user.account !== undefined && signedInAs(parsed_data.dataArray && parsed_data.dataArray[0]) !== user.account) {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'reauth_failed' }));
+                        break;
+                    }
+                    startSession(user);
+                    break;
+                }
+                case 'history': {
+                    // Messages around a permalinked one, for clients that joined after it was sent.
+                    const user = users.find((u) => u.ws === ws);
+                    const index = history.findIndex((m) => m.id === Number(parsed_data.data));
+                    if (!user) {
+                        break;
+                    }
+                    const around =
+                        index < 0
+                            ? []
+                            : history
+                                  .slice(Math.max(0, index - HISTORY_CONTEXT), index + HISTORY_CONTEXT + 1)
+                                  .filter((m) => !m.to || m.to === user.nick || m.from === user.nick)
+                                  .filter((m) => !m.expiresAt || m.expiresAt > Date.now());
+                    ws.send(JSON.stringify({ messageType: 'history', data: JSON.stringify(around) }));
+                    break;
+                }
+                case 'whois': {
+                    const name = parsed_data.data as string;
+                    const online = users.find((u) => u.nick === name);
+                    if (online) {
+                        const { nick, displayName, avatar, bio, status, role, joinedAt } = online;
+                        const profile = { name: nick, displayName, avatar, bio, status, role, joinedAt, rooms: [ROOM] };
+                        ws.send(JSON.stringify({ messageType: 'userinfo', data: JSON.stringify(profile) }));
+                    } else if (lastSeen.has(name)) {
+                        const profile = { name, lastSeen: lastSeen.get(name), rooms: [] };
+                        ws.send(JSON.stringify({ messageType: 'userinfo', data: JSON.stringify(profile) }));
+                    } else {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'unknown_user' }));
+                    }
+                    break;
+                }
+                case 'unban': {
+                    const admin = findActor(ws);
+                    if (admin && admin.role === 'admin' && banned.delete(parsed_data.data as string)) {
+                        sendStats();
+                    }
+                    break;
+                }
                 case 'message':
                     const sender = users.find((u) => u.ws === ws);
-                    if (sender) {
-                        broadcast(
+                    if (sender && allowSend(sender)) {
+                        sendChatMessage({ from: sender.nick as string, message: parsed_data.data as string });
+                    }
+                    break;
+                case 'poll': {
+                    const creator = users.find((u) => u.ws === ws);
+                    const { question, options } = JSON.parse(parsed_data.data as string);
+                    if (creator && question && Array.isArray(options) && options.length >= 2 && allowSend(creator)) {
+                        const poll: Poll = {
+                            id: nextPollId++,
+                            creator: creator.nick as string,
+                            question,
+                            options: options.map((text: string) => ({ text, votes: [] })),
+                            closed: false,
+                        };
+                        polls.push(poll);
+                        broadcastPoll(poll);
+                    }
+                    break;
+                }
+                case 'vote': {
+                    const voter = users.find((u) => u.ws === ws);
+                    const { pollId, option } = JSON.parse(parsed_data.data as string);
+                    const poll = polls.find((p) => p.id === pollId);
+                    if (voter && poll && !poll.closed && poll.options[option]) {
+                        const nick = voter.nick as string;
+                        const alreadyChosen = poll.options[option].votes.includes(nick);
+                        poll.options.forEach((o) => (o.votes = o.votes.filter((v) => v !== nick)));
+                        if (!alreadyChosen) {
+                            poll.options[option].votes.push(nick);
+                        }
+                        broadcastPoll(poll);
+                    }
+                    break;
+                }
+                case 'direct': {
+                    const sender = users.find((u) => u.ws === ws);
+                    const { to, message } = JSON.parse(parsed_data.data as string);
+                    const recipients = users.filter((u) => u.nick === to);
+                    // Recipients who left can still be reached by push.
+                    if (sender && (recipients.length > 0 || pushSubscriptions.has(to)) && allowSend(sender)) {
+                        sendChatMessage({ from: sender.nick as string, to, message }, [sender, ...recipients]);
+                    }
+                    break;
+                }
+                case 'push': {
+                    const user = users.find((u) => u.ws === ws);
+                    const subscription = JSON.parse(parsed_data.data as string);
+                    if (
+                        !user ||
+                        !VAPID_PRIVATE_KEY ||
+                        typeof subscription.endpoint !== 'string' ||
+                        !subscription.endpoint.startsWith('https://') ||
+                        !subscription.keys ||
+                        typeof subscription.keys.p256dh !== 'string' ||
+                        typeof subscription.keys.auth !== 'string'
+                    ) {
+                        break;
+                    }
+                    const nick = user.nick as string;
+                    const { endpoint, keys } = subscription;
+                    const others = (pushSubscriptions.get(nick) || []).filter((s) => s.endpoint !== endpoint);
+                    pushSubscriptions.set(nick, [...others, { endpoint, keys: { p256dh: keys.p256dh, auth: keys.auth } }]);
+                    break;
+                }
+                case 'outbox': {
+                    // Messages written offline, sent by the page or its service worker once the
+                    // network is back. The token proves who wrote them; no registration needed.
+                    const { token, messages } = JSON.parse(parsed_data.data as string);
+                    const session = sessions.get(token);
+                    if (!session || session.expiresAt < Date.now() || banned.has(session.nick) || !Array.isArray(messages)) {
+                        ws.send(JSON.stringify({ messageType: 'error', data: 'outbox_rejected' }));
+                        break;
+                    }
+                    const author = users.find((u) => u.nick === session.nick) || session.user;
+                    const delivered: string[] = [];
+                    let heldBack = 0;
+                    messages.forEach((queued: { id?: unknown; to?: unknown; message?: unknown }) => {
+                        if (typeof queued.id !== 'string' || typeof queued.message !== 'string') {
+                            return;
+                        }
+                        if (!deliveredOutbox.includes(queued.id)) {
+                            // Left out of the reply, so the client keeps it for its next flush.
+                            if (!allowSend(author)) {
+                                heldBack++;
+                                return;
+                            }
+                            deliveredOutbox = [...deliveredOutbox, queued.id].slice(-MAX_DELIVERED_OUTBOX);
+                            if (typeof queued.to === 'string') {
+                                const to = queued.to;
+                                const recipients = users.filter((u) => u.nick === to || u.nick === session.nick);
+                                sendChatMessage({ from: session.nick, to, message: queued.message }, recipients);
+                            } else {
+                                sendChatMessage({ from: session.nick, message: queued.message });
+                            }
+                        }
+                        delivered.push(queued.id);
+                    });
+                    if (heldBack > 0) {
+                        ws.send(
                             JSON.stringify({
-                                messageType: 'message',
-                                data: JSON.stringify({
-                                    from: sender.nick,
-                                    message: parsed_data.data,
-                                    time: Date.now(),
-                                }),
+                                messageType: 'system',
+                                data: `Slow mode held back ${heldBack} of your offline messages; they will be sent later.`,
                             })
                         );
                     }
+                    ws.send(JSON.stringify({ messageType: 'outbox', data: JSON.stringify(delivered) }));
+                    break;
+                }
+                case 'location': {
+                    const sharer = users.find((u) => u.ws === ws);
+                    const { latitude, longitude } = JSON.parse(parsed_data.data as string);
+                    if (sharer && typeof latitude === 'number' && typeof longitude === 'number' && allowSend(sharer)) {
+                        sendChatMessage({
+                            from: sharer.nick as string,
+                            message: 'shared a location',
+                            location: { latitude, longitude },
+                        });
+                    }
+                    break;
+                }
+                case 'ephemeral': {
+                    const seconds = parseInt(parsed_data.data as string);
+                    const changer = users.find((u) => u.ws === ws);
+                    if (changer && canModerate(changer) && !isNaN(seconds) && seconds >= 0) {
+                        ephemeralSeconds = seconds;
+                        broadcast(JSON.stringify({ messageType: 'ephemeral', data: String(ephemeralSeconds) }));
+                        broadcastSystem(
+                            seconds > 0
+                                ? `${changer.nick} turned on disappearing messages (${seconds}s)`
+                                : `${changer.nick} turned off disappearing messages`
+                        );
+                    }
+                    break;
+                }
+                case 'slowmode': {
+                    const seconds = parseInt(parsed_data.data as string);
+                    const changer = users.find((u) => u.ws === ws);
+                    if (changer && canModerate(changer) && !isNaN(seconds) && seconds >= 0) {
+                        slowModeSeconds = seconds;
+                        broadcast(JSON.stringify({ messageType: 'slowmode', data: String(slowModeSeconds) }));
+                        broadcastSystem(
+                            seconds > 0
+                                ? `${changer.nick} turned on slow mode (${seconds}s)`
+                                : `${changer.nick} turned off slow mode`
+                        );
+                    }
+                    break;
+                }
+                case 'blockedwords': {
+                    const changer = users.find((u) => u.ws === ws);
+                    const words = JSON.parse(parsed_data.data as string);
+                    if (changer && canModerate(changer) && Array.isArray(words)) {
+                        blockedWords = Array.from(
+                            new Set(
+                                words
+                                    .filter((w): w is string => typeof w === 'string')
+                                    .map((w) => w.trim().toLowerCase())
+                                    .filter((w) => w.length > 0)
+                            )
+                        );
+                        broadcast(JSON.stringify({ messageType: 'blockedwords', data: JSON.stringify(blockedWords) }));
+                    }
+                    break;
+                }
+                case 'profile': {
+                    const user = users.find((u) => u.ws === ws);
+                    if (user) {
+                        user.bio = (parsed_data.data as string).slice(0, 160);
+                        broadcastUsers();
+                    }
+                    break;
+                }
+                case 'status': {
+                    const user = users.find((u) => u.ws === ws);
+                    if (user) {
+                        user.status = (parsed_data.data as string).slice(0, 60);
+                        broadcastUsers();
+                    }
+                    break;
+                }
+                case 'displayname': {
+                    const user = users.find((u) => u.ws === ws);
+                    if (user) {
+                        user.displayName = (parsed_data.data as string).trim().slice(0, 32);
+                        broadcastUsers();
+                    }
+                    break;
+                }
+                case 'avatar': {
+                    const user = users.find((u) => u.ws === ws);
+                    const avatar = parsed_data.data as string;
+                    // Uploaded avatars arrive as data: URLs; keep the users broadcast small.
+                    if (user && avatar.length <= MAX_AVATAR_LENGTH) {
+                        user.avatar = avatar;
+                        broadcastUsers();
+                    }
+                    break;
+                }
+                case 'pin': {
+                    const pinner = users.find((u) => u.ws === ws);
+                    if (pinner && canModerate(pinner)) {
+                        const id = parseInt(parsed_data.data as string);
+                        // An empty payload unpins.
+                        const previous = pinned;
+                        pinned = isNaN(id) ? null : history.find((m) => m.id === id && !m.to) || pinned;
+                        broadcast(JSON.stringify({ messageType: 'pin', data: pinned ? JSON.stringify(pinned) : '' }));
+                        if (pinned && pinned !== previous) {
+                            recordAudit('pin', pinner, pinned.from, pinned.message);
+                        } else if (!pinned && previous) {
+                            recordAudit('unpin', pinner, previous.from, previous.message);
+                        }
+                    }
+                    break;
+                }
+                case 'delete': {
+                    const deleter = users.find((u) => u.ws === ws);
+                    const id = parseInt(parsed_data.data as string);
+                    const target = history.find((m) => m.id === id);
+                    if (deleter && target && (target.from === deleter.nick || canModerate(deleter))) {
+                        history = history.filter((m) => m !== target);
+                        if (pinned && pinned.id === id) {
+                            pinned = null;
+                        }
+                        broadcast(JSON.stringify({ messageType: 'delete', data: String(id) }));
+                        // Authors tidying up their own messages aren't moderation.
+                        if (target.from !== deleter.nick) {
+                            recordAudit('delete', deleter, target.from, target.message);
+                        }
+                    }
+                    break;
+                }
+                case 'role': {
+                    const owner = users.find((u) => u.ws === ws);
+                    const { name, role } = JSON.parse(parsed_data.data as string);
+                    // Only owners hand out moderator rights, and ownership isn't transferable here.
+                    if (owner && ROLE_RANK[owner.role] >= ROLE_RANK.owner && (role === 'moderator' || role === 'member')) {
+                        users
+                            .filter((u) => u.nick === name && ROLE_RANK[u.role] < ROLE_RANK.owner)
+                            .forEach((u) => (u.role = role));
+                        broadcastUsers();
+                    }
+                    break;
+                }
+                case 'kick':
+                case 'ban': {
+                    const moderator = findActor(ws);
+                    const name = parsed_data.data as string;
+                    const targets = users.filter((u) => u.nick === name);
+                    // Moderators can only remove people ranked below them.
+                    if (
+                        !moderator ||
+                        !canModerate(moderator) ||
+                        targets.length === 0 ||
+                        targets.some((u) => ROLE_RANK[u.role] >= ROLE_RANK[moderator.role])
+                    ) {
+                        break;
+                    }
+                    const reason = parsed_data.messageType === 'ban' ? 'ban' : 'kick';
+                    if (reason === 'ban') {
+                        banned.add(name);
+                    }
+                    targets.forEach((u) => disconnect(u.ws, reason, moderator.nick as string));
+                    users = users.filter((u) => !targets.includes(u));
+                    lastSeen.set(name, Date.now());
+                    broadcastUsers();
+                    broadcastSystem(`${name} was ${reason === 'ban' ? 'banned' : 'kicked'} by ${moderator.nick}`);
+                    recordAudit(reason, moderator, name);
+                    sendStats();
+                    break;
+                }
+                case 'report': {
+                    const reporter = users.find((u) => u.ws === ws);
+                    const { messageId, reason } = JSON.parse(parsed_data.data as string);
+                    const reported = history.find((m) => m.id === messageId);
+                    if (reporter && reported) {
+                        console.log(`report from ${reporter.nick} on message ${messageId}: ${reason}`);
+                        const notice = JSON.stringify({
+                            messageType: 'system',
+                            data: `${reporter.nick} reported a message from ${reported.from} (${reason}): "${reported.message}"`,
+                        });
+                        users
+                            .filter((u) => canModerate(u) && u.ws.readyState === WebSocket.OPEN)
+                            .forEach((u) => u.ws.send(notice));
+                    }
+                    break;
+                }
+                case 'closepoll': {
+                    const closer = users.find((u) => u.ws === ws);
+                    const poll = polls.find((p) => p.id === parseInt(parsed_data.data as string));
+                    if (closer && poll && poll.creator === closer.nick) {
+                        poll.closed = true;
+                        broadcastPoll(poll);
+                        broadcastSystem(`${closer.nick} closed the poll "${poll.question}"`);
+                    }
+                    break;
+                }
             }
         } catch (e) {
             console.log('Error in message', e);
@@ -55,11 +712,451 @@ const interval = setInterval(function ping() {
     const current_clients = Array.from(wss.clients);
     const updated_users = users.filter((u) => current_clients.includes(u.ws));
     if (updated_users.length !== users.length) {
+        const now = Date.now();
+        users
+            .filter((u) => !updated_users.includes(u))
+            .forEach((u) => {
+                if (!updated_users.some((other) => other.nick === u.nick)) {
+                    lastSeen.set(u.nick as string, now);
+                }
+            });
         users = updated_users;
-        broadcast(JSON.stringify({ messageType: 'users', dataArray: users.map((u) => u.nick) }));
+        broadcastUsers();
     }
+    observers = observers.filter((o) => current_clients.includes(o.ws));
+    sessions.forEach((session, token) => {
+        if (session.expiresAt < Date.now()) {
+            sessions.delete(token);
+        }
+    });
+    accountTokens.forEach((signedIn, token) => {
+        if (signedIn.expiresAt < Date.now()) {
+            accountTokens.delete(token);
+        }
+    });
+    sendStats();
 }, 5000);
 
+const broadcastUsers = () => {
+    broadcast(
+        JSON.stringify({
+            messageType: 'users',
+            dataArray: users.map((u) => u.nick),
+            // Offline users carry only their name and `lastSeen`.
+            data: JSON.stringify([
+                ...users.map((u) => ({ name: u.nick, bio: u.bio, status: u.status, avatar: u.avatar, displayName: u.displayName, role: u.role, joinedAt: u.joinedAt })),
+                ...Array.from(lastSeen, ([name, seen]) => ({ name, lastSeen: seen })),
+            ]),
+        })
+    );
+};
+
+// Adds the connection to the room once any CAPTCHA has been checked.
+const register = (ws: WebSocket, parsed_data: Message) => {
+    if (banned.has(parsed_data.data as string)) {
+        disconnect(ws, 'ban');
+        return;
+    }
+    const wanted = (parsed_data.data as string).toLowerCase();
+    // A client coming back from another page hangs up first; don't hold its name.
+    users = users.filter((u) => u.nick.toLowerCase() !== wanted || u.ws.readyState === WebSocket.OPEN);
+    if (users.some((u) => u.nick.toLowerCase() === wanted)) {
+        ws.send(JSON.stringify({ messageType: 'error', data: 'name_taken' }));
+        ws.close();
+        return;
+    }
+    // An avatar picked at login comes along in `dataArray`, and after the CAPTCHA
+    // token, the account token of a password sign-in.
+    const initialAvatar = parsed_data.dataArray && parsed_data.dataArray[0];
+    const account = signedInAs(parsed_data.dataArray && parsed_data.dataArray[2]);
+    const isAdmin = isAdminAccount(account, parsed_data.data);
+    users.push({
+        ws,
+        nick: parsed_data.data,
+        isAlive: true,
+        bio: '',
+        status: '',
+        avatar:
+            typeof initialAvatar === 'string' && initialAvatar.length <= MAX_AVATAR_LENGTH
+                ? initialAvatar
+                : '',
+        displayName: '',
+        // Whoever opens an empty room owns it.
+        role: isAdmin ? 'admin' : users.length === 0 ? 'owner' : 'member',
+        joinedAt: Date.now(),
+        lastSentAt: 0,
+        sessionToken: '',
+        sessionExpiresAt: 0,
+        account,
+    });
+    startSession(users[users.length - 1]);
+    lastSeen.delete(parsed_data.data as string);
+    broadcastUsers();
+    ws.send(JSON.stringify({ messageType: 'ephemeral', data: String(ephemeralSeconds) }));
+    ws.send(JSON.stringify({ messageType: 'slowmode', data: String(slowModeSeconds) }));
+    ws.send(JSON.stringify({ messageType: 'blockedwords', data: JSON.stringify(blockedWords) }));
+    if (pinned) {
+        ws.send(JSON.stringify({ messageType: 'pin', data: JSON.stringify(pinned) }));
+    }
+    if (isAdmin) {
+        auditLog.forEach((event) => ws.send(JSON.stringify({ messageType: 'auditevent', data: JSON.stringify(event) })));
+    }
+};
+
+// Checks a CAPTCHA token with the provider. Without `CAPTCHA_SECRET` every
+// registration is let through.
+const verifyCaptcha = (token: unknown): Promise<boolean> => {
+    if (!CAPTCHA_SECRET) {
+        return Promise.resolve(true);
+    }
+    if (typeof token !== 'string' || token.length === 0) {
+        return Promise.resolve(false);
+    }
+    const body = new URLSearchParams({ secret: CAPTCHA_SECRET, response: token }).toString();
+    return new Promise((resolve) => {
+        const req = https.request(
+            CAPTCHA_VERIFY_URL,
+            { method: 'POST', headers: { 'Content-Type': 'application/x-www-form-urlencoded' } },
+            (res) => {
+                let raw = '';
+                res.on('data', (chunk) => (raw += chunk));
+                res.on('end', () => {
+                    try {
+                        resolve(JSON.parse(raw).success === true);
+                    } catch (e) {
+                        resolve(false);
+                    }
+                });
+            }
+        );
+        req.on('error', (e) => {
+            console.log('CAPTCHA verification failed', e);
+            resolve(false);
+        });
+        req.end(body);
+    });
+};
+
+const hashPassword = (password: string, salt: string) => scryptSync(password, salt, 64).toString('hex');
+
+// The account for `email` if `password` matches it.
+const checkCredentials = (email: unknown, password: unknown): Account | undefined => {
+    if (typeof email !== 'string' || typeof password !== 'string') {
+        return undefined;
+    }
+    const account = accounts.get(email.toLowerCase());
+    if (!account) {
+        return undefined;
+    }
+    const expected = Buffer.from(account.passwordHash, 'hex');
+    const actual = Buffer.from(hashPassword(password, account.salt), 'hex');
+    return timingSafeEqual(expected, actual) ? account : undefined;
+};
+
+const BASE32_ALPHABET = 'ABCDEFGHIJKLMNOPQRSTUVWXYZ234567';
+
+const base32 = (bytes: Buffer) => {
+    let bits = '';
+    bytes.forEach((b) => (bits += b.toString(2).padStart(8, '0')));
+    let out = '';
+    for (let i = 0; i < bits.length; i += 5) {
+        out += BASE32_ALPHABET[parseInt(bits.slice(i, i + 5).padEnd(5, '0'), 2)];
+    }
+    return out;
+};
+
+const fromBase32 = (text: string) => {
+    let bits = '';
+    text.toUpperCase().replace(/=+$/, '').split('').forEach((c) => (bits += BASE32_ALPHABET.indexOf(c).toString(2).padStart(5, '0')));
+    const bytes: number[] = [];
+    for (let i = 0; i + 8 <= bits.length; i += 8) {
+        bytes.push(parseInt(bits.slice(i, i + 8), 2));
+    }
+    return Buffer.from(bytes);
+};
+
+// RFC 6238 code for the 30-second step `counter`.
+const totpCode = (secret: string, counter: number) => {
+    const message = Buffer.alloc(8);
+    message.writeBigUInt64BE(BigInt(counter));
+    const hmac = createHmac('sha1', fromBase32(secret)).update(message).digest();
+    const offset = hmac[hmac.length - 1] & 0x0f;
+    const value = (hmac.readUInt32BE(offset) & 0x7fffffff) % 1000000;
+    return value.toString().padStart(6, '0');
+};
+
+// Accepts the current code and its neighbours, to allow for clock drift.
+const checkTotp = (secret: string, code: unknown) => {
+    if (typeof code !== 'string' || !/^\d{6}$/.test(code)) {
+        return false;
+    }
+    const step = Math.floor(Date.now() / 30000);
+    return [-1, 0, 1].some((drift) => totpCode(secret, step + drift) === code);
+};
+
+// Checks a code from the account's current authenticator, refusing any for a
+// while after too many wrong ones in a row.
+const checkAccountCode = (account: Account, code: unknown) => {
+    if (account.codesLockedUntil > Date.now() || !account.totpSecret) {
+        return false;
+    }
+    if (checkTotp(account.totpSecret, code)) {
+        account.failedCodes = 0;
+        return true;
+    }
+    account.failedCodes++;
+    if (account.failedCodes >= MAX_TOTP_ATTEMPTS) {
+        account.failedCodes = 0;
+        account.codesLockedUntil = Date.now() + TOTP_CHALLENGE_TTL_MS;
+    }
+    return false;
+};
+
+const findAccountByName = (username: string) =>
+    Array.from(accounts.values()).find((a) => a.username.toLowerCase() === username.toLowerCase());
+
+// Tells a client it signed in to `account`, with a token to prove it when joining.
+const sendAccount = (ws: WebSocket, account: Account) => {
+    const token = randomUUID();
+    accountTokens.set(token, { username: account.username, expiresAt: Date.now() + ACCOUNT_TOKEN_TTL_MS });
+    ws.send(JSON.stringify({ messageType: 'account', data: JSON.stringify({ username: account.username, token }) }));
+};
+
+// The username of the account `token` was issued for, if it is still good.
+const signedInAs = (token: unknown): string | undefined => {
+    const signedIn = typeof token === 'string' ? accountTokens.get(token) : undefined;
+    return signedIn && signedIn.expiresAt > Date.now() ? signedIn.username : undefined;
+};
+
+// Whether `username`, proven by a password sign-in, is an admin joining under its own name.
+const isAdminAccount = (username: string | undefined, nick: unknown) =>
+    username !== undefined &&
+    ADMINS.has(username) &&
+    typeof nick === 'string' &&
+    nick.toLowerCase() === username.toLowerCase();
+
+// Issues a fresh session to `user` and tells its client when it runs out.
+const startSession = (user: User) => {
+    user.sessionToken = randomUUID();
+    user.sessionExpiresAt = Date.now() + SESSION_TTL_MS;
+    sessions.set(user.sessionToken, { nick: user.nick as string, expiresAt: user.sessionExpiresAt, user });
+    user.ws.send(
+        JSON.stringify({
+            messageType: 'session',
+            data: JSON.stringify({ token: user.sessionToken, expiresAt: user.sessionExpiresAt }),
+        })
+    );
+};
+
+// The user or admin dashboard behind a socket.
+const findActor = (ws: WebSocket) => users.find((u) => u.ws === ws) || observers.find((o) => o.ws === ws);
+
+// Pushes connection and activity figures to open admin dashboards.
+const sendStats = () => {
+    if (observers.length === 0) {
+        return;
+    }
+    const hourAgo = Date.now() - 60 * 60 * 1000;
+    const payload = JSON.stringify({
+        messageType: 'stats',
+        data: JSON.stringify({
+            connected: wss.clients.size,
+            rooms: [
+                {
+                    name: ROOM,
+                    online: users.length,
+                    messagesLastHour: history.filter((m) => m.time >= hourAgo).length,
+                    lastMessageAt: history.length > 0 ? history[history.length - 1].time : undefined,
+                },
+            ],
+            users: users.map((u) => ({ name: u.nick, role: u.role })),
+            banned: Array.from(banned),
+        }),
+    });
+    observers.filter((o) => o.ws.readyState === WebSocket.OPEN).forEach((o) => o.ws.send(payload));
+};
+
+const canModerate = (user: User) => ROLE_RANK[user.role] >= ROLE_RANK.moderator;
+
+// Keeps a moderation event and forwards it to every connected admin.
+const recordAudit = (action: AuditAction, actor: User, target?: string, detail?: string) => {
+    const event: AuditEvent = { action, actor: actor.nick as string, target, detail, time: Date.now() };
+    auditLog = [...auditLog, event].slice(-MAX_AUDIT_LOG);
+    const payload = JSON.stringify({ messageType: 'auditevent', data: JSON.stringify(event) });
+    [...users, ...observers]
+        .filter((u) => u.role === 'admin' && u.ws.readyState === WebSocket.OPEN)
+        .forEach((u) => u.ws.send(payload));
+};
+
+// Enforces slow mode; moderators are exempt. Records the post time when allowed.
+const allowSend = (user: User) => {
+    const now = Date.now();
+    if (slowModeSeconds > 0 && !canModerate(user) && now - user.lastSentAt < slowModeSeconds * 1000) {
+        return false;
+    }
+    user.lastSentAt = now;
+    return true;
+};
+
+// Stamps a chat message with an id and expiry, records it, and delivers it to
+// `recipients` (a direct message) or everyone.
+const sendChatMessage = (
+    message: Omit<ChatMessage, 'id' | 'time' | 'expiresAt'>,
+    recipients?: User[]
+) => {
+    const now = Date.now();
+    const stamped: ChatMessage = {
+        ...message,
+        id: nextMessageId++,
+        time: now,
+        expiresAt: ephemeralSeconds > 0 ? now + ephemeralSeconds * 1000 : undefined,
+    };
+    history = [...history, stamped].slice(-MAX_HISTORY);
+
+    const payload = JSON.stringify({ messageType: 'message', data: JSON.stringify(stamped) });
+    if (recipients) {
+        new Set(recipients.map((u) => u.ws)).forEach((client) => {
+            if (client.readyState === WebSocket.OPEN) {
+                client.send(payload);
+            }
+        });
+    } else {
+        broadcast(payload);
+    }
+    pushToOffline(stamped);
+};
+
+// Sends mentions and direct messages to the push subscriptions of users who
+// have no tab open.
+const pushToOffline = (message: ChatMessage) => {
+    if (!VAPID_PRIVATE_KEY) {
+        return;
+    }
+    pushSubscriptions.forEach((subscriptions, nick) => {
+        if (nick === message.from || users.some((u) => u.nick === nick)) {
+            return;
+        }
+        const direct = message.to === nick;
+        const mentioned = !message.to && message.message.toLowerCase().includes(`@${nick.toLowerCase()}`);
+        if (!direct && !mentioned) {
+            return;
+        }
+        const payload = JSON.stringify({
+            id: message.id,
+            from: message.from,
+            // Push services cap payloads at around 4KB.
+            message: message.message.slice(0, 500),
+            direct,
+            url: `/chat/${ROOM}#msg-${message.id}`,
+        });
+        subscriptions.forEach((subscription) => sendPush(nick, subscription, payload));
+    });
+};
+
+const sendPush = (nick: string, subscription: PushSubscription, payload: string) => {
+    let body: Buffer;
+    try {
+        body = encryptPush(subscription, payload);
+    } catch (e) {
+        console.log('Dropping a broken push subscription', e);
+        forgetPush(nick, subscription.endpoint);
+        return;
+    }
+    const req = https.request(
+        subscription.endpoint,
+        {
+            method: 'POST',
+            headers: {
+                Authorization: vapidAuthorization(subscription.endpoint),
+                'Content-Encoding': 'aes128gcm',
+                'Content-Type': 'application/octet-stream',
+                'Content-Length': body.length,
+                TTL: 24 * 60 * 60,
+                Urgency: 'high',
+            },
+        },
+        (res) => {
+            // The browser unsubscribed, or the user took back permission.
+            if (res.statusCode === 404 || res.statusCode === 410) {
+                forgetPush(nick, subscription.endpoint);
+            }
+            res.resume();
+        }
+    );
+    req.on('error', (e) => console.log('Web Push failed', e));
+    req.end(body);
+};
+
+const forgetPush = (nick: string, endpoint: string) => {
+    const remaining = (pushSubscriptions.get(nick) || []).filter((s) => s.endpoint !== endpoint);
+    if (remaining.length > 0) {
+        pushSubscriptions.set(nick, remaining);
+    } else {
+        pushSubscriptions.delete(nick);
+    }
+};
+
+// RFC 8291: `payload` encrypted for one subscription, as a single aes128gcm record.
+const encryptPush = (subscription: PushSubscription, payload: string) => {
+    const clientKey = Buffer.from(subscription.keys.p256dh, 'base64url');
+    const authSecret = Buffer.from(subscription.keys.auth, 'base64url');
+    const ecdh = createECDH('prime256v1');
+    const serverKey = ecdh.generateKeys();
+    const keyInfo = Buffer.concat([Buffer.from('WebPush: info\0'), clientKey, serverKey]);
+    const ikm = Buffer.from(hkdfSync('sha256', ecdh.computeSecret(clientKey), authSecret, keyInfo, 32));
+    const salt = randomBytes(16);
+    const key = Buffer.from(hkdfSync('sha256', ikm, salt, Buffer.from('Content-Encoding: aes128gcm\0'), 16));
+    const nonce = Buffer.from(hkdfSync('sha256', ikm, salt, Buffer.from('Content-Encoding: nonce\0'), 12));
+    const cipher = createCipheriv('aes-128-gcm', key, nonce);
+    // The 2 marks the last (and only) record.
+    const plaintext = Buffer.concat([Buffer.from(payload), Buffer.from([2])]);
+    const ciphertext = Buffer.concat([cipher.update(plaintext), cipher.final(), cipher.getAuthTag()]);
+    const header = Buffer.alloc(21);
+    salt.copy(header);
+    header.writeUInt32BE(4096, 16);
+    header.writeUInt8(serverKey.length, 20);
+    return Buffer.concat([header, serverKey, ciphertext]);
+};
+
+// RFC 8292: a signed token telling the push service which server is sending.
+const vapidAuthorization = (endpoint: string) => {
+    const publicKey = Buffer.from(VAPID_PUBLIC_KEY, 'base64url');
+    const key = createPrivateKey({
+        key: {
+            kty: 'EC',
+            crv: 'P-256',
+            d: VAPID_PRIVATE_KEY,
+            x: publicKey.subarray(1, 33).toString('base64url'),
+            y: publicKey.subarray(33, 65).toString('base64url'),
+        },
+        format: 'jwk',
+    });
+    const encode = (part: object) => Buffer.from(JSON.stringify(part)).toString('base64url');
+    const unsigned = `${encode({ typ: 'JWT', alg: 'ES256' })}.${encode({
+        aud: new URL(endpoint).origin,
+        exp: Math.floor(Date.now() / 1000) + 12 * 60 * 60,
+        sub: VAPID_SUBJECT,
+    })}`;
+    const signature = sign('sha256', Buffer.from(unsigned), { key, dsaEncoding: 'ieee-p1363' });
+    return `vapid t=${unsigned}.${signature.toString('base64url')}, k=${VAPID_PUBLIC_KEY}`;
+};
+
+// Tells a client why it is being removed, then closes its connection.
+const disconnect = (ws: WebSocket, reason: 'kick' | 'ban', by?: string) => {
+    if (ws.readyState === WebSocket.OPEN) {
+        ws.send(JSON.stringify({ messageType: 'disconnect', data: JSON.stringify({ reason, by }) }));
+    }
+    ws.close();
+};
+
+const broadcastSystem = (text: string) => {
+    broadcast(JSON.stringify({ messageType: 'system', data: text }));
+};
+
+const broadcastPoll = (poll: Poll) => {
+    broadcast(JSON.stringify({ messageType: 'poll', data: JSON.stringify(poll) }));
+};
+
 const broadcast = (data: any) => {
     wss.clients.forEach((client) => {
         if (client.readyState === WebSocket.OPEN) {
@@ -0,0 +1,496 @@
+# YewChat UI strings — English.
+# Keys are shared across locales; see src/services/i18n.rs for the syntax.
+
+## User sidebar
+sidebar-title = Online Users
+sidebar-contacts = Contacts
+sidebar-no-contacts = Star someone below to add them here.
+sidebar-empty = No users online at the moment
+sidebar-loading = Loading people…
+sidebar-filter = Filter people
+sidebar-no-matches = No one online matches “{ $query }”
+sidebar-sort = Sort by
+sort-name = Name
+sort-joined = Join time
+sort-contacts = Contacts and moderators first
+sidebar-blocked = Blocked
+sidebar-unblock = Unblock
+sidebar-online = Online
+sidebar-add-contact = Add to contacts
+sidebar-remove-contact = Remove from contacts
+sidebar-block = Block user
+sidebar-direct = Send a direct message
+sidebar-forget-contact = Remove contact
+sidebar-muted = (muted)
+sidebar-label = Online users
+sidebar-resize = Resize the sidebar
+
+## Message list
+messages-joining = Joining the room…
+messages-connecting = Connecting…
+messages-loading-history = Loading earlier messages…
+messages-empty = No messages yet. Start the conversation!
+messages-unpin = Unpin
+messages-label = Messages
+
+## Message composer
+composer-direct-to = Direct message to { $name }
+composer-back-to-group = Back to the group
+composer-placeholder = Type your message here...
+composer-label = Message
+composer-slow-mode = Slow mode is on
+composer-slow-mode-placeholder = Slow mode is on...
+composer-share-location = Share location
+composer-send = Send
+messages-pinned = 📌 Pinned
+message-from = Message from { $name }
+message-own = Your message
+
+## Message bubbles
+message-link-title = Link to this message
+message-actions = Message actions
+message-reply = Reply
+message-copy = Copy text
+message-copy-image = Copy image address
+message-copy-location = Copy map link
+message-share = Share…
+message-pin = Pin
+message-delete = Delete
+message-report = Report
+message-translate = Translate
+message-show-original = Show original
+message-translating = Translating…
+message-translated = Translated
+message-translated-from = Translated from { $language }
+message-muted = Muted message — click to show
+message-open-location = 📍 Open shared location
+message-location-alt = Shared location
+message-open-gif = GIF — click to open
+message-direct = → { $name } (direct)
+message-disappears = Disappears in { $time }
+poll-votes-one = { $count } vote
+poll-votes-other = { $count } votes
+poll-closed = closed
+poll-close = Close poll
+
+## Chat header
+header-title = Group Chat
+header-participants-one = { $count } participant
+header-participants-other = { $count } participants
+header-connecting = Connecting…
+header-offline = Offline
+header-signed-in-as = { $presence } · signed in as { $name } · 
+header-not-you = Not you?
+header-toggle-sidebar = Toggle user list
+header-disappearing = Disappearing messages
+header-ephemeral-off = Messages kept
+header-ephemeral-30s = Disappear after 30s
+header-ephemeral-5m = Disappear after 5m
+header-ephemeral-1h = Disappear after 1h
+header-ephemeral-1d = Disappear after 1d
+header-slow-mode = Slow mode
+header-slow-mode-off = Slow mode off
+header-slow-mode-5s = Slow mode 5s
+header-slow-mode-10s = Slow mode 10s
+header-slow-mode-30s = Slow mode 30s
+header-slow-mode-1m = Slow mode 1m
+header-moderation-log = Moderation log
+header-read-aloud = Read messages aloud
+header-stop-reading = Stop reading messages aloud
+header-light-mode = Switch to light mode
+header-dark-mode = Switch to dark mode
+header-settings = Settings
+header-install = Install YewChat
+header-shortcuts = Keyboard shortcuts
+header-speech-rate = Speech rate ({ $rate }x)
+header-voice = Voice
+header-default-voice = Browser default
+header-filter-profanity = Filter profanity
+header-add-word = Add a word
+header-add = Add
+header-remove-word = Remove word
+header-blocked-words = Room blocked words
+header-blocked-words-hint = Masked for everyone in the room.
+header-block-word = Block a word
+header-account-settings = Account settings
+
+## Usernames and accounts
+username-spaces = Remove the spaces at the start or end.
+username-too-short = Use at least { $count } characters.
+username-too-long = Use at most { $count } characters.
+username-bad-character = "{ $character }" isn't allowed; use letters, numbers, spaces, _, - and .
+username-reserved = That name is reserved.
+account-invalid-email = Enter a valid email address.
+account-email-taken = An account with this email already exists.
+account-name-taken = That username is already taken.
+account-invalid = Some of the details weren't accepted; please check them.
+account-bad-credentials = That email and password don't match an account.
+account-bad-code = That code isn't right; check your authenticator app and try again.
+account-challenge-expired = The sign-in took too long; please start again.
+account-code-required = Enter a code from your current authenticator app to replace it.
+account-unknown-error = Something went wrong ({ $code }).
+account-unexpected-reply = Unexpected reply from the server: { $error }
+account-enter-code = Enter the 6-digit code from your authenticator app.
+reset-not-configured = Password resets aren't set up for this deployment.
+reset-unreachable = Couldn't reach the reset service: { $error }
+reset-link-invalid = This reset link is invalid or has expired.
+reset-service-failed = The reset service failed ({ $status }).
+password-too-short = Use at least { $count } characters.
+
+## Sign in and accounts
+field-email = Email
+field-username = Username
+field-password = Password
+field-confirm-password = Confirm password
+field-code = Authentication code
+login-welcome = Welcome to YewChat
+login-pick-look = Or pick a look:
+login-go = Go Chatting!
+login-new-here = New here?
+login-create-account = Create an account
+login-or-account = or sign in to your account
+login-forgot-password = Forgot password?
+login-or = or
+login-continue-with = Continue with { $provider }
+sign-in-enter-code = Enter the 6-digit code from your authenticator app.
+sign-in-submitting = Signing in…
+sign-in-verify = Verify
+sign-in = Sign in
+create-account-title = Create an account
+create-account-mismatch = The passwords don't match.
+create-account-submitting = Creating account…
+create-account-submit = Create account
+create-account-have-name = Already have a name?
+create-account-back = Back to login
+
+## Error pages
+not-found-title = This page wandered off
+not-found-body = The link might be broken, or the page may have moved.
+not-found-back = Back to the chat
+error-title = Something went wrong
+error-body = This page ran into a problem. Reloading usually fixes it.
+error-reload = Reload
+error-retry = Try again
+
+## Avatars
+avatar-not-image = Please choose an image file
+avatar-too-large = Images must be smaller than { $size } KB
+avatar-unreadable = Couldn't read that image: { $error }
+avatar-upload = Upload image
+avatar-use-generated = Use generated
+avatar-url = …or paste an image URL
+
+## Password reset
+reset-forgot-title = Forgot password
+reset-forgot-body = Enter the email you signed up with and we'll send you a reset link.
+reset-sending = Sending…
+reset-send = Send reset link
+reset-sent-title = Check your inbox
+reset-sent-body = If { $email } belongs to an account, a link to reset the password is on its way.
+reset-title = Reset password
+reset-missing-token = This link is missing its reset token.
+reset-request-new = Request a new one
+reset-done-title = Password updated
+reset-done-body = Your password has been changed. You can sign in with it now.
+reset-choose-title = Choose a new password
+field-new-password = New password
+reset-saving = Saving…
+reset-set-password = Set password
+
+## Sign-in providers
+oauth-failed = Sign-in failed
+oauth-signing-in = Signing you in…
+
+## Profiles
+profile-unknown-user = Nobody called "{ $name }" has been here since the server started.
+profile-loading = Loading profile…
+profile-online-joined = Online · joined { $when }
+profile-last-seen = Last seen { $when }
+profile-offline = Offline
+profile-about = About
+profile-no-bio = No bio yet.
+profile-shared-rooms = Rooms you share
+profile-no-shared-rooms = None right now.
+profile-message = Send a direct message
+profile-cant-message = Offline — can't message right now
+profile-back = ← Back to chat
+
+## Times
+time-just-now = just now
+time-minutes-ago = { $count } min ago
+time-hours-ago = { $count } h ago
+time-days-ago = { $count } d ago
+time-today = Today
+time-yesterday = Yesterday
+time-today-at = today at { $time }
+time-yesterday-at = yesterday at { $time }
+time-date-at = { $date } at { $time }
+clock-auto = Language default
+clock-12h = 12-hour
+clock-24h = 24-hour
+
+## Appearance
+theme-system = Match system
+theme-light = Light
+theme-dark = Dark
+theme-high-contrast = High contrast
+accent-blue = Blue
+accent-purple = Purple
+accent-green = Green
+accent-teal = Teal
+accent-rose = Rose
+accent-amber = Amber
+bubbles-rounded = Rounded
+bubbles-soft = Extra round
+bubbles-square = Square
+motion-system = Match system
+motion-reduced = Reduced
+motion-full = Full
+font-small = Small
+font-medium = Medium
+font-large = Large
+density-cozy = Cozy
+density-compact = Compact
+
+## Experimental features
+feature-reactions = Message reactions
+feature-threads = Threaded replies
+feature-e2e = End-to-end encrypted direct messages
+
+## Settings
+settings-title = Settings
+settings-back = Back to chat
+settings-preferences = Preferences
+settings-language = Language
+settings-theme = Theme
+settings-accent = Accent colour
+settings-bubbles = Message bubbles
+settings-font-size = Text size
+settings-density = Message spacing
+settings-motion = Animations
+settings-clock = Clock
+settings-time-zone = Time zone
+settings-time-zone-device = This device
+settings-time-zone-device-named = This device ({ $zone })
+settings-translate-to = Translate messages into
+settings-translate-to-interface = The interface language
+settings-notifications = Notify me about new messages while the tab is in the background
+settings-notifications-blocked = Your browser is blocking notifications from this site; allow them in its site settings.
+settings-push = Notify me about mentions and direct messages while YewChat is closed
+settings-push-on = This browser is subscribed.
+settings-push-off = This browser is not subscribed.
+settings-push-unsupported = This browser can't receive push notifications.
+settings-push-failed = Couldn't change the subscription. Try again later.
+settings-sound-messages = Play a sound for new messages
+settings-sound-mentions = Play a sound when someone mentions me
+settings-sound-direct = Play a sound for direct messages
+settings-do-not-disturb = Do not disturb: no notifications or sounds
+settings-quiet-hours = Do not disturb every day during quiet hours
+settings-quiet-from = From
+settings-quiet-until = Until
+settings-autoload = Load GIFs and map previews automatically
+settings-announce-mentions-only = Only announce messages that mention me to screen readers
+settings-warn-unsent = Ask before leaving with an unsent message
+settings-telemetry = Send anonymous usage statistics, like which features get used, to help improve YewChat
+settings-profile = Profile
+settings-profile-hint = Shared with the room whenever you join.
+settings-display-name = Display name
+settings-status = What's your status?
+settings-bio = Write something about yourself
+settings-two-factor = Two-factor authentication
+settings-install = App
+settings-install-hint = Install YewChat to open it from your home screen or dock in its own window, even without a network.
+settings-install-button = Install YewChat
+settings-experimental = Experimental features
+settings-experimental-hint = Still in the works and may change or go away. Applies to this browser only.
+settings-on-by-default = on by default here
+settings-debug = Logs and bug reports
+two-factor-on = Two-factor authentication is on. You'll be asked for a code each time you sign in with your password.
+two-factor-scan = Scan this code with your authenticator app, or enter the secret by hand.
+two-factor-qr-alt = Authenticator QR code
+two-factor-code = 6-digit code
+two-factor-current-code = 6-digit code from your current app
+two-factor-confirm = Confirm your account to set up an authenticator app.
+two-factor-turn-on = Turn on
+two-factor-continue = Continue
+
+## Chat
+role-admin = Admin
+role-owner = Owner
+role-moderator = Mod
+report-spam = Spam
+report-harassment = Harassment or abuse
+report-inappropriate = Inappropriate content
+report-other = Something else
+audit-deleted = deleted a message from
+audit-kicked = kicked
+audit-banned = banned
+audit-pinned = pinned a message from
+audit-unpinned = unpinned a message from
+chat-last-seen = Last seen { $when }
+chat-offline = Offline
+chat-offline-banner = You're offline. Showing the messages saved on this device.
+chat-offline-retry = Try again
+chat-outbox-one = { $count } message waiting for the network
+chat-outbox-other = { $count } messages waiting for the network
+chat-outbox-to = To { $name }:
+chat-anchor-missing = That message is no longer available.
+chat-reported = Thanks — the moderators have been notified.
+chat-copied = Copied to the clipboard.
+chat-send-failed = Couldn't send that. Check your connection and try again.
+chat-copy-failed = Couldn’t copy — the browser blocked the clipboard.
+chat-link-copied = Link copied to the clipboard.
+chat-share-failed = Couldn’t share that message.
+chat-speak-gif = { $name } sent a GIF
+chat-speak-message = { $name } says { $text }
+chat-announce-message = { $name } says: { $text }
+chat-name-taken = The username "{ $name }" is already in use. Please pick another one.
+chat-captcha-failed = Please complete the CAPTCHA before joining.
+chat-blocked-words = This room doesn't allow: { $words }. Others will see them masked — send again to post anyway.
+reauth-title = Your session has expired
+reauth-body = Sign in again to keep chatting. Your unsent message will be kept.
+reauth-continue = Continue as { $name }
+reauth-other-name = Use a different name
+reauth-failed = Your sign-in has run out. Please sign in with your password again.
+menu-kick = Kick
+menu-ban = Ban
+report-title = Report message
+report-body = Moderators will review it. What's wrong with this message?
+report-cancel = Cancel
+dialog-close = Close
+dialog-cancel = Cancel
+confirm-delete-title = Delete this message?
+confirm-delete-body = It's removed for everyone in the room. This can't be undone.
+confirm-delete = Delete
+confirm-ban-title = Ban { $name }?
+confirm-ban-body = They're removed from the room and can't join again with that name.
+confirm-ban = Ban
+confirm-leave-title = Leave without sending?
+confirm-leave-body = What you've typed or picked here hasn't been sent or saved and will be lost.
+confirm-leave = Leave
+notify-prompt-title = Know when someone answers
+notify-prompt-body = Get a desktop notification for new messages while YewChat is in the background.
+notify-prompt-not-now = Not now
+notify-prompt-allow = Turn on
+audit-title = Moderation log
+audit-all-moderators = All moderators
+audit-any-time = Any time
+audit-last-hour = Last hour
+audit-last-day = Last 24 hours
+audit-last-week = Last 7 days
+audit-empty = No moderation events.
+removed-kick-title = You were removed from the chat
+removed-kick-body = A moderator kicked you out of the room. You can sign in again to rejoin.
+removed-ban-title = You have been banned
+removed-ban-body = A moderator banned this username from the room, so it can't rejoin.
+removed-by = Removed by { $name }
+removed-back = Back to sign in
+user-joined-at = Joined at { $time }
+user-generated-avatars = Generated avatars
+user-save-profile = Save profile
+user-no-bio = No bio yet.
+user-message = Message
+user-mute = Mute
+user-unmute = Unmute
+user-block = Block
+user-make-moderator = Make moderator
+user-remove-moderator = Remove moderator
+poll-usage = Usage: /poll "Question" option1 option2
+poll-too-few-options = A poll needs at least two options
+presence-joined = { $name } joined
+presence-left = { $name } left
+
+## Admin dashboard
+admin-connections = Connections
+admin-users-in-rooms = Users in rooms
+admin-messages-last-hour = Messages in the last hour
+admin-rooms = Rooms
+admin-room-stats = { $online } online · { $rate } messages/h
+admin-room-last-message = last message { $when }
+admin-room-no-messages = no messages yet
+admin-connected-users = Connected users
+admin-nobody-chatting = Nobody is chatting right now.
+admin-kick = Kick
+admin-ban = Ban
+admin-banned = Banned
+admin-no-banned = No banned users.
+admin-unban = Unban
+admin-title = Admin dashboard
+admin-back = Back to chat
+admin-access-denied = Access denied
+admin-connecting = Connecting…
+
+## Sign-in providers
+oauth-malformed-callback = Malformed callback URL.
+oauth-refused = The provider refused the login ({ $error }).
+oauth-no-code = The provider didn't send a login code.
+oauth-not-started = This login wasn't started here; please try again.
+oauth-state-mismatch = The login response didn't match the request; please try again.
+oauth-no-endpoint = No token endpoint is configured for this deployment.
+oauth-unreachable = Couldn't reach the login server: { $error }
+oauth-rejected = The login server rejected the code ({ $status }).
+oauth-unexpected = The login server sent an unexpected response: { $error }
+
+## Location
+location-unsupported = Location sharing is not supported by this browser
+location-failed-reason = Couldn't get your location: { $error }
+location-failed = Couldn't get your location
+
+## Server
+server-unreachable = Couldn't reach the server: { $error }
+server-unexpected-reply = Unexpected reply from the server: { $error }
+server-closed = The server closed the connection.
+
+## Onboarding
+onboarding-welcome = Welcome to the room!
+    Press Enter to send a message, or start a poll with /poll "Question" option1 option2.
+    Click an avatar to see someone's profile; star people to keep them in your contacts.
+    Be kind — everyone here is a person too.
+
+## Translation
+translate-not-configured = Translation isn't set up for this deployment.
+translate-unreachable = Couldn't reach the translation service: { $error }
+translate-failed = The translation service failed ({ $status }).
+translate-unexpected = The translation service sent an unexpected response: { $error }
+
+## Keyboard shortcuts
+shortcuts-title = Keyboard shortcuts
+shortcuts-close = Close
+shortcut-area-general = General
+shortcut-area-rooms = Rooms
+shortcut-area-people = People
+shortcut-search = Search people
+shortcut-previous-room = Previous room
+shortcut-next-room = Next room
+shortcut-close = Close the newest menu or panel, then clear the message
+shortcut-help = Show keyboard shortcuts
+shortcut-palette = Find a room, person or action
+
+## Command palette
+palette-title = Command palette
+palette-placeholder = Type a room, person or action…
+palette-no-matches = Nothing matches
+palette-room = Room
+palette-message = Direct message
+palette-profile = Profile
+palette-action = Action
+palette-alerts-all = Notify me of every message in #{ $room }
+palette-alerts-mentions = Only notify me of mentions in #{ $room }
+palette-alerts-muted = Mute #{ $room }
+palette-open-settings = Open settings
+
+## Toasts
+toast-dismiss = Dismiss
+
+## Debug panel
+debug-title = Debug
+debug-back = Back to chat
+debug-log = Log
+debug-level = Level
+debug-refresh = Refresh
+debug-level-hint = The level is remembered in this browser. Add ?log=trace to the address to change it for one visit.
+debug-log-empty = Nothing logged yet.
+debug-report = Bug report
+debug-report-hint = Collects the app version, your browser, recent chat traffic and the log above to paste into a bug report.
+debug-prepare-report = Prepare report
+debug-copy = Copy
@@ -0,0 +1,496 @@
+# YewChat UI strings — Spanish.
+# Keys are shared across locales; see src/services/i18n.rs for the syntax.
+
+## User sidebar
+sidebar-title = Usuarios conectados
+sidebar-contacts = Contactos
+sidebar-no-contacts = Marca a alguien con una estrella para añadirlo aquí.
+sidebar-empty = No hay nadie conectado en este momento
+sidebar-loading = Cargando personas…
+sidebar-filter = Filtrar personas
+sidebar-no-matches = Nadie conectado coincide con «{ $query }»
+sidebar-sort = Ordenar por
+sort-name = Nombre
+sort-joined = Hora de llegada
+sort-contacts = Contactos y moderadores primero
+sidebar-blocked = Bloqueados
+sidebar-unblock = Desbloquear
+sidebar-online = Conectado
+sidebar-add-contact = Añadir a contactos
+sidebar-remove-contact = Quitar de contactos
+sidebar-block = Bloquear usuario
+sidebar-direct = Enviar un mensaje directo
+sidebar-forget-contact = Eliminar contacto
+sidebar-muted = (silenciado)
+sidebar-label = Usuarios conectados
+sidebar-resize = Cambiar el ancho de la barra lateral
+
+## Message list
+messages-joining = Entrando en la sala…
+messages-connecting = Conectando…
+messages-loading-history = Cargando mensajes anteriores…
+messages-empty = Todavía no hay mensajes. ¡Empieza la conversación!
+messages-unpin = Desfijar
+messages-label = Mensajes
+
+## Message composer
+composer-direct-to = Mensaje directo a { $name }
+composer-back-to-group = Volver al grupo
+composer-placeholder = Escribe tu mensaje aquí...
+composer-label = Mensaje
+composer-slow-mode = El modo lento está activado
+composer-slow-mode-placeholder = El modo lento está activado...
+composer-share-location = Compartir ubicación
+composer-send = Enviar
+messages-pinned = 📌 Fijado
+message-from = Mensaje de { $name }
+message-own = Tu mensaje
+
+## Message bubbles
+message-link-title = Enlace a este mensaje
+message-actions = Acciones del mensaje
+message-reply = Responder
+message-copy = Copiar texto
+message-copy-image = Copiar dirección de la imagen
+message-copy-location = Copiar enlace del mapa
+message-share = Compartir…
+message-pin = Fijar
+message-delete = Eliminar
+message-report = Denunciar
+message-translate = Traducir
+message-show-original = Ver original
+message-translating = Traduciendo…
+message-translated = Traducido
+message-translated-from = Traducido del { $language }
+message-muted = Mensaje silenciado — haz clic para verlo
+message-open-location = 📍 Abrir la ubicación compartida
+message-location-alt = Ubicación compartida
+message-open-gif = GIF — haz clic para abrirlo
+message-direct = → { $name } (directo)
+message-disappears = Desaparece en { $time }
+poll-votes-one = { $count } voto
+poll-votes-other = { $count } votos
+poll-closed = cerrada
+poll-close = Cerrar encuesta
+
+## Chat header
+header-title = Chat de grupo
+header-participants-one = { $count } participante
+header-participants-other = { $count } participantes
+header-connecting = Conectando…
+header-offline = Sin conexión
+header-signed-in-as = { $presence } · conectado como { $name } · 
+header-not-you = ¿No eres tú?
+header-toggle-sidebar = Mostrar u ocultar la lista de usuarios
+header-disappearing = Mensajes temporales
+header-ephemeral-off = Los mensajes se conservan
+header-ephemeral-30s = Desaparecen a los 30 s
+header-ephemeral-5m = Desaparecen a los 5 min
+header-ephemeral-1h = Desaparecen a la hora
+header-ephemeral-1d = Desaparecen al día
+header-slow-mode = Modo lento
+header-slow-mode-off = Modo lento desactivado
+header-slow-mode-5s = Modo lento 5 s
+header-slow-mode-10s = Modo lento 10 s
+header-slow-mode-30s = Modo lento 30 s
+header-slow-mode-1m = Modo lento 1 min
+header-moderation-log = Registro de moderación
+header-read-aloud = Leer los mensajes en voz alta
+header-stop-reading = Dejar de leer los mensajes en voz alta
+header-light-mode = Cambiar al modo claro
+header-dark-mode = Cambiar al modo oscuro
+header-settings = Ajustes
+header-install = Instalar YewChat
+header-shortcuts = Atajos de teclado
+header-speech-rate = Velocidad de lectura ({ $rate }x)
+header-voice = Voz
+header-default-voice = Predeterminada del navegador
+header-filter-profanity = Filtrar palabrotas
+header-add-word = Añadir una palabra
+header-add = Añadir
+header-remove-word = Quitar palabra
+header-blocked-words = Palabras bloqueadas en la sala
+header-blocked-words-hint = Se ocultan para todos en la sala.
+header-block-word = Bloquear una palabra
+header-account-settings = Ajustes de la cuenta
+
+## Usernames and accounts
+username-spaces = Quita los espacios del principio o del final.
+username-too-short = Usa al menos { $count } caracteres.
+username-too-long = Usa como máximo { $count } caracteres.
+username-bad-character = «{ $character }» no está permitido; usa letras, números, espacios, _, - y .
+username-reserved = Ese nombre está reservado.
+account-invalid-email = Introduce una dirección de correo válida.
+account-email-taken = Ya existe una cuenta con este correo.
+account-name-taken = Ese nombre de usuario ya está en uso.
+account-invalid = Algunos datos no se aceptaron; revísalos.
+account-bad-credentials = Ese correo y esa contraseña no corresponden a ninguna cuenta.
+account-bad-code = Ese código no es correcto; revisa tu aplicación de autenticación e inténtalo de nuevo.
+account-challenge-expired = El inicio de sesión tardó demasiado; vuelve a empezar.
+account-code-required = Introduce un código de tu aplicación de autenticación actual para sustituirla.
+account-unknown-error = Algo salió mal ({ $code }).
+account-unexpected-reply = Respuesta inesperada del servidor: { $error }
+account-enter-code = Introduce el código de 6 dígitos de tu aplicación de autenticación.
+reset-not-configured = Este despliegue no permite restablecer contraseñas.
+reset-unreachable = No se pudo contactar con el servicio de restablecimiento: { $error }
+reset-link-invalid = Este enlace de restablecimiento no es válido o ha caducado.
+reset-service-failed = El servicio de restablecimiento falló ({ $status }).
+password-too-short = Usa al menos { $count } caracteres.
+
+## Sign in and accounts
+field-email = Correo electrónico
+field-username = Nombre de usuario
+field-password = Contraseña
+field-confirm-password = Confirmar contraseña
+field-code = Código de autenticación
+login-welcome = Te damos la bienvenida a YewChat
+login-pick-look = O elige un aspecto:
+login-go = ¡A chatear!
+login-new-here = ¿Eres nuevo?
+login-create-account = Crear una cuenta
+login-or-account = o inicia sesión con tu cuenta
+login-forgot-password = ¿Olvidaste tu contraseña?
+login-or = o
+login-continue-with = Continuar con { $provider }
+sign-in-enter-code = Introduce el código de 6 dígitos de tu aplicación de autenticación.
+sign-in-submitting = Iniciando sesión…
+sign-in-verify = Verificar
+sign-in = Iniciar sesión
+create-account-title = Crear una cuenta
+create-account-mismatch = Las contraseñas no coinciden.
+create-account-submitting = Creando la cuenta…
+create-account-submit = Crear cuenta
+create-account-have-name = ¿Ya tienes un nombre?
+create-account-back = Volver al inicio de sesión
+
+## Error pages
+not-found-title = Esta página se ha perdido
+not-found-body = Puede que el enlace esté roto o que la página se haya movido.
+not-found-back = Volver al chat
+error-title = Algo salió mal
+error-body = Esta página tuvo un problema. Recargarla suele solucionarlo.
+error-reload = Recargar
+error-retry = Reintentar
+
+## Avatars
+avatar-not-image = Elige un archivo de imagen
+avatar-too-large = Las imágenes deben pesar menos de { $size } KB
+avatar-unreadable = No se pudo leer esa imagen: { $error }
+avatar-upload = Subir imagen
+avatar-use-generated = Usar la generada
+avatar-url = …o pega la URL de una imagen
+
+## Password reset
+reset-forgot-title = Contraseña olvidada
+reset-forgot-body = Introduce el correo con el que te registraste y te enviaremos un enlace para restablecerla.
+reset-sending = Enviando…
+reset-send = Enviar enlace
+reset-sent-title = Revisa tu bandeja de entrada
+reset-sent-body = Si { $email } pertenece a una cuenta, el enlace para restablecer la contraseña va de camino.
+reset-title = Restablecer contraseña
+reset-missing-token = A este enlace le falta el código de restablecimiento.
+reset-request-new = Solicita uno nuevo
+reset-done-title = Contraseña actualizada
+reset-done-body = Tu contraseña se ha cambiado. Ya puedes iniciar sesión con ella.
+reset-choose-title = Elige una contraseña nueva
+field-new-password = Contraseña nueva
+reset-saving = Guardando…
+reset-set-password = Guardar contraseña
+
+## Sign-in providers
+oauth-failed = No se pudo iniciar sesión
+oauth-signing-in = Iniciando tu sesión…
+
+## Profiles
+profile-unknown-user = Nadie llamado «{ $name }» ha estado aquí desde que se inició el servidor.
+profile-loading = Cargando el perfil…
+profile-online-joined = Conectado · entró { $when }
+profile-last-seen = Visto por última vez { $when }
+profile-offline = Sin conexión
+profile-about = Acerca de
+profile-no-bio = Todavía no hay biografía.
+profile-shared-rooms = Salas en común
+profile-no-shared-rooms = Ninguna por ahora.
+profile-message = Enviar un mensaje directo
+profile-cant-message = Sin conexión — no se le puede escribir ahora
+profile-back = ← Volver al chat
+
+## Times
+time-just-now = ahora mismo
+time-minutes-ago = hace { $count } min
+time-hours-ago = hace { $count } h
+time-days-ago = hace { $count } d
+time-today = Hoy
+time-yesterday = Ayer
+time-today-at = hoy a las { $time }
+time-yesterday-at = ayer a las { $time }
+time-date-at = el { $date } a las { $time }
+clock-auto = Según el idioma
+clock-12h = 12 horas
+clock-24h = 24 horas
+
+## Appearance
+theme-system = Según el sistema
+theme-light = Claro
+theme-dark = Oscuro
+theme-high-contrast = Alto contraste
+accent-blue = Azul
+accent-purple = Morado
+accent-green = Verde
+accent-teal = Turquesa
+accent-rose = Rosa
+accent-amber = Ámbar
+bubbles-rounded = Redondeadas
+bubbles-soft = Muy redondeadas
+bubbles-square = Cuadradas
+motion-system = Según el sistema
+motion-reduced = Reducido
+motion-full = Completo
+font-small = Pequeño
+font-medium = Mediano
+font-large = Grande
+density-cozy = Cómoda
+density-compact = Compacta
+
+## Experimental features
+feature-reactions = Reacciones a mensajes
+feature-threads = Respuestas en hilos
+feature-e2e = Mensajes directos cifrados de extremo a extremo
+
+## Settings
+settings-title = Ajustes
+settings-back = Volver al chat
+settings-preferences = Preferencias
+settings-language = Idioma
+settings-theme = Tema
+settings-accent = Color de acento
+settings-bubbles = Burbujas de mensaje
+settings-font-size = Tamaño del texto
+settings-density = Espaciado de los mensajes
+settings-motion = Animaciones
+settings-clock = Formato de hora
+settings-time-zone = Zona horaria
+settings-time-zone-device = Este dispositivo
+settings-time-zone-device-named = Este dispositivo ({ $zone })
+settings-translate-to = Traducir mensajes al
+settings-translate-to-interface = Idioma de la interfaz
+settings-notifications = Avisarme de los mensajes nuevos cuando la pestaña esté en segundo plano
+settings-notifications-blocked = Tu navegador bloquea las notificaciones de este sitio; permítelas en su configuración del sitio.
+settings-push = Avísame de menciones y mensajes directos mientras YewChat está cerrado
+settings-push-on = Este navegador está suscrito.
+settings-push-off = Este navegador no está suscrito.
+settings-push-unsupported = Este navegador no puede recibir notificaciones push.
+settings-push-failed = No se pudo cambiar la suscripción. Inténtalo más tarde.
+settings-sound-messages = Reproducir un sonido con los mensajes nuevos
+settings-sound-mentions = Reproducir un sonido cuando alguien me mencione
+settings-sound-direct = Reproducir un sonido con los mensajes directos
+settings-do-not-disturb = No molestar: sin notificaciones ni sonidos
+settings-quiet-hours = No molestar cada día durante las horas de silencio
+settings-quiet-from = Desde
+settings-quiet-until = Hasta
+settings-autoload = Cargar automáticamente los GIF y las vistas previas de mapas
+settings-announce-mentions-only = Anunciar al lector de pantalla solo los mensajes que me mencionan
+settings-warn-unsent = Preguntar antes de salir con un mensaje sin enviar
+settings-telemetry = Enviar estadísticas de uso anónimas, como qué funciones se usan, para ayudar a mejorar YewChat
+settings-profile = Perfil
+settings-profile-hint = Se comparte con la sala cada vez que entras.
+settings-display-name = Nombre visible
+settings-status = ¿Cuál es tu estado?
+settings-bio = Escribe algo sobre ti
+settings-two-factor = Verificación en dos pasos
+settings-install = Aplicación
+settings-install-hint = Instala YewChat para abrirlo desde la pantalla de inicio o el dock en su propia ventana, incluso sin conexión.
+settings-install-button = Instalar YewChat
+settings-experimental = Funciones experimentales
+settings-experimental-hint = Todavía en desarrollo; pueden cambiar o desaparecer. Solo se aplican a este navegador.
+settings-on-by-default = activada por defecto aquí
+settings-debug = Registros e informes de errores
+two-factor-on = La verificación en dos pasos está activada. Se te pedirá un código cada vez que inicies sesión con tu contraseña.
+two-factor-scan = Escanea este código con tu aplicación de autenticación o introduce el secreto a mano.
+two-factor-qr-alt = Código QR para la aplicación de autenticación
+two-factor-code = Código de 6 dígitos
+two-factor-current-code = Código de 6 dígitos de tu aplicación actual
+two-factor-confirm = Confirma tu cuenta para configurar una aplicación de autenticación.
+two-factor-turn-on = Activar
+two-factor-continue = Continuar
+
+## Chat
+role-admin = Admin
+role-owner = Propietario
+role-moderator = Mod
+report-spam = Spam
+report-harassment = Acoso o abuso
+report-inappropriate = Contenido inapropiado
+report-other = Otro motivo
+audit-deleted = eliminó un mensaje de
+audit-kicked = expulsó a
+audit-banned = vetó a
+audit-pinned = fijó un mensaje de
+audit-unpinned = desfijó un mensaje de
+chat-last-seen = Visto por última vez { $when }
+chat-offline = Sin conexión
+chat-offline-banner = Estás sin conexión. Se muestran los mensajes guardados en este dispositivo.
+chat-offline-retry = Reintentar
+chat-outbox-one = { $count } mensaje esperando la conexión
+chat-outbox-other = { $count } mensajes esperando la conexión
+chat-outbox-to = Para { $name }:
+chat-anchor-missing = Ese mensaje ya no está disponible.
+chat-reported = Gracias — hemos avisado a los moderadores.
+chat-copied = Copiado al portapapeles.
+chat-send-failed = No se pudo enviar. Comprueba tu conexión e inténtalo de nuevo.
+chat-copy-failed = No se pudo copiar: el navegador bloqueó el portapapeles.
+chat-link-copied = Enlace copiado al portapapeles.
+chat-share-failed = No se pudo compartir ese mensaje.
+chat-speak-gif = { $name } envió un GIF
+chat-speak-message = { $name } dice { $text }
+chat-announce-message = { $name } dice: { $text }
+chat-name-taken = El nombre de usuario «{ $name }» ya está en uso. Elige otro.
+chat-captcha-failed = Completa el CAPTCHA antes de entrar.
+chat-blocked-words = Esta sala no permite: { $words }. Los demás las verán ocultas — vuelve a enviar para publicarlo de todos modos.
+reauth-title = Tu sesión ha caducado
+reauth-body = Vuelve a iniciar sesión para seguir chateando. Tu mensaje sin enviar se conservará.
+reauth-continue = Continuar como { $name }
+reauth-other-name = Usar otro nombre
+reauth-failed = Tu inicio de sesión ha caducado. Vuelve a iniciar sesión con tu contraseña.
+menu-kick = Expulsar
+menu-ban = Vetar
+report-title = Denunciar mensaje
+report-body = Los moderadores lo revisarán. ¿Qué problema tiene este mensaje?
+report-cancel = Cancelar
+dialog-close = Cerrar
+dialog-cancel = Cancelar
+confirm-delete-title = ¿Eliminar este mensaje?
+confirm-delete-body = Se eliminará para todos en la sala. No se puede deshacer.
+confirm-delete = Eliminar
+confirm-ban-title = ¿Vetar a { $name }?
+confirm-ban-body = Saldrá de la sala y no podrá volver a entrar con ese nombre.
+confirm-ban = Vetar
+confirm-leave-title = ¿Salir sin enviar?
+confirm-leave-body = Lo que has escrito o elegido aquí no se ha enviado ni guardado y se perderá.
+confirm-leave = Salir
+notify-prompt-title = Entérate cuando alguien responda
+notify-prompt-body = Recibe una notificación de escritorio de los mensajes nuevos mientras YewChat está en segundo plano.
+notify-prompt-not-now = Ahora no
+notify-prompt-allow = Activar
+audit-title = Registro de moderación
+audit-all-moderators = Todos los moderadores
+audit-any-time = Cualquier momento
+audit-last-hour = Última hora
+audit-last-day = Últimas 24 horas
+audit-last-week = Últimos 7 días
+audit-empty = No hay acciones de moderación.
+removed-kick-title = Te han sacado del chat
+removed-kick-body = Un moderador te expulsó de la sala. Puedes volver a iniciar sesión para entrar de nuevo.
+removed-ban-title = Te han vetado
+removed-ban-body = Un moderador vetó este nombre de usuario en la sala, así que no puede volver a entrar.
+removed-by = Lo hizo { $name }
+removed-back = Volver al inicio de sesión
+user-joined-at = Entró a las { $time }
+user-generated-avatars = Avatares generados
+user-save-profile = Guardar perfil
+user-no-bio = Todavía no hay biografía.
+user-message = Mensaje
+user-mute = Silenciar
+user-unmute = Dejar de silenciar
+user-block = Bloquear
+user-make-moderator = Hacer moderador
+user-remove-moderator = Quitar de moderador
+poll-usage = Uso: /poll "Pregunta" opción1 opción2
+poll-too-few-options = Una encuesta necesita al menos dos opciones
+presence-joined = { $name } se ha unido
+presence-left = { $name } se ha ido
+
+## Admin dashboard
+admin-connections = Conexiones
+admin-users-in-rooms = Usuarios en salas
+admin-messages-last-hour = Mensajes en la última hora
+admin-rooms = Salas
+admin-room-stats = { $online } en línea · { $rate } mensajes/h
+admin-room-last-message = último mensaje { $when }
+admin-room-no-messages = aún no hay mensajes
+admin-connected-users = Usuarios conectados
+admin-nobody-chatting = No hay nadie chateando ahora mismo.
+admin-kick = Expulsar
+admin-ban = Vetar
+admin-banned = Vetados
+admin-no-banned = No hay usuarios vetados.
+admin-unban = Quitar veto
+admin-title = Panel de administración
+admin-back = Volver al chat
+admin-access-denied = Acceso denegado
+admin-connecting = Conectando…
+
+## Sign-in providers
+oauth-malformed-callback = La URL de retorno no es válida.
+oauth-refused = El proveedor rechazó el inicio de sesión ({ $error }).
+oauth-no-code = El proveedor no envió un código de acceso.
+oauth-not-started = Este inicio de sesión no empezó aquí; inténtalo de nuevo.
+oauth-state-mismatch = La respuesta no coincide con la solicitud; inténtalo de nuevo.
+oauth-no-endpoint = Esta instalación no tiene configurado un endpoint de tokens.
+oauth-unreachable = No se pudo contactar con el servidor de acceso: { $error }
+oauth-rejected = El servidor de acceso rechazó el código ({ $status }).
+oauth-unexpected = El servidor de acceso envió una respuesta inesperada: { $error }
+
+## Location
+location-unsupported = Este navegador no permite compartir la ubicación
+location-failed-reason = No se pudo obtener tu ubicación: { $error }
+location-failed = No se pudo obtener tu ubicación
+
+## Server
+server-unreachable = No se pudo contactar con el servidor: { $error }
+server-unexpected-reply = Respuesta inesperada del servidor: { $error }
+server-closed = El servidor cerró la conexión.
+
+## Onboarding
+onboarding-welcome = ¡Te damos la bienvenida a la sala!
+    Pulsa Intro para enviar un mensaje, o crea una encuesta con /poll "Pregunta" opción1 opción2.
+    Haz clic en un avatar para ver el perfil de alguien; marca con una estrella a quien quieras tener en tus contactos.
+    Sé amable: aquí todo el mundo es una persona.
+
+## Translation
+translate-not-configured = La traducción no está configurada en esta instalación.
+translate-unreachable = No se pudo contactar con el servicio de traducción: { $error }
+translate-failed = El servicio de traducción falló ({ $status }).
+translate-unexpected = El servicio de traducción envió una respuesta inesperada: { $error }
+
+## Keyboard shortcuts
+shortcuts-title = Atajos de teclado
+shortcuts-close = Cerrar
+shortcut-area-general = General
+shortcut-area-rooms = Salas
+shortcut-area-people = Personas
+shortcut-search = Buscar personas
+shortcut-previous-room = Sala anterior
+shortcut-next-room = Sala siguiente
+shortcut-close = Cerrar el último menú o panel y después borrar el mensaje
+shortcut-help = Mostrar atajos de teclado
+shortcut-palette = Buscar una sala, persona o acción
+
+## Command palette
+palette-title = Paleta de comandos
+palette-placeholder = Escribe una sala, persona o acción…
+palette-no-matches = No hay coincidencias
+palette-room = Sala
+palette-message = Mensaje directo
+palette-profile = Perfil
+palette-action = Acción
+palette-alerts-all = Avisarme de todos los mensajes en #{ $room }
+palette-alerts-mentions = Avisarme solo de las menciones en #{ $room }
+palette-alerts-muted = Silenciar #{ $room }
+palette-open-settings = Abrir ajustes
+
+## Toasts
+toast-dismiss = Descartar
+
+## Debug panel
+debug-title = Depuración
+debug-back = Volver al chat
+debug-log = Registro
+debug-level = Nivel
+debug-refresh = Actualizar
+debug-level-hint = El nivel se recuerda en este navegador. Añade ?log=trace a la dirección para cambiarlo solo en esta visita.
+debug-log-empty = Todavía no se ha registrado nada.
+debug-report = Informe de errores
+debug-report-hint = Reúne la versión de la app, tu navegador, el tráfico reciente del chat y el registro de arriba para pegarlo en un informe de errores.
+debug-prepare-report = Preparar informe
+debug-copy = Copiar
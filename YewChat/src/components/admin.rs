@@ -0,0 +1,258 @@
+use serde::Deserialize;
+use yew::prelude::*;
+use yew_agent::{Bridge, Bridged};
+use yew_router::prelude::*;
+
+use crate::components::chat::{MsgTypes, WebSocketMessage};
+use crate::services::event_bus::EventBus;
+use crate::services::i18n::{self, t, t_with};
+use crate::services::time;
+use crate::services::websocket::WebsocketService;
+use crate::{Route, User};
+
+#[allow(clippy::enum_variant_names)]
+pub enum Msg {
+    /// A frame from the server, relayed by the event bus.
+    HandleMsg(String),
+    Kick(String),
+    Ban(String),
+    Unban(String),
+    SocketFailed(String),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RoomActivity {
+    name: String,
+    online: usize,
+    messages_last_hour: usize,
+    #[serde(default)]
+    last_message_at: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+struct ConnectedUser {
+    name: String,
+    role: String,
+}
+
+/// Pushed by the server to admin dashboards every few seconds.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+struct Stats {
+    connected: usize,
+    rooms: Vec<RoomActivity>,
+    users: Vec<ConnectedUser>,
+    banned: Vec<String>,
+}
+
+/// Server-wide overview for admins. It connects as an observer rather than
+/// registering, so opening it doesn't add the admin to the room.
+pub struct Admin {
+    wss: WebsocketService,
+    stats: Option<Stats>,
+    /// Set when the server refused the dashboard connection.
+    denied: Option<String>,
+    /// Set when the server couldn't be reached.
+    failed: Option<String>,
+    _producer: Box<dyn Bridge<EventBus>>,
+}
+
+impl Admin {
+    fn send(&self, message_type: MsgTypes, data: String) {
+        let message = WebSocketMessage {
+            message_type,
+            data: Some(data),
+            data_array: None,
+        };
+        if let Err(e) = self
+            .wss
+            .tx
+            .clone()
+            .try_send(serde_json::to_string(&message).unwrap())
+        {
+            log::debug!("error sending to channel: {:?}", e);
+        }
+    }
+
+    fn view_card(label: &str, value: String) -> Html {
+        html! {
+            <div class="bg-white rounded-xl shadow-sm p-5">
+                <p class="text-sm text-gray-500">{label}</p>
+                <p class="mt-1 text-3xl font-semibold text-gray-800">{value}</p>
+            </div>
+        }
+    }
+
+    fn view_stats(&self, ctx: &Context<Self>, stats: &Stats) -> Html {
+        let now = js_sys::Date::now();
+        let messages_last_hour: usize = stats.rooms.iter().map(|r| r.messages_last_hour).sum();
+
+        html! {
+            <>
+                <div class="grid grid-cols-1 md:grid-cols-3 gap-4">
+                    { Self::view_card(&t("admin-connections"), i18n::number(stats.connected as f64)) }
+                    { Self::view_card(&t("admin-users-in-rooms"), i18n::number(stats.users.len() as f64)) }
+                    { Self::view_card(&t("admin-messages-last-hour"), i18n::number(messages_last_hour as f64)) }
+                </div>
+
+                <h2 class="mt-8 mb-3 text-lg font-semibold text-gray-800">{t("admin-rooms")}</h2>
+                <div class="bg-white rounded-xl shadow-sm divide-y divide-gray-100">
+                    {
+                        stats.rooms.iter().map(|room| html! {
+                            <div class="flex items-center justify-between px-5 py-3 text-sm">
+                                <span class="font-medium text-gray-800">{format!("#{}", room.name)}</span>
+                                <span class="text-gray-500">
+                                    {t_with("admin-room-stats", &[("online", &i18n::number(room.online as f64)), ("rate", &i18n::number(room.messages_last_hour as f64))])}
+                                    {" · "}
+                                    {
+                                        match room.last_message_at {
+                                            Some(at) => t_with("admin-room-last-message", &[("when", &time::relative(now - at))]),
+                                            None => t("admin-room-no-messages"),
+                                        }
+                                    }
+                                </span>
+                            </div>
+                        }).collect::<Html>()
+                    }
+                </div>
+
+                <h2 class="mt-8 mb-3 text-lg font-semibold text-gray-800">{t("admin-connected-users")}</h2>
+                <div class="bg-white rounded-xl shadow-sm divide-y divide-gray-100">
+                    if stats.users.is_empty() {
+                        <p class="px-5 py-4 text-sm text-gray-400">{t("admin-nobody-chatting")}</p>
+                    }
+                    {
+                        stats.users.iter().map(|user| {
+                            let kick = {
+                                let name = user.name.clone();
+                                ctx.link().callback(move |_| Msg::Kick(name.clone()))
+                            };
+                            let ban = {
+                                let name = user.name.clone();
+                                ctx.link().callback(move |_| Msg::Ban(name.clone()))
+                            };
+                            html! {
+                                <div class="flex items-center px-5 py-3 text-sm">
+                                    <span class="flex-1 text-gray-800">{user.name.clone()}</span>
+                                    <span class="me-4 text-xs text-gray-400">{user.role.clone()}</span>
+                                    if user.role != "admin" {
+                                        <button onclick={kick} class="px-2 py-1 rounded text-gray-600 hover:bg-gray-100">{t("admin-kick")}</button>
+                                        <button onclick={ban} class="ms-2 px-2 py-1 rounded text-red-600 hover:bg-red-50">{t("admin-ban")}</button>
+                                    }
+                                </div>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+
+                <h2 class="mt-8 mb-3 text-lg font-semibold text-gray-800">{t("admin-banned")}</h2>
+                <div class="bg-white rounded-xl shadow-sm divide-y divide-gray-100">
+                    if stats.banned.is_empty() {
+                        <p class="px-5 py-4 text-sm text-gray-400">{t("admin-no-banned")}</p>
+                    }
+                    {
+                        stats.banned.iter().map(|name| {
+                            let unban = {
+                                let name = name.clone();
+                                ctx.link().callback(move |_| Msg::Unban(name.clone()))
+                            };
+                            html! {
+                                <div class="flex items-center justify-between px-5 py-3 text-sm">
+                                    <span class="text-gray-800">{name.clone()}</span>
+                                    <button onclick={unban} class="text-xs text-blue-500 hover:underline">{t("admin-unban")}</button>
+                                </div>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+            </>
+        }
+    }
+}
+
+impl Component for Admin {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (user, _) = ctx
+            .link()
+            .context::<User>(Callback::noop())
+            .expect("context to be set");
+        let admin = Self {
+            wss: WebsocketService::new(ctx.link().callback(Msg::SocketFailed)),
+            stats: None,
+            denied: None,
+            failed: None,
+            _producer: EventBus::bridge(ctx.link().callback(Msg::HandleMsg)),
+        };
+        // The server only opens the dashboard to a password sign-in of an admin account.
+        admin.send(MsgTypes::Admin, user.account_token.clone().unwrap_or_default());
+        admin
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::HandleMsg(s) => {
+                let msg: WebSocketMessage = serde_json::from_str(&s).unwrap();
+                match msg.message_type {
+                    MsgTypes::Stats => {
+                        match msg.data.map(|d| serde_json::from_str::<Stats>(&d)) {
+                            Some(Ok(stats)) => self.stats = Some(stats),
+                            Some(Err(e)) => log::error!("bad stats: {:?}", e),
+                            None => {}
+                        }
+                        true
+                    }
+                    // The only notice the dashboard gets is the refusal.
+                    MsgTypes::System if self.stats.is_none() => {
+                        self.denied = msg.data;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Msg::Kick(name) => {
+                self.send(MsgTypes::Kick, name);
+                false
+            }
+            Msg::Ban(name) => {
+                self.send(MsgTypes::Ban, name);
+                false
+            }
+            Msg::Unban(name) => {
+                self.send(MsgTypes::Unban, name);
+                false
+            }
+            Msg::SocketFailed(error) => {
+                self.failed = Some(error);
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div class="w-full min-h-screen overflow-y-auto bg-gray-50">
+                <div class="max-w-4xl mx-auto px-6 py-8">
+                    <div class="flex items-center justify-between mb-6">
+                        <h1 class="text-2xl font-bold text-gray-800">{t("admin-title")}</h1>
+                        <Link<Route> to={Route::Chat} classes="text-sm text-blue-500 hover:underline">{t("admin-back")}</Link<Route>>
+                    </div>
+                    if let Some(reason) = &self.denied {
+                        <div class="bg-white rounded-xl shadow-sm p-8 text-center">
+                            <h2 class="text-lg font-semibold text-gray-800">{t("admin-access-denied")}</h2>
+                            <p class="mt-2 text-gray-600">{reason.clone()}</p>
+                        </div>
+                    } else if let Some(error) = &self.failed {
+                        <p class="text-red-600">{error.clone()}</p>
+                    } else if let Some(stats) = &self.stats {
+                        { self.view_stats(ctx, stats) }
+                    } else {
+                        <p class="text-gray-500">{t("admin-connecting")}</p>
+                    }
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use yew::prelude::*;
+
+use crate::services::avatar as avatar_service;
+use crate::services::palette;
+
+#[derive(Properties, PartialEq)]
+pub struct AvatarProps {
+    pub name: String,
+    pub src: String,
+    /// Size and shape classes, applied to both the image and the fallback badge.
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+/// An avatar image that falls back to an initials badge when the image fails to load.
+#[function_component(Avatar)]
+pub fn avatar(props: &AvatarProps) -> Html {
+    // Remember which image failed, so a new `src` gets a fresh attempt.
+    let failed_src = use_state(|| None::<String>);
+
+    if failed_src.as_ref() == Some(&props.src) {
+        let initials = avatar_service::initials(&props.name);
+        html! {
+            <div
+                class={classes!(props.class.clone(), "flex", "items-center", "justify-center", "text-white", "font-semibold", "select-none")}
+                style={format!("background-color: {};", palette::user_color(&props.name))}
+                title={props.name.clone()}
+            >
+                {if initials.is_empty() { "?".to_string() } else { initials }}
+            </div>
+        }
+    } else {
+        let onerror = {
+            let failed_src = failed_src.clone();
+            let src = props.src.clone();
+            Callback::from(move |_: Event| failed_src.set(Some(src.clone())))
+        };
+        html! {
+            <img class={classes!(props.class.clone(), "object-cover")} src={props.src.clone()} alt={props.name.clone()} onerror={onerror}/>
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use yew::prelude::*;
+
+use crate::components::avatar::Avatar;
+use crate::services::avatar;
+
+#[derive(Properties, PartialEq)]
+pub struct AvatarGalleryProps {
+    /// Username the generated looks are seeded from.
+    pub name: String,
+    pub value: Option<String>,
+    pub on_select: Callback<Option<String>>,
+}
+
+/// A grid of generated avatars to choose from; picking the current one again
+/// goes back to the default.
+#[function_component(AvatarGallery)]
+pub fn avatar_gallery(props: &AvatarGalleryProps) -> Html {
+    html! {
+        <div class="grid grid-cols-4 gap-2">
+            {
+                avatar::gallery(&props.name).into_iter().map(|url| {
+                    let selected = props.value.as_ref() == Some(&url);
+                    let onclick = {
+                        let url = url.clone();
+                        props.on_select.reform(move |_| if selected { None } else { Some(url.clone()) })
+                    };
+                    html! {
+                        <button
+                            type="button"
+                            onclick={onclick}
+                            class={classes!(
+                                "p-1", "rounded-full", "border-2", "transition-colors",
+                                if selected { "border-purple-500" } else { "border-transparent hover:border-gray-300" }
+                            )}
+                        >
+                            <Avatar name={props.name.clone()} src={url} class="w-full aspect-square rounded-full bg-gray-100 text-sm"/>
+                        </button>
+                    }
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
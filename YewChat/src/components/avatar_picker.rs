@@ -0,0 +1,97 @@
+use gloo::file::callbacks::{read_as_data_url, FileReader};
+use gloo::file::File;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::avatar::Avatar;
+use crate::services::avatar::{self, MAX_UPLOAD_BYTES};
+use crate::services::i18n::{self, t, t_with};
+
+#[derive(Properties, PartialEq)]
+pub struct AvatarPickerProps {
+    /// Username the generated fallback avatar is derived from.
+    pub name: String,
+    /// The chosen avatar URL, or `None` for the generated one.
+    pub value: Option<String>,
+    pub on_change: Callback<Option<String>>,
+}
+
+#[function_component(AvatarPicker)]
+pub fn avatar_picker(props: &AvatarPickerProps) -> Html {
+    let error = use_state(|| None::<String>);
+    // Dropping the reader cancels the read, so keep it alive until it finishes.
+    let reader = use_mut_ref(|| None::<FileReader>);
+
+    let on_file = {
+        let error = error.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let file = match input.files().and_then(|files| files.get(0)) {
+                Some(file) => File::from(file),
+                None => return,
+            };
+            if !file.raw_mime_type().starts_with("image/") {
+                error.set(Some(t("avatar-not-image")));
+                return;
+            }
+            if file.size() > MAX_UPLOAD_BYTES {
+                error.set(Some(t_with("avatar-too-large", &[("size", &i18n::number((MAX_UPLOAD_BYTES / 1024) as f64))])));
+                return;
+            }
+            error.set(None);
+
+            let on_change = on_change.clone();
+            let error = error.clone();
+            *reader.borrow_mut() = Some(read_as_data_url(&file, move |result| match result {
+                Ok(url) => on_change.emit(Some(url)),
+                Err(e) => error.set(Some(t_with("avatar-unreadable", &[("error", &e.to_string())]))),
+            }));
+        })
+    };
+
+    let on_url = {
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let url = input.value().trim().to_string();
+            on_change.emit(if url.is_empty() { None } else { Some(url) });
+        })
+    };
+
+    let reset = props.on_change.reform(|_| None);
+    let preview = props
+        .value
+        .clone()
+        .unwrap_or_else(|| avatar::default_avatar(&props.name));
+    let url_value = props
+        .value
+        .clone()
+        .filter(|v| !v.starts_with("data:"))
+        .unwrap_or_default();
+
+    html! {
+        <div class="flex items-center">
+            <Avatar name={props.name.clone()} src={preview} class="w-16 h-16 rounded-full border-2 border-white shadow text-xl"/>
+            <div class="ms-4 flex-1 min-w-0">
+                <label class="inline-block px-3 py-1 text-sm rounded-lg border border-gray-300 text-gray-700 hover:bg-gray-50 cursor-pointer">
+                    {t("avatar-upload")}
+                    <input type="file" accept="image/*" class="hidden" onchange={on_file}/>
+                </label>
+                if props.value.is_some() {
+                    <button onclick={reset} class="ms-2 text-sm text-gray-500 hover:underline">{t("avatar-use-generated")}</button>
+                }
+                <input
+                    type="url"
+                    value={url_value}
+                    oninput={on_url}
+                    placeholder={t("avatar-url")}
+                    class="mt-2 w-full px-3 py-1 text-sm rounded-lg border border-gray-300 focus:outline-none focus:ring-2 focus:ring-purple-500"
+                />
+                if let Some(error) = (*error).clone() {
+                    <p class="mt-1 text-xs text-red-500">{error}</p>
+                }
+            </div>
+        </div>
+    }
+}
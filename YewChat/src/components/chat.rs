@@ -1,292 +1,2750 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::rc::Rc;
+
+use gloo::events::{EventListener, EventListenerOptions, EventListenerPhase};
+use gloo::timers::callback::Interval;
+use js_sys::{Function, Object, Promise, Reflect};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use web_sys::HtmlInputElement;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{
+    Element, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement, ScrollBehavior, ScrollIntoViewOptions, ScrollLogicalPosition,
+};
 use yew::prelude::*;
-use yew_agent::{Bridge, Bridged};
+use yew_agent::use_bridge;
+use yew_router::history::Location as _;
+use yew_router::prelude::*;
 
-use crate::{User, services::websocket::WebsocketService};
+use crate::{Route, User, UserAction, DEFAULT_ROOM, services::websocket::WebsocketService};
+use crate::components::avatar::Avatar;
+use crate::components::avatar_picker::AvatarPicker;
+use crate::components::chat_header::ChatHeader;
+use crate::components::command_palette::{Command, CommandPalette};
+use crate::components::error_boundary::ErrorReporter;
+use crate::components::focus_trap::FocusTrap;
+use crate::components::message_bubble::{MessageAction, Translated};
+use crate::components::message_composer::MessageComposer;
+use crate::components::message_list::MessageList;
+use crate::components::modal::{ConfirmDialog, Modal};
+use crate::components::theme_provider::{use_theme, ThemeClasses, ThemeContext};
+use crate::components::toast::{use_toast, ToastContext};
+use crate::components::user_sidebar::UserSidebar;
+use crate::services::alerts::{self, AlertLevel, RoomAlerts};
+use crate::services::avatar::{self, AvatarProvider};
+use crate::services::chat_store::{use_incoming_messages, ChatStore, Connection, IncomingMessages, StoreAction};
 use crate::services::event_bus::EventBus;
+use crate::services::focus::{self, FocusTarget};
+use crate::services::i18n::{t, t_count, t_with};
+use crate::services::layout::{self, Layout};
+use crate::services::location;
+use crate::services::name_list::{self, NameList};
+use crate::services::offline;
+use crate::services::onboarding;
+use crate::services::outbox::{self, Queued};
+use crate::services::profanity::{self, ProfanityFilter};
+use crate::services::push;
+use crate::services::session::{self, Session};
+use crate::services::settings::{self, SettingsStore, UserSort};
+use crate::services::shortcuts::{self, use_dismissable, use_shortcut, Chord, ShortcutArea};
+use crate::services::sound::{self, Sound};
+use crate::services::speech::{self, SpeechSettings};
+use crate::services::telemetry::{self, EventKind};
+use crate::services::theme;
+use crate::services::title;
+use crate::services::time;
+use crate::services::translate::{self, Translation};
+
+pub use yewchat_protocol::{MsgTypes, WebSocketMessage};
 
+#[allow(clippy::enum_variant_names)]
 pub enum Msg {
+    /// A frame from the server, relayed by the event bus.
     HandleMsg(String),
     SubmitMessage,
     ToggleSidebar,
+    ResizeSidebar(u32),
+    SortUsers(UserSort),
+    ToggleReadAloud,
+    ToggleSpeechPanel,
+    SetSpeechRate(f32),
+    SetSpeechVoice(Option<String>),
+    VotePoll(u64, usize),
+    ClosePoll(u64),
+    SetEphemeral(u64),
+    SetSlowMode(u64),
+    ShareLocation,
+    SendLocation(f64, f64),
+    LocationFailed(String),
+    AddContact(String),
+    RemoveContact(String),
+    OpenDirect(String),
+    CloseDirect,
+    BlockUser(String),
+    UnblockUser(String),
+    ShowProfile(String),
+    HideProfile,
+    SaveProfile,
+    SetAvatar(Option<String>),
+    SetAvatarProvider(AvatarProvider),
+    PinMessage(u64),
+    UnpinMessage,
+    DeleteMessage(u64),
+    SetRole(String, Role),
+    OpenUserMenu(String, i32, i32),
+    CloseUserMenu,
+    KickUser(String),
+    BanUser(String),
+    ToggleMute(String),
+    RevealMessage(u64),
+    ReplyTo(u64),
+    CopyMessage(u64),
+    ShareMessage(u64),
+    OpenReport(u64),
+    CancelReport,
+    SubmitReport(ReportReason),
+    ToggleProfanityFilter,
+    AddFilterWord,
+    RemoveFilterWord(String),
+    AddBlockedWord,
+    RemoveBlockedWord(String),
+    ToggleAuditPanel,
+    SetAuditActor(Option<String>),
+    SetAuditWindow(u64),
+    SignOut,
+    Reauthenticate,
+    /// Scroll to a message and point the URL at its permalink.
+    FocusMessage(u64),
+    TranslateMessage(u64),
+    /// Moves to the room this many places up or down the list.
+    SwitchRoom(isize),
+    OpenRoom(String),
+    SetRoomAlerts(String, AlertLevel),
+    /// Goes to `route`, asking first if something unsent would be lost.
+    Navigate(Route),
+    Leave,
+    StayHere,
+    /// From the card offering desktop notifications.
+    AllowNotifications,
+    NotificationsAllowed,
+    NotNowNotifications,
+    /// This browser's push subscription, for the server to reach it once the tab is closed.
+    SharePushSubscription(String),
+    OutboxLoaded(Vec<Queued>),
+    /// Opens a new socket, after starting without a network.
+    Reconnect,
+    /// The socket couldn't be opened or stopped taking frames.
+    SocketFailed(String),
+    ToggleShortcuts,
+    TogglePalette,
+    Confirmed,
+    CancelConfirmation,
+    /// Escape with nothing left open to close: the composer is emptied.
+    ClearComposer,
+}
+
+impl From<MessageAction> for Msg {
+    fn from(action: MessageAction) -> Self {
+        match action {
+            MessageAction::ShowProfile(name) => Msg::ShowProfile(name),
+            MessageAction::Reveal(id) => Msg::RevealMessage(id),
+            MessageAction::Reply(id) => Msg::ReplyTo(id),
+            MessageAction::Copy(id) => Msg::CopyMessage(id),
+            MessageAction::Share(id) => Msg::ShareMessage(id),
+            MessageAction::Focus(id) => Msg::FocusMessage(id),
+            MessageAction::Pin(id) => Msg::PinMessage(id),
+            MessageAction::Unpin => Msg::UnpinMessage,
+            MessageAction::Delete(id) => Msg::DeleteMessage(id),
+            MessageAction::Report(id) => Msg::OpenReport(id),
+            MessageAction::Vote(poll, option) => Msg::VotePoll(poll, option),
+            MessageAction::ClosePoll(poll) => Msg::ClosePoll(poll),
+            MessageAction::Translate(id) => Msg::TranslateMessage(id),
+        }
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MessageData {
+    /// Assigned by the server; used to pin and delete messages.
+    #[serde(default)]
+    pub(crate) id: Option<u64>,
+    pub(crate) from: String,
+    pub(crate) message: String,
+    #[serde(default)]
+    pub(crate) poll: Option<Poll>,
+    /// Milliseconds since the epoch when the server received the message.
+    #[serde(default)]
+    pub(crate) time: Option<f64>,
+    /// Milliseconds since the epoch after which the message disappears.
+    #[serde(default)]
+    pub(crate) expires_at: Option<f64>,
+    #[serde(default)]
+    pub(crate) location: Option<Location>,
+    /// Set when the message was sent privately to a single user.
+    #[serde(default)]
+    pub(crate) to: Option<String>,
+    /// A notice about the room itself (joins, leaves, ...) rather than chat.
+    #[serde(default)]
+    pub(crate) system: bool,
+    /// Numbers the notices this tab made up itself, which have no `id`.
+    #[serde(skip)]
+    pub(crate) notice_id: Option<u64>,
+}
+
+thread_local! {
+    static NEXT_NOTICE_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// How long a message opened from a permalink stays highlighted.
+const HIGHLIGHT_MS: f64 = 2500.0;
+
+/// Announcements left in the live region; older ones have been read already.
+const ANNOUNCEMENTS_KEPT: usize = 5;
+
+pub(crate) fn message_anchor(id: u64) -> String {
+    format!("msg-{}", id)
+}
+
+/// Path of a message's permalink, `/chat/<room>#msg-<id>`.
+pub(crate) fn permalink(id: u64) -> String {
+    let room = Route::ChatRoom {
+        room: DEFAULT_ROOM.to_string(),
+    };
+    format!("{}#{}", room.to_path(), message_anchor(id))
+}
+
+/// The message an anchor made by [`message_anchor`] belongs to.
+pub(crate) fn anchor_message_id(anchor: &str) -> Option<u64> {
+    anchor.strip_prefix("msg-")?.parse().ok()
+}
+
+/// Puts `text` on the clipboard, failing if the browser refuses. The
+/// Clipboard API is reached through `Reflect` since web-sys only has it
+/// behind its unstable APIs flag.
+async fn copy_to_clipboard(text: &str) -> Result<(), JsValue> {
+    let clipboard = Reflect::get(&gloo::utils::window().navigator(), &"clipboard".into())?;
+    let write: Function = Reflect::get(&clipboard, &"writeText".into())?.dyn_into()?;
+    let written: Promise = write.call1(&clipboard, &text.into())?.dyn_into()?;
+    JsFuture::from(written).await?;
+    Ok(())
+}
+
+/// `path` on this site, for handing to other apps.
+fn absolute_url(path: &str) -> String {
+    let origin = gloo::utils::window().location().origin().unwrap_or_default();
+    format!("{}{}", origin, path)
+}
+
+/// Opens the system share sheet with `text` and `url` through
+/// `navigator.share`, or `None` where the browser has no Web Share API. The
+/// future fails if the sheet is dismissed or sharing is refused.
+fn share(text: &str, url: &str) -> Option<impl std::future::Future<Output = Result<(), JsValue>>> {
+    let navigator = gloo::utils::window().navigator();
+    let open: Function = Reflect::get(&navigator, &"share".into()).ok()?.dyn_into().ok()?;
+    let data = Object::new();
+    let _ = Reflect::set(&data, &"text".into(), &text.into());
+    let _ = Reflect::set(&data, &"url".into(), &url.into());
+    let shared = open.call1(&navigator, &data).and_then(|promise| promise.dyn_into::<Promise>());
+    Some(async move {
+        JsFuture::from(shared?).await?;
+        Ok(())
+    })
+}
+
+/// The message a `#msg-<id>` fragment in the address bar points at.
+fn anchor_from_location() -> Option<u64> {
+    let hash = gloo::utils::window().location().hash().ok()?;
+    anchor_message_id(hash.strip_prefix('#')?)
+}
+
+impl MessageData {
+    pub(crate) fn notice(text: String) -> Self {
+        let notice_id = NEXT_NOTICE_ID.with(|next| next.replace(next.get() + 1));
+        Self {
+            message: text,
+            system: true,
+            notice_id: Some(notice_id),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the message is meant for `username`: sent to them directly, or
+    /// naming them or their display name with an `@`.
+    pub(crate) fn mentions(&self, username: &str, display_name: &str) -> bool {
+        if self.to.as_deref() == Some(username) {
+            return true;
+        }
+        let text = self.message.to_lowercase();
+        [username, display_name]
+            .iter()
+            .filter(|name| !name.is_empty())
+            .any(|name| text.contains(&format!("@{}", name.to_lowercase())))
+    }
+
+    /// The address of the GIF the message is, if it is one. Only `http:` and
+    /// `https:` addresses count, so a message can't pass off a script as one.
+    pub(crate) fn gif_url(&self) -> Option<String> {
+        if !self.message.ends_with(".gif") {
+            return None;
+        }
+        let url = web_sys::Url::new(&self.message).ok()?;
+        matches!(url.protocol().as_str(), "http:" | "https:").then(|| url.href())
+    }
 }
 
+/// Query of a `/chat?dm=<name>` link.
 #[derive(Deserialize)]
-struct MessageData {
-    from: String,
+struct DirectLink {
+    dm: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DirectMessage {
+    to: String,
     message: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Location {
+    pub(crate) latitude: f64,
+    pub(crate) longitude: f64,
+}
+
+impl Location {
+    pub(crate) fn map_url(&self) -> String {
+        format!(
+            "https://www.openstreetmap.org/?mlat={lat}&mlon={lon}#map=16/{lat}/{lon}",
+            lat = self.latitude,
+            lon = self.longitude
+        )
+    }
+
+    pub(crate) fn thumbnail_url(&self) -> String {
+        format!(
+            "https://staticmap.openstreetmap.de/staticmap.php?center={lat},{lon}&zoom=15&size=300x150&markers={lat},{lon},red-pushpin",
+            lat = self.latitude,
+            lon = self.longitude
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Poll {
+    pub(crate) id: u64,
+    pub(crate) creator: String,
+    pub(crate) question: String,
+    pub(crate) options: Vec<PollOption>,
+    pub(crate) closed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub(crate) struct PollOption {
+    pub(crate) text: String,
+    pub(crate) votes: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct NewPoll {
+    question: String,
+    options: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PollVote {
+    poll_id: u64,
+    option: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Role {
+    #[default]
+    Member,
+    Moderator,
+    Owner,
+    /// Configured on the server; can also see the moderation audit log.
+    Admin,
+}
+
+impl Role {
+    /// Moderators and owners may pin and delete anyone's messages.
+    pub(crate) fn can_moderate(self) -> bool {
+        self >= Role::Moderator
+    }
+
+    /// The badge's label key and colours.
+    pub(crate) fn badge(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Role::Admin => Some(("role-admin", "bg-red-100 text-red-800")),
+            Role::Owner => Some(("role-owner", "bg-yellow-100 text-yellow-800")),
+            Role::Moderator => Some(("role-moderator", "bg-blue-100 text-blue-800")),
+            Role::Member => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum DisconnectReason {
+    Kick,
+    Ban,
+}
+
+/// Sent by the server right before it closes a kicked or banned client's socket.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+struct Disconnect {
+    reason: DisconnectReason,
+    #[serde(default)]
+    by: Option<String>,
+}
+
+/// Why a message is being reported, sent to the server with the report.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
-pub enum MsgTypes {
-    Users,
-    Register,
-    Message,
+pub enum ReportReason {
+    Spam,
+    Harassment,
+    Inappropriate,
+    Other,
+}
+
+impl ReportReason {
+    const ALL: [ReportReason; 4] = [Self::Spam, Self::Harassment, Self::Inappropriate, Self::Other];
+
+    fn label(self) -> String {
+        t(match self {
+            Self::Spam => "report-spam",
+            Self::Harassment => "report-harassment",
+            Self::Inappropriate => "report-inappropriate",
+            Self::Other => "report-other",
+        })
+    }
+}
+
+/// Moderation that can't be undone, waiting for the user to confirm it.
+#[derive(Clone, PartialEq)]
+enum Confirmation {
+    Delete(u64),
+    Ban(String),
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
-struct WebSocketMessage {
-    message_type: MsgTypes,
-    data_array: Option<Vec<String>>,
-    data: Option<String>,
+struct Report {
+    message_id: u64,
+    reason: ReportReason,
 }
 
-#[derive(Clone)]
-struct UserProfile {
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum AuditAction {
+    Delete,
+    Kick,
+    Ban,
+    Pin,
+    Unpin,
+}
+
+impl AuditAction {
+    fn verb(self) -> String {
+        t(match self {
+            Self::Delete => "audit-deleted",
+            Self::Kick => "audit-kicked",
+            Self::Ban => "audit-banned",
+            Self::Pin => "audit-pinned",
+            Self::Unpin => "audit-unpinned",
+        })
+    }
+}
+
+/// A moderation action, sent by the server to admins only.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+struct AuditEvent {
+    action: AuditAction,
+    actor: String,
+    #[serde(default)]
+    target: Option<String>,
+    /// The affected message's text, for deletes and pins.
+    #[serde(default)]
+    detail: Option<String>,
+    time: f64,
+}
+
+/// The server's registration lease, renewed by signing in again.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionGrant {
+    expires_at: f64,
+    #[serde(default)]
+    token: String,
+}
+
+#[derive(Serialize)]
+struct RoleChange {
     name: String,
-    avatar: String,
+    role: Role,
 }
 
-pub struct Chat {
-    users: Vec<UserProfile>,
-    chat_input: NodeRef,
-    wss: WebsocketService,
-    messages: Vec<MessageData>,
-    _producer: Box<dyn Bridge<EventBus>>,
-    sidebar_visible: bool,
-}
-
-impl Component for Chat {
-    type Message = Msg;
-    type Properties = ();
-
-    fn create(ctx: &Context<Self>) -> Self {
-        let (user, _) = ctx
-            .link()
-            .context::<User>(Callback::noop())
-            .expect("context to be set");
-        let wss = WebsocketService::new();
-        let username = user.username.borrow().clone();
-
-        let message = WebSocketMessage {
-            message_type: MsgTypes::Register,
-            data: Some(username.to_string()),
-            data_array: None,
-        };
+/// Per-user details carried in the `data` field of a `Users` message.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserInfo {
+    name: String,
+    #[serde(default)]
+    bio: Option<String>,
+    #[serde(default)]
+    joined_at: Option<f64>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    avatar: Option<String>,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    role: Role,
+    /// Present only for users who have left, in milliseconds since the epoch.
+    #[serde(default)]
+    last_seen: Option<f64>,
+}
+
+#[derive(Clone, Default, PartialEq)]
+pub(crate) struct UserProfile {
+    /// The unique login name; messages and DMs are addressed by it.
+    pub(crate) name: String,
+    pub(crate) display_name: Option<String>,
+    pub(crate) role: Role,
+    /// The user's own avatar, if they picked one.
+    pub(crate) avatar: Option<String>,
+    pub(crate) bio: Option<String>,
+    pub(crate) joined_at: Option<f64>,
+    pub(crate) status: Option<String>,
+}
 
-        if let Ok(_) = wss
-            .tx
+impl UserProfile {
+    pub(crate) fn avatar(&self) -> String {
+        self.avatar
             .clone()
-            .try_send(serde_json::to_string(&message).unwrap())
-        {
-            log::debug!("message sent successfully");
+            .unwrap_or_else(|| avatar::default_avatar(&self.name))
+    }
+
+    pub(crate) fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+}
+
+impl From<UserInfo> for UserProfile {
+    fn from(info: UserInfo) -> Self {
+        Self {
+            avatar: info.avatar.filter(|a| !a.is_empty()),
+            name: info.name,
+            display_name: info.display_name.filter(|d| !d.trim().is_empty()),
+            role: info.role,
+            bio: info.bio.filter(|b| !b.is_empty()),
+            joined_at: info.joined_at,
+            status: info.status.filter(|s| !s.is_empty()),
         }
+    }
+}
+
+/// The name to show for `name`, which may be offline and unknown to us.
+pub(crate) fn display_name_of(users: &[Rc<UserProfile>], name: &str) -> String {
+    users
+        .iter()
+        .find(|u| u.name == name)
+        .map(|u| u.display_name().to_string())
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Message text as it should be shown to `username`: profanity filtered and,
+/// for other people's messages, with the room's blocked words masked.
+pub(crate) fn masked_text(m: &MessageData, username: &str, profanity: &ProfanityFilter, blocked_words: &[String]) -> String {
+    let text = profanity.mask(&m.message);
+    if m.from == username {
+        text
+    } else {
+        profanity::mask_words(&text, |word| profanity::is_listed(word, blocked_words))
+    }
+}
+
+pub(crate) fn last_seen_label(last_seen: &HashMap<String, f64>, name: &str) -> String {
+    match last_seen.get(name) {
+        Some(seen) => t_with("chat-last-seen", &[("when", &time::last_seen(*seen, js_sys::Date::now()))]),
+        None => t("chat-offline"),
+    }
+}
+
+pub(crate) fn view_role_badge(role: Role) -> Html {
+    match role.badge() {
+        Some((label, colors)) => html! {
+            <span class={classes!("ms-1", "px-1.5", "py-0.5", "rounded", "text-xs", "font-medium", colors)}>{t(label)}</span>
+        },
+        None => html! {},
+    }
+}
+
+/// How the chat screen is set up and what it has open. It only changes through
+/// [`ChatAction`]s; the conversation itself lives in the [`ChatStore`].
+#[derive(Clone, PartialEq)]
+struct ChatState {
+    username: String,
+    composer_error: Option<String>,
+    ephemeral_seconds: u64,
+    slow_mode_seconds: u64,
+    /// When slow mode lets us post again.
+    next_send_at: Option<f64>,
+    /// Moved on each tick while the cooldown counts down, to redraw it.
+    countdown_at: f64,
+    layout: Layout,
+    profile_card: Option<String>,
+    /// Avatar picked in the profile card but not saved yet.
+    pending_avatar: Option<Option<String>>,
+    /// Where generated avatars come from; every default avatar changes with it.
+    avatar_provider: AvatarProvider,
+    contacts: NameList,
+    blocked: NameList,
+    muted: NameList,
+    /// How much each room may interrupt: read aloud, notify, chime.
+    room_alerts: RoomAlerts,
+    /// Ids of muted users' messages the user chose to show anyway.
+    revealed: HashSet<u64>,
+    translations: HashMap<u64, Translated>,
+    dm_target: Option<String>,
+    speech: SpeechSettings,
+    settings: SettingsStore,
+    speech_panel_visible: bool,
+    voices: Vec<String>,
+    profanity: ProfanityFilter,
+    /// Words the room's moderators don't allow, lowercased.
+    blocked_words: Vec<String>,
+    /// A message the user was warned about; sending it again goes through.
+    confirmed_blocked: Option<String>,
+    /// Target and viewport coordinates of the open moderation menu.
+    user_menu: Option<(String, i32, i32)>,
+    disconnected: Option<Disconnect>,
+    audit_log: Vec<AuditEvent>,
+    audit_panel_visible: bool,
+    audit_actor: Option<String>,
+    /// Only show events from the last this many seconds; 0 shows everything.
+    audit_window: u64,
+    session_expires_at: Option<f64>,
+    /// Shown when the server stops accepting our messages until we sign in again.
+    reauth_visible: bool,
+    /// The last chat message we sent, restored to the composer if it was rejected.
+    last_sent: Option<String>,
+    /// Message the report dialog is open for.
+    reporting: Option<u64>,
+    /// Permalinked message to scroll to once it has been rendered.
+    pending_anchor: Option<u64>,
+    /// Message being highlighted and when the highlight fades.
+    highlighted: Option<(u64, f64)>,
+    /// The latest screen reader announcements, numbered so each is read once.
+    announcements: Vec<(u64, String)>,
+    announced: u64,
+    shortcuts_visible: bool,
+    palette_visible: bool,
+    confirming: Option<Confirmation>,
+    /// Where the user asked to go while something was still unsent.
+    leaving: Option<Route>,
+    /// Whether the card offering desktop notifications is up.
+    notification_prompt: bool,
+    /// Messages written while offline, in the order they go out.
+    outbox: Vec<Queued>,
+    /// Bumped to open a new socket, e.g. when the network comes back.
+    connect_attempt: u32,
+}
+
+/// State changes of the chat screen. Talking to the server and touching the
+/// page happen in [`ChatHandler`] before these are dispatched.
+enum ChatAction {
+    SessionGranted(Option<f64>),
+    /// Text for screen readers to read out, e.g. a new message.
+    Announce(String),
+    /// The server no longer has the permalinked message.
+    AnchorMissing,
+    Disconnected(Option<Disconnect>),
+    EphemeralChanged(u64),
+    AuditReceived(AuditEvent),
+    BlockedWordsChanged(Vec<String>),
+    SlowModeChanged(u64),
+    ReauthRequired,
+    /// The composer's `text` uses blocked words; sending it again posts it anyway.
+    BlockedWordsWarning {
+        error: String,
+        text: String,
+    },
+    ComposerError(String),
+    /// Something was posted; `text` is kept in case the server refuses it.
+    Sent {
+        text: Option<String>,
+        /// Whether slow mode applies to us; moderators aren't throttled.
+        throttled: bool,
+    },
+    Tick,
+    ToggleSidebar,
+    ResizeSidebar(u32),
+    SortUsers(UserSort),
+    OpenDirect(String),
+    CloseDirect,
+    AddContact(String),
+    RemoveContact(String),
+    BlockUser(String),
+    UnblockUser(String),
+    ToggleMute(String),
+    ShowProfile(String),
+    HideProfile,
+    SetPendingAvatar(Option<String>),
+    AvatarProviderChanged(AvatarProvider),
+    /// Fields from the profile card; `None` where the input wasn't on screen.
+    ProfileSaved {
+        display_name: Option<String>,
+        status: Option<String>,
+        bio: Option<String>,
+    },
+    OpenUserMenu(String, i32, i32),
+    CloseUserMenu,
+    RevealMessage(u64),
+    TranslationStarted(u64),
+    Translated(u64, Result<Translation, String>),
+    HideTranslation(u64),
+    OpenReport(u64),
+    CancelReport,
+    Reported,
+    ToggleProfanityFilter,
+    AddFilterWord(String),
+    RemoveFilterWord(String),
+    ToggleAuditPanel,
+    SetAuditActor(Option<String>),
+    SetAuditWindow(u64),
+    /// Read-aloud is switched per room; carries the active one.
+    ToggleReadAloud(String),
+    /// Carries the browser's voices, which load lazily and are refreshed on open.
+    ToggleSpeechPanel(Vec<String>),
+    SetSpeechRate(f32),
+    SetSpeechVoice(Option<String>),
+    FocusMessage(u64),
+    /// The permalinked message is on screen and gets highlighted.
+    AnchorReached(u64),
+    SetRoomAlerts(String, AlertLevel),
+    ToggleShortcuts,
+    TogglePalette,
+    Confirm(Option<Confirmation>),
+    ConfirmLeave(Option<Route>),
+    HideNotificationPrompt,
+    EnableNotifications,
+    OutboxLoaded(Vec<Queued>),
+    /// A message written while offline, kept to send later.
+    Queued(Queued),
+    OutboxDelivered(Vec<String>),
+    Reconnect,
+}
 
+impl ChatState {
+    fn new(username: String, dm_target: Option<String>) -> Self {
         Self {
-            users: vec![],
-            messages: vec![],
-            chat_input: NodeRef::default(),
-            wss,
-            _producer: EventBus::bridge(ctx.link().callback(Msg::HandleMsg)),
-            sidebar_visible: true,
+            username,
+            composer_error: None,
+            ephemeral_seconds: 0,
+            slow_mode_seconds: 0,
+            next_send_at: None,
+            countdown_at: 0.0,
+            layout: Layout::load(),
+            profile_card: None,
+            pending_avatar: None,
+            avatar_provider: avatar::provider(),
+            contacts: NameList::load(name_list::CONTACTS),
+            blocked: NameList::load(name_list::BLOCKED),
+            muted: NameList::load(name_list::MUTED),
+            room_alerts: RoomAlerts::load(),
+            revealed: HashSet::new(),
+            translations: HashMap::new(),
+            dm_target,
+            speech: SpeechSettings::load(),
+            settings: SettingsStore::load(),
+            speech_panel_visible: false,
+            voices: vec![],
+            profanity: ProfanityFilter::load(),
+            blocked_words: vec![],
+            confirmed_blocked: None,
+            user_menu: None,
+            disconnected: None,
+            audit_log: vec![],
+            audit_panel_visible: false,
+            audit_actor: None,
+            audit_window: 0,
+            session_expires_at: None,
+            reauth_visible: false,
+            last_sent: None,
+            reporting: None,
+            pending_anchor: anchor_from_location(),
+            highlighted: None,
+            announcements: vec![],
+            announced: 0,
+            shortcuts_visible: false,
+            palette_visible: false,
+            confirming: None,
+            leaving: None,
+            notification_prompt: false,
+            outbox: vec![],
+            connect_attempt: 0,
         }
     }
-    
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        match msg {
-            Msg::HandleMsg(s) => {
-                let msg: WebSocketMessage = serde_json::from_str(&s).unwrap();
-                match msg.message_type {
-                    MsgTypes::Users => {
-                        let users_from_message = msg.data_array.unwrap_or_default();
-                        self.users = users_from_message
-                            .iter()
-                            .map(|u| UserProfile {
-                                name: u.into(),
-                                avatar: format!(
-                                    "https://avatars.dicebear.com/api/adventurer-neutral/{}.svg",
-                                    u
-                                )
-                                .into(),
-                            })
-                            .collect();
-                        return true;
-                    }
-                    MsgTypes::Message => {
-                        let message_data: MessageData =
-                            serde_json::from_str(&msg.data.unwrap()).unwrap();
-                        self.messages.push(message_data);
-                        return true;
-                    }
-                    _ => {
-                        return false;
-                    }
+
+    /// Starts the slow-mode countdown after a post.
+    fn start_cooldown(&mut self) {
+        if self.slow_mode_seconds > 0 {
+            self.next_send_at = Some(js_sys::Date::now() + self.slow_mode_seconds as f64 * 1000.0);
+        }
+    }
+
+    /// Whole seconds until the composer unlocks, if it is locked.
+    fn cooldown_remaining(&self, now: f64) -> Option<u64> {
+        self.next_send_at
+            .filter(|at| *at > now)
+            .map(|at| ((at - now) / 1000.0).ceil() as u64)
+    }
+
+    fn masked_text(&self, m: &MessageData) -> String {
+        masked_text(m, &self.username, &self.profanity, &self.blocked_words)
+    }
+
+    /// What copying or sharing a message hands over: its text, or the
+    /// address of its GIF or map.
+    fn plain_text(&self, m: &MessageData) -> String {
+        match &m.location {
+            Some(location) => location.map_url(),
+            None => m.gif_url().unwrap_or_else(|| self.masked_text(m)),
+        }
+    }
+
+    /// Whether a message from `from` should interrupt us by voice, sound or notification.
+    fn alerts_for(&self, from: &str) -> bool {
+        from != self.username && !self.blocked.contains(from) && !self.muted.contains(from)
+    }
+
+    /// Whether screen readers should hear about `m` as it arrives.
+    fn announces(&self, m: &MessageData) -> bool {
+        !m.system
+            && self.alerts_for(&m.from)
+            && (!self.settings.announce_mentions_only || m.mentions(&self.username, &self.settings.display_name))
+    }
+}
+
+impl Reducible for ChatState {
+    type Action = ChatAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut state = (*self).clone();
+        match action {
+            ChatAction::SessionGranted(expires_at) => {
+                state.session_expires_at = expires_at;
+                if state.reauth_visible {
+                    // It was restored to the composer when the dialog opened.
+                    state.last_sent = None;
+                }
+                state.reauth_visible = false;
+            }
+            ChatAction::Announce(text) => {
+                state.announced += 1;
+                state.announcements.push((state.announced, text));
+                let read = state.announcements.len().saturating_sub(ANNOUNCEMENTS_KEPT);
+                state.announcements.drain(..read);
+            }
+            ChatAction::AnchorMissing => state.pending_anchor = None,
+            ChatAction::Disconnected(disconnect) => state.disconnected = disconnect,
+            ChatAction::EphemeralChanged(seconds) => state.ephemeral_seconds = seconds,
+            ChatAction::AuditReceived(event) => state.audit_log.push(event),
+            ChatAction::BlockedWordsChanged(words) => state.blocked_words = words,
+            ChatAction::SlowModeChanged(seconds) => {
+                state.slow_mode_seconds = seconds;
+                if seconds == 0 {
+                    state.next_send_at = None;
+                }
+            }
+            ChatAction::ReauthRequired => state.reauth_visible = true,
+            ChatAction::BlockedWordsWarning { error, text } => {
+                state.composer_error = Some(error);
+                state.confirmed_blocked = Some(text);
+            }
+            ChatAction::ComposerError(error) => state.composer_error = Some(error),
+            ChatAction::Sent { text, throttled } => {
+                state.confirmed_blocked = None;
+                state.composer_error = None;
+                if text.is_some() {
+                    state.last_sent = text;
+                }
+                if throttled {
+                    state.start_cooldown();
+                }
+                // Once they've said something, hearing back is worth a notification.
+                state.notification_prompt = onboarding::notification_prompt_due(state.settings.notifications);
+            }
+            ChatAction::Tick => {
+                let now = js_sys::Date::now();
+                if state.highlighted.is_some_and(|(_, until)| until <= now) {
+                    state.highlighted = None;
+                }
+                if !state.reauth_visible && state.session_expires_at.is_some_and(|at| at <= now) {
+                    state.last_sent = None;
+                    state.reauth_visible = true;
+                }
+                let cooling_down = state.next_send_at.is_some();
+                if state.cooldown_remaining(now).is_none() {
+                    state.next_send_at = None;
+                }
+                // Only re-render while the cooldown is counting down.
+                if cooling_down {
+                    state.countdown_at = now;
+                }
+            }
+            ChatAction::ToggleSidebar => state.layout.sidebar_visible = !state.layout.sidebar_visible,
+            ChatAction::ResizeSidebar(width) => state.layout.sidebar_width = width,
+            ChatAction::SortUsers(sort) => state.settings.user_sort = sort,
+            ChatAction::OpenDirect(name) => {
+                state.dm_target = Some(name);
+                state.profile_card = None;
+            }
+            ChatAction::CloseDirect => state.dm_target = None,
+            ChatAction::AddContact(name) => state.contacts.add(&name),
+            ChatAction::RemoveContact(name) => state.contacts.remove(&name),
+            ChatAction::BlockUser(name) => {
+                state.blocked.add(&name);
+                state.contacts.remove(&name);
+                if state.dm_target.as_ref() == Some(&name) {
+                    state.dm_target = None;
+                }
+                state.profile_card = None;
+            }
+            ChatAction::UnblockUser(name) => state.blocked.remove(&name),
+            ChatAction::ToggleMute(name) => state.muted.toggle(&name),
+            ChatAction::ShowProfile(name) => {
+                state.profile_card = Some(name);
+                state.pending_avatar = None;
+            }
+            ChatAction::HideProfile => state.profile_card = None,
+            ChatAction::SetPendingAvatar(avatar) => state.pending_avatar = Some(avatar),
+            ChatAction::AvatarProviderChanged(provider) => state.avatar_provider = provider,
+            ChatAction::ProfileSaved { display_name, status, bio } => {
+                if let Some(display_name) = display_name {
+                    state.settings.display_name = display_name;
                 }
+                if let Some(status) = status {
+                    state.settings.status = status;
+                }
+                if let Some(bio) = bio {
+                    state.settings.bio = bio;
+                }
+                state.pending_avatar = None;
+                state.profile_card = None;
+            }
+            ChatAction::OpenUserMenu(name, x, y) => state.user_menu = Some((name, x, y)),
+            ChatAction::CloseUserMenu => state.user_menu = None,
+            ChatAction::RevealMessage(id) => {
+                state.revealed.insert(id);
+            }
+            ChatAction::TranslationStarted(id) => {
+                state.translations.insert(id, Translated::Loading);
             }
-            Msg::SubmitMessage => {
-                let input = self.chat_input.cast::<HtmlInputElement>();
-                if let Some(input) = input {
-                    let message = WebSocketMessage {
-                        message_type: MsgTypes::Message,
-                        data: Some(input.value()),
-                        data_array: None,
+            ChatAction::Translated(id, result) => {
+                // Unless it was hidden while we waited.
+                if let Some(shown) = state.translations.get_mut(&id) {
+                    *shown = match result {
+                        Ok(translation) => Translated::Done(translation),
+                        Err(error) => Translated::Failed(error),
                     };
-                    if let Err(e) = self
-                        .wss
-                        .tx
-                        .clone()
-                        .try_send(serde_json::to_string(&message).unwrap())
-                    {
-                        log::debug!("error sending to channel: {:?}", e);
-                    }
-                    input.set_value("");
-                };
+                }
+            }
+            ChatAction::HideTranslation(id) => {
+                state.translations.remove(&id);
+            }
+            ChatAction::OpenReport(id) => state.reporting = Some(id),
+            ChatAction::CancelReport => state.reporting = None,
+            ChatAction::Reported => state.reporting = None,
+            ChatAction::ToggleProfanityFilter => state.profanity.enabled = !state.profanity.enabled,
+            ChatAction::AddFilterWord(word) => state.profanity.add_word(&word),
+            ChatAction::RemoveFilterWord(word) => state.profanity.remove_word(&word),
+            ChatAction::ToggleAuditPanel => state.audit_panel_visible = !state.audit_panel_visible,
+            ChatAction::SetRoomAlerts(room, level) => state.room_alerts.set(&room, level),
+            ChatAction::ToggleShortcuts => state.shortcuts_visible = !state.shortcuts_visible,
+            ChatAction::TogglePalette => state.palette_visible = !state.palette_visible,
+            ChatAction::Confirm(confirmation) => state.confirming = confirmation,
+            ChatAction::ConfirmLeave(route) => state.leaving = route,
+            ChatAction::HideNotificationPrompt => state.notification_prompt = false,
+            ChatAction::EnableNotifications => state.settings.notifications = true,
+            ChatAction::OutboxLoaded(queued) => state.outbox = queued,
+            ChatAction::Queued(queued) => {
+                state.confirmed_blocked = None;
+                state.composer_error = None;
+                state.outbox.push(queued);
+            }
+            ChatAction::OutboxDelivered(ids) => state.outbox.retain(|m| !ids.contains(&m.id)),
+            ChatAction::Reconnect => {
+                state.connect_attempt += 1;
+                // The next grant is a new registration.
+                state.session_expires_at = None;
+            }
+            ChatAction::SetAuditActor(actor) => state.audit_actor = actor,
+            ChatAction::SetAuditWindow(seconds) => state.audit_window = seconds,
+            ChatAction::ToggleReadAloud(room) => state.speech.toggle(&room),
+            ChatAction::ToggleSpeechPanel(voices) => {
+                state.speech_panel_visible = !state.speech_panel_visible;
+                state.voices = voices;
+            }
+            ChatAction::SetSpeechRate(rate) => state.speech.rate = rate,
+            ChatAction::SetSpeechVoice(voice) => state.speech.voice = voice,
+            ChatAction::FocusMessage(id) => state.pending_anchor = Some(id),
+            ChatAction::AnchorReached(id) => {
+                state.pending_anchor = None;
+                state.highlighted = Some((id, js_sys::Date::now() + HIGHLIGHT_MS));
+            }
+        }
+        state.into()
+    }
+}
+
+/// Inputs whose values are read when the user submits them.
+#[derive(Clone, Default)]
+struct ChatRefs {
+    chat_input: NodeRef,
+    filter_word_input: NodeRef,
+    blocked_word_input: NodeRef,
+    display_name_input: NodeRef,
+    status_input: NodeRef,
+    bio_input: NodeRef,
+}
+
+/// Turns the view's [`Msg`]s into server messages, page effects, [`ChatAction`]s
+/// and [`StoreAction`]s. Rebuilt on every render, so it sees the latest state.
+#[derive(Clone)]
+struct ChatHandler {
+    state: UseReducerHandle<ChatState>,
+    store: ChatStore,
+    user: User,
+    history: Option<AnyHistory>,
+    reporter: Option<ErrorReporter>,
+    socket: Rc<RefCell<Option<WebsocketService>>>,
+    /// Messages from the socket waiting for the next frame to reach the store.
+    incoming: IncomingMessages,
+    refs: ChatRefs,
+    toasts: ToastContext,
+}
+
+impl ChatHandler {
+    /// Like `Scope::callback`, handling the message on a copy of this handler.
+    fn callback<E: 'static>(&self, f: impl Fn(E) -> Msg + 'static) -> Callback<E> {
+        let handler = self.clone();
+        Callback::from(move |e| handler.handle(f(e)))
+    }
+
+    /// Like `Scope::batch_callback`: only events `f` turns into a message are handled.
+    fn batch_callback<E: 'static>(&self, f: impl Fn(E) -> Option<Msg> + 'static) -> Callback<E> {
+        let handler = self.clone();
+        Callback::from(move |e| {
+            if let Some(msg) = f(e) {
+                handler.handle(msg);
+            }
+        })
+    }
+
+    fn display_name_of(&self, name: &str) -> String {
+        display_name_of(&self.store.users, name)
+    }
+
+    fn my_role(&self) -> Role {
+        self.store.role_of(&self.state.username)
+    }
+
+    /// Whether the message went out; if not, the user is told.
+    fn send(&self, message_type: MsgTypes, data: String) -> bool {
+        self.send_frame(WebSocketMessage::new(message_type, data))
+    }
+
+    /// [`Self::send`] for frames that need more than `data`.
+    fn send_frame(&self, message: WebSocketMessage) -> bool {
+        let sent = match self.socket.borrow().as_ref() {
+            Some(wss) => match wss.tx.clone().try_send(serde_json::to_string(&message).unwrap()) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("error sending to channel: {:?}", e);
+                    false
+                }
+            },
+            None => {
+                log::warn!("not connected, dropping {:?}", message.message_type);
                 false
             }
-            Msg::ToggleSidebar => {
-                self.sidebar_visible = !self.sidebar_visible;
-                true
+        };
+        if !sent {
+            self.toasts.error(t("chat-send-failed"));
+        }
+        sent
+    }
+
+    /// Shares the profile fields from the settings page, which the server
+    /// forgets whenever we leave.
+    fn share_profile(&self) {
+        for (message_type, value) in [
+            (MsgTypes::DisplayName, &self.state.settings.display_name),
+            (MsgTypes::Status, &self.state.settings.status),
+            (MsgTypes::Profile, &self.state.settings.bio),
+        ] {
+            if !value.trim().is_empty() {
+                self.send(message_type, value.trim().to_string());
             }
         }
     }
-    
-    fn view(&self, ctx: &Context<Self>) -> Html {
-        let submit = ctx.link().callback(|_| Msg::SubmitMessage);
-        let on_keypress = ctx.link().batch_callback(|e: KeyboardEvent| {
-            if e.key() == "Enter" {
-                Some(Msg::SubmitMessage)
-            } else {
-                None
+
+    fn send_blocked_words(&self, words: Vec<String>) {
+        self.send(MsgTypes::BlockedWords, serde_json::to_string(&words).unwrap());
+    }
+
+    /// Keeps the composer's text (or the message the server just refused)
+    /// while the sign-in-again dialog is open, so nothing typed is lost.
+    fn keep_draft(&self) {
+        if let Some(input) = self.refs.chat_input.cast::<HtmlInputElement>() {
+            if input.value().is_empty() {
+                if let Some(text) = &self.state.last_sent {
+                    input.set_value(text);
+                }
             }
-        });
-        let toggle_sidebar = ctx.link().callback(|_| Msg::ToggleSidebar);
+            session::save_draft(DEFAULT_ROOM, &input.value());
+        }
+    }
 
-        html! {
-            <div class="flex h-screen w-full bg-gray-50">
-                // Sidebar with responsive design
-                <div class={classes!(
-                    "bg-white", "shadow-lg", "transition-all", "duration-300",
-                    "md:block", // Always show on medium screens and above
-                    if self.sidebar_visible { "w-72" } else { "w-0 md:w-72" },
-                    if !self.sidebar_visible { "hidden" } else { "" }
-                )}>
-                    <div class="py-4 px-5 border-b border-gray-200">
-                        <h2 class="text-xl font-semibold text-gray-800 flex items-center">
-                            <svg xmlns="http://www.w3.org/2000/svg" class="h-6 w-6 mr-2 text-blue-500" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M17 20h5v-2a3 3 0 00-5.356-1.857M17 20H7m10 0v-2c0-.656-.126-1.283-.356-1.857M7 20H2v-2a3 3 0 015.356-1.857M7 20v-2c0-.656.126-1.283.356-1.857m0 0a5.002 5.002 0 019.288 0M15 7a3 3 0 11-6 0 3 3 0 016 0zm6 3a2 2 0 11-4 0 2 2 0 014 0zM7 10a2 2 0 11-4 0 2 2 0 014 0z" />
-                            </svg>
-                            {"Online Users"}
-                        </h2>
-                    </div>
-                    <div class="overflow-y-auto" style="max-height: calc(100vh - 68px);">
-                        {
-                            if self.users.is_empty() {
-                                html! {
-                                    <div class="py-8 px-5 text-center text-gray-500">
-                                        {"No users online at the moment"}
-                                    </div>
-                                }
-                            } else {
-                                self.users.clone().iter().map(|u| {
-                                    html! {
-                                        <div class="flex items-center px-5 py-3 hover:bg-gray-50 transition-colors cursor-pointer">
-                                            <div class="relative">
-                                                <img class="w-12 h-12 rounded-full object-cover border-2 border-white shadow-sm" src={u.avatar.clone()} alt="avatar"/>
-                                                <div class="absolute bottom-0 right-0 h-3 w-3 rounded-full bg-green-400 border-2 border-white"></div>
-                                            </div>
-                                            <div class="ml-3">
-                                                <div class="font-medium text-gray-800">{u.name.clone()}</div>
-                                                <div class="text-xs text-gray-500">{"Online"}</div>
-                                            </div>
-                                        </div>
-                                    }
-                                }).collect::<Html>()
-                            }
-                        }
-                    </div>
-                </div>
+    /// Whether leaving now would lose something: text in the composer or an
+    /// avatar picked but not saved. Never with the warning turned off.
+    fn has_unsent(&self) -> bool {
+        let typed = self
+            .refs
+            .chat_input
+            .cast::<HtmlInputElement>()
+            .is_some_and(|input| !input.value().trim().is_empty());
+        self.state.settings.warn_unsent && (typed || self.state.pending_avatar.is_some())
+    }
 
-                <div class="flex-1 flex flex-col w-full">
-                    <div class="bg-white border-b border-gray-200 px-6 py-4 shadow-sm">
-                        <div class="flex items-center justify-between">
-                            <div class="flex items-center">
-                                // Mobile toggle for sidebar
-                                <button 
-                                    onclick={toggle_sidebar} 
-                                    class="md:hidden mr-4 text-gray-500 hover:text-gray-700 focus:outline-none"
-                                >
-                                    <svg xmlns="http://www.w3.org/2000/svg" class="h-6 w-6" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M4 6h16M4 12h16M4 18h16" />
-                                    </svg>
-                                </button>
-                                <div class="h-10 w-10 rounded-full bg-blue-100 flex items-center justify-center text-blue-500">
-                                    <svg xmlns="http://www.w3.org/2000/svg" class="h-6 w-6" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 12h.01M12 12h.01M16 12h.01M21 12c0 4.418-4.03 8-9 8a9.863 9.863 0 01-4.255-.949L3 20l1.395-3.72C3.512 15.042 3 13.574 3 12c0-4.418 4.03-8 9-8s9 3.582 9 8z" />
-                                    </svg>
-                                </div>
-                                <div class="ml-4">
-                                    <h2 class="text-lg font-semibold text-gray-800">{"Group Chat"}</h2>
-                                    <p class="text-sm text-gray-500">{format!("{} participants", self.users.len())}</p>
-                                </div>
-                            </div>
-                        </div>
-                    </div>
+    /// Back to the login screen, with `notice` explaining why if it wasn't our choice.
+    fn sign_out(&self, notice: Option<String>) {
+        // Otherwise the login screen would send us straight back here.
+        Session::forget();
+        let forced = notice.is_some();
+        self.user.dispatch(UserAction::SignOut { notice });
+        self.incoming.clear();
+        self.store.dispatch(StoreAction::Reset);
+        offline::forget_history();
+        outbox::clear();
+        if let Some(history) = &self.history {
+            if forced {
+                history.replace(Route::Login);
+            } else {
+                history.push(Route::Login);
+            }
+        }
+    }
 
-                    <div class="flex-1 overflow-y-auto p-6 bg-gray-50" style="scrollbar-width: thin;">
-                        {
-                            if self.messages.is_empty() {
-                                html! {
-                                    <div class="flex flex-col items-center justify-center h-full text-gray-500">
-                                        <svg xmlns="http://www.w3.org/2000/svg" class="h-16 w-16 mb-4 text-gray-300" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 12h.01M12 12h.01M16 12h.01M21 12c0 4.418-4.03 8-9 8a9.863 9.863 0 01-4.255-.949L3 20l1.395-3.72C3.512 15.042 3 13.574 3 12c0-4.418 4.03-8 9-8s9 3.582 9 8z" />
-                                        </svg>
-                                        {"No messages yet. Start the conversation!"}
-                                    </div>
-                                }
-                            } else {
-                                self.messages.iter().map(|m| {
-                                    let default_profile = UserProfile { 
-                                        name: m.from.clone(), 
-                                        avatar: format!("https://avatars.dicebear.com/api/adventurer-neutral/{}.svg", m.from)
-                                    };
-                                    let user = self.users.iter().find(|u| u.name == m.from).unwrap_or(&default_profile);
-                                    
-                                    html! {
-                                        <div class="flex mb-4 items-end">
-                                            <div class="flex-shrink-0">
-                                                <img class="w-8 h-8 rounded-full" src={user.avatar.clone()} alt="avatar"/>
-                                            </div>
-                                            <div class="ml-2 max-w-xl lg:max-w-2xl">
-                                                <div class="font-medium text-sm text-gray-700">{user.name.clone()}</div>
-                                                <div class="bg-white p-3 rounded-lg shadow-sm mt-1">
-                                                    if m.message.ends_with(".gif") {
-                                                        <img class="rounded-lg max-w-full" src={m.message.clone()}/>
-                                                    } else {
-                                                        <p class="text-gray-800">{m.message.clone()}</p>
-                                                    }
-                                                </div>
-                                            </div>
-                                        </div>
-                                    }
-                                }).collect::<Html>()
-                            }
-                        }
-                    </div>
+    /// Gives up on the chat screen; the error boundary offers to reload.
+    fn fail(&self, error: String) {
+        match &self.reporter {
+            Some(reporter) => reporter.report(error),
+            None => log::error!("{}", error),
+        }
+    }
 
-                    <div class="bg-white border-t border-gray-200 px-6 py-3">
-                        <div class="flex items-center">
-                            <input 
-                                ref={self.chat_input.clone()} 
-                                type="text" 
-                                placeholder="Type your message here..." 
-                                class="block w-full px-4 py-3 bg-gray-100 rounded-full outline-none focus:ring-2 focus:ring-blue-400 focus:bg-white"
-                                onkeypress={on_keypress}
-                            />
-                            <button 
-                                onclick={submit} 
-                                class="ml-3 px-4 py-3 bg-blue-500 hover:bg-blue-600 rounded-full text-white shadow-sm transition"
-                            >
-                                <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 19l9 2-9-18-9 18 9-2zm0 0v-8" />
-                                </svg>
-                            </button>
-                        </div>
-                    </div>
-                </div>
-            </div>
+    /// Parses a payload the chat can't go on without, failing if it's missing or malformed.
+    fn parse<T: DeserializeOwned>(&self, data: Option<String>, what: &str) -> Option<T> {
+        let parsed = data
+            .ok_or_else(|| "no data".to_string())
+            .and_then(|data| serde_json::from_str(&data).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.fail(format!("bad {} from the server: {}", what, e));
+                None
+            }
         }
     }
-}
\ No newline at end of file
+
+    fn handle_server_message(&self, s: &str) {
+        let msg: WebSocketMessage = match serde_json::from_str(s) {
+            Ok(msg) => msg,
+            Err(e) => return self.fail(format!("unreadable frame from the server: {}", e)),
+        };
+        let state = &self.state;
+        // Whatever else the server says may be about messages still waiting,
+        // like a deletion or a poll's votes, so they go first.
+        if msg.message_type != MsgTypes::Message {
+            self.incoming.flush(&self.store);
+        }
+        match msg.message_type {
+            MsgTypes::Users => {
+                // Older servers only send the bare usernames in `data_array`.
+                let users_from_message: Vec<UserInfo> = match msg.data {
+                    Some(data) => serde_json::from_str(&data).unwrap_or_default(),
+                    None => msg
+                        .data_array
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|name| UserInfo {
+                            name,
+                            ..Default::default()
+                        })
+                        .collect(),
+                };
+                let (offline, online): (Vec<UserInfo>, Vec<UserInfo>) = users_from_message
+                    .into_iter()
+                    .partition(|u| u.last_seen.is_some());
+                self.store.dispatch(StoreAction::UsersReceived {
+                    online: online.into_iter().map(UserProfile::from).collect(),
+                    last_seen: offline
+                        .into_iter()
+                        .filter_map(|u| Some((u.name, u.last_seen?)))
+                        .collect(),
+                    quiet: state.blocked.clone(),
+                });
+            }
+            MsgTypes::Message => {
+                let message_data: MessageData = match self.parse(msg.data, "message") {
+                    Some(message_data) => message_data,
+                    None => return,
+                };
+                let mentioned = message_data.mentions(&state.username, &state.settings.display_name);
+                // Messages from the server land in the active room.
+                let room = &self.store.active_room;
+                let room_allows = state.room_alerts.allows(room, mentioned);
+                // Only spoken and screen-read messages get through Do Not Disturb.
+                let quiet = alerts::do_not_disturb(&state.settings, js_sys::Date::now());
+                if state.speech.is_enabled(room) && room_allows && state.alerts_for(&message_data.from) {
+                    let from = self.display_name_of(&message_data.from);
+                    let text = if message_data.gif_url().is_some() {
+                        t_with("chat-speak-gif", &[("name", &from)])
+                    } else {
+                        t_with("chat-speak-message", &[("name", &from), ("text", &state.masked_text(&message_data))])
+                    };
+                    speech::speak(&text, &state.speech);
+                }
+                if state.announces(&message_data) {
+                    let from = self.display_name_of(&message_data.from);
+                    let text = if message_data.gif_url().is_some() {
+                        t_with("chat-speak-gif", &[("name", &from)])
+                    } else {
+                        t_with("chat-announce-message", &[("name", &from), ("text", &state.masked_text(&message_data))])
+                    };
+                    state.dispatch(ChatAction::Announce(text));
+                }
+                if state.settings.notifications && room_allows && !quiet && state.alerts_for(&message_data.from) {
+                    let on_click = match message_data.id {
+                        Some(id) => self.callback(move |_| Msg::FocusMessage(id)),
+                        None => Callback::noop(),
+                    };
+                    settings::notify(&self.display_name_of(&message_data.from), &state.masked_text(&message_data), on_click);
+                }
+                if room_allows && !quiet && !message_data.system && state.alerts_for(&message_data.from) {
+                    let sound = if message_data.to.is_some() {
+                        Sound::Direct
+                    } else if mentioned {
+                        Sound::Mention
+                    } else {
+                        Sound::Message
+                    };
+                    if sound.enabled(&state.settings) {
+                        sound::play(sound);
+                    }
+                }
+                self.incoming.push(&self.store, message_data, mentioned);
+            }
+            MsgTypes::Poll => {
+                if let Some(poll) = self.parse(msg.data, "poll") {
+                    self.store.dispatch(StoreAction::PollUpdated(poll));
+                }
+            }
+            MsgTypes::Pin => {
+                // An empty payload means the pin was removed.
+                self.store.dispatch(StoreAction::Pinned(msg.data.and_then(|d| serde_json::from_str(&d).ok())));
+            }
+            MsgTypes::Delete => {
+                if let Some(id) = msg.data.and_then(|d| d.parse::<u64>().ok()) {
+                    offline::forget_message(id);
+                    self.store.dispatch(StoreAction::Deleted(id));
+                }
+            }
+            MsgTypes::System => self.store.dispatch(StoreAction::Notice(msg.data.unwrap_or_default())),
+            MsgTypes::Error => match msg.data.as_deref() {
+                Some("name_taken") => {
+                    telemetry::record(EventKind::RegistrationRejected);
+                    self.sign_out(Some(t_with("chat-name-taken", &[("name", &state.username)])))
+                }
+                Some("captcha_failed") => {
+                    telemetry::record(EventKind::RegistrationRejected);
+                    self.sign_out(Some(t("chat-captcha-failed")))
+                }
+                Some("auth_expired") => state.dispatch(ChatAction::ReauthRequired),
+                Some("reauth_failed") => self.sign_out(Some(t("reauth-failed"))),
+                _ => log::error!("server error: {:?}", msg.data),
+            },
+            MsgTypes::Session => {
+                let grant: Option<SessionGrant> = msg.data.and_then(|d| serde_json::from_str(&d).ok());
+                // The first grant means registration went through.
+                if state.session_expires_at.is_none() {
+                    self.store.dispatch(StoreAction::Connected);
+                    telemetry::record(EventKind::Connected);
+                    self.share_profile();
+                    let share = self.callback(Msg::SharePushSubscription);
+                    spawn_local(async move {
+                        if let Some(subscription) = push::subscription().await {
+                            share.emit(subscription);
+                        }
+                    });
+                    // What was written offline goes out now.
+                    if let Some(grant) = grant.as_ref().filter(|_| !state.outbox.is_empty()) {
+                        self.send(MsgTypes::Outbox, outbox::frame(&grant.token, &state.outbox));
+                    }
+                    // Messages from before we joined have to be fetched.
+                    if let Some(id) = state.pending_anchor {
+                        if self.send(MsgTypes::History, id.to_string()) {
+                            self.store.dispatch(StoreAction::HistoryRequested);
+                        }
+                    }
+                }
+                if state.reauth_visible {
+                    // The draft never left the composer, so the stored copy is stale.
+                    session::take_draft(DEFAULT_ROOM);
+                }
+                if let Some(token) = grant.as_ref().map(|g| g.token.clone()).filter(|token| !token.is_empty()) {
+                    spawn_local(outbox::set_token(token));
+                }
+                state.dispatch(ChatAction::SessionGranted(grant.map(|g| g.expires_at)));
+            }
+            MsgTypes::Outbox => {
+                let delivered: Vec<String> = msg.data.and_then(|d| serde_json::from_str(&d).ok()).unwrap_or_default();
+                spawn_local(outbox::delivered(delivered.clone()));
+                state.dispatch(ChatAction::OutboxDelivered(delivered));
+            }
+            MsgTypes::History => {
+                let older: Vec<MessageData> = msg.data.and_then(|d| serde_json::from_str(&d).ok()).unwrap_or_default();
+                if older.is_empty() {
+                    self.toasts.info(t("chat-anchor-missing"));
+                    state.dispatch(ChatAction::AnchorMissing);
+                }
+                self.store.dispatch(StoreAction::HistoryReceived(older));
+            }
+            MsgTypes::Disconnect => {
+                let disconnect: Option<Disconnect> = msg.data.and_then(|d| serde_json::from_str(&d).ok());
+                // A banned name can't rejoin, so don't log back in with it.
+                if disconnect.as_ref().map(|d| d.reason) == Some(DisconnectReason::Ban) {
+                    Session::forget();
+                }
+                self.store.dispatch(StoreAction::Removed);
+                state.dispatch(ChatAction::Disconnected(disconnect));
+            }
+            MsgTypes::Ephemeral => {
+                state.dispatch(ChatAction::EphemeralChanged(
+                    msg.data.and_then(|d| d.parse().ok()).unwrap_or_default(),
+                ));
+            }
+            MsgTypes::AuditEvent => match msg.data.map(|d| serde_json::from_str::<AuditEvent>(&d)) {
+                Some(Ok(event)) => state.dispatch(ChatAction::AuditReceived(event)),
+                Some(Err(e)) => log::error!("bad audit event: {:?}", e),
+                None => {}
+            },
+            MsgTypes::BlockedWords => {
+                state.dispatch(ChatAction::BlockedWordsChanged(
+                    msg.data.and_then(|d| serde_json::from_str(&d).ok()).unwrap_or_default(),
+                ));
+            }
+            MsgTypes::SlowMode => {
+                state.dispatch(ChatAction::SlowModeChanged(
+                    msg.data.and_then(|d| d.parse().ok()).unwrap_or_default(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    fn submit_message(&self) {
+        let state = &self.state;
+        if state.cooldown_remaining(js_sys::Date::now()).is_some() {
+            return;
+        }
+        let input = match self.refs.chat_input.cast::<HtmlInputElement>() {
+            Some(input) => input,
+            None => return,
+        };
+        let value = input.value();
+        let matched = profanity::find_words(&value, &state.blocked_words);
+        if !matched.is_empty() && state.confirmed_blocked.as_ref() != Some(&value) {
+            state.dispatch(ChatAction::BlockedWordsWarning {
+                error: t_with("chat-blocked-words", &[("words", &matched.join(", "))]),
+                text: value,
+            });
+            return;
+        }
+        // Without a connection, plain and direct messages wait in the outbox.
+        if (!offline::online() || self.store.connection != Connection::Connected) && !value.starts_with("/poll ") {
+            let queued = Queued::new(state.dm_target.clone(), value);
+            spawn_local(outbox::push(queued.clone()));
+            input.set_value("");
+            focus::focus(FocusTarget::Composer);
+            return state.dispatch(ChatAction::Queued(queued));
+        }
+        let (sent, text) = if let Some(args) = value.strip_prefix("/poll ") {
+            match parse_poll(args) {
+                Ok(poll) => {
+                    telemetry::feature_used("poll");
+                    (self.send(MsgTypes::Poll, serde_json::to_string(&poll).unwrap()), None)
+                }
+                Err(e) => return state.dispatch(ChatAction::ComposerError(e)),
+            }
+        } else if let Some(to) = state.dm_target.clone() {
+            let direct = DirectMessage { to, message: value.clone() };
+            telemetry::feature_used("direct_message");
+            (self.send(MsgTypes::Direct, serde_json::to_string(&direct).unwrap()), Some(value))
+        } else {
+            (self.send(MsgTypes::Message, value.clone()), Some(value))
+        };
+        // What never left stays in the composer to try again.
+        if !sent {
+            return;
+        }
+        input.set_value("");
+        // Clicking Send leaves focus on the button; carry on typing instead.
+        focus::focus(FocusTarget::Composer);
+        state.dispatch(ChatAction::Sent {
+            text,
+            throttled: !self.my_role().can_moderate(),
+        });
+    }
+
+    fn save_profile(&self) {
+        let display_name = self
+            .refs
+            .display_name_input
+            .cast::<HtmlInputElement>()
+            .map(|input| input.value().trim().to_string());
+        let status = self
+            .refs
+            .status_input
+            .cast::<HtmlInputElement>()
+            .map(|input| input.value().trim().to_string());
+        let bio = self
+            .refs
+            .bio_input
+            .cast::<HtmlTextAreaElement>()
+            .map(|input| input.value().trim().to_string());
+        for (message_type, value) in [
+            (MsgTypes::DisplayName, &display_name),
+            (MsgTypes::Status, &status),
+            (MsgTypes::Profile, &bio),
+        ] {
+            if let Some(value) = value {
+                self.send(message_type, value.clone());
+            }
+        }
+        if let Some(avatar) = self.state.pending_avatar.clone() {
+            self.send(MsgTypes::Avatar, avatar.clone().unwrap_or_default());
+            self.user.dispatch(UserAction::SetAvatar(avatar));
+        }
+        self.state.dispatch(ChatAction::ProfileSaved { display_name, status, bio });
+    }
+
+    fn handle(&self, msg: Msg) {
+        let state = &self.state;
+        match msg {
+            Msg::HandleMsg(s) => self.handle_server_message(&s),
+            Msg::SubmitMessage => self.submit_message(),
+            Msg::VotePoll(poll_id, option) => {
+                let vote = PollVote { poll_id, option };
+                self.send(MsgTypes::Vote, serde_json::to_string(&vote).unwrap());
+            }
+            Msg::ClosePoll(poll_id) => {
+                self.send(MsgTypes::ClosePoll, poll_id.to_string());
+            }
+            Msg::SetEphemeral(seconds) => {
+                self.send(MsgTypes::Ephemeral, seconds.to_string());
+            }
+            Msg::SetSlowMode(seconds) => {
+                self.send(MsgTypes::SlowMode, seconds.to_string());
+            }
+            Msg::ShareLocation => {
+                location::current_position(
+                    self.callback(|(lat, lon)| Msg::SendLocation(lat, lon)),
+                    self.callback(Msg::LocationFailed),
+                );
+            }
+            Msg::SendLocation(latitude, longitude) => {
+                if state.cooldown_remaining(js_sys::Date::now()).is_some() {
+                    return;
+                }
+                let location = Location { latitude, longitude };
+                self.send(MsgTypes::Location, serde_json::to_string(&location).unwrap());
+                telemetry::feature_used("location");
+                state.dispatch(ChatAction::Sent {
+                    text: None,
+                    throttled: !self.my_role().can_moderate(),
+                });
+            }
+            Msg::LocationFailed(error) => state.dispatch(ChatAction::ComposerError(error)),
+            Msg::AddContact(name) => state.dispatch(ChatAction::AddContact(name)),
+            Msg::RemoveContact(name) => state.dispatch(ChatAction::RemoveContact(name)),
+            Msg::OpenDirect(name) => {
+                focus::focus(FocusTarget::Composer);
+                state.dispatch(ChatAction::OpenDirect(name));
+            }
+            Msg::CloseDirect => state.dispatch(ChatAction::CloseDirect),
+            Msg::BlockUser(name) => state.dispatch(ChatAction::BlockUser(name)),
+            Msg::UnblockUser(name) => state.dispatch(ChatAction::UnblockUser(name)),
+            Msg::ShowProfile(name) => state.dispatch(ChatAction::ShowProfile(name)),
+            Msg::HideProfile => state.dispatch(ChatAction::HideProfile),
+            Msg::SaveProfile => self.save_profile(),
+            Msg::SetAvatar(avatar) => state.dispatch(ChatAction::SetPendingAvatar(avatar)),
+            Msg::SetAvatarProvider(provider) => {
+                avatar::set_provider(provider);
+                state.dispatch(ChatAction::AvatarProviderChanged(provider));
+            }
+            Msg::PinMessage(id) => {
+                self.send(MsgTypes::Pin, id.to_string());
+                telemetry::feature_used("pin");
+            }
+            Msg::UnpinMessage => {
+                self.send(MsgTypes::Pin, String::new());
+            }
+            Msg::DeleteMessage(id) => state.dispatch(ChatAction::Confirm(Some(Confirmation::Delete(id)))),
+            Msg::SetRole(name, role) => {
+                let change = RoleChange { name, role };
+                self.send(MsgTypes::Role, serde_json::to_string(&change).unwrap());
+            }
+            Msg::OpenUserMenu(name, x, y) => state.dispatch(ChatAction::OpenUserMenu(name, x, y)),
+            Msg::CloseUserMenu => state.dispatch(ChatAction::CloseUserMenu),
+            Msg::KickUser(name) => {
+                self.send(MsgTypes::Kick, name);
+                state.dispatch(ChatAction::CloseUserMenu);
+            }
+            Msg::BanUser(name) => {
+                state.dispatch(ChatAction::CloseUserMenu);
+                state.dispatch(ChatAction::Confirm(Some(Confirmation::Ban(name))));
+            }
+            Msg::Confirmed => {
+                match state.confirming.clone() {
+                    Some(Confirmation::Delete(id)) => self.send(MsgTypes::Delete, id.to_string()),
+                    Some(Confirmation::Ban(name)) => self.send(MsgTypes::Ban, name),
+                    None => return,
+                };
+                state.dispatch(ChatAction::Confirm(None));
+            }
+            Msg::CancelConfirmation => state.dispatch(ChatAction::Confirm(None)),
+            Msg::ToggleMute(name) => state.dispatch(ChatAction::ToggleMute(name)),
+            Msg::RevealMessage(id) => state.dispatch(ChatAction::RevealMessage(id)),
+            Msg::TranslateMessage(id) => {
+                if state.translations.contains_key(&id) {
+                    state.dispatch(ChatAction::HideTranslation(id));
+                    return;
+                }
+                let text = match self.store.room().messages.iter().find(|m| m.id == Some(id)) {
+                    Some(message) => state.masked_text(message),
+                    None => return,
+                };
+                let target = translate::target(&state.settings.translate_to);
+                state.dispatch(ChatAction::TranslationStarted(id));
+                telemetry::feature_used("translate");
+                let state = state.clone();
+                spawn_local(async move {
+                    let result = translate::translate(&text, &target).await;
+                    state.dispatch(ChatAction::Translated(id, result));
+                });
+            }
+            Msg::ReplyTo(id) => {
+                let message = match self.store.room().messages.iter().find(|m| m.id == Some(id)) {
+                    Some(message) => message.clone(),
+                    None => return,
+                };
+                if message.to.is_some() {
+                    // Answer a direct message privately.
+                    return self.handle(Msg::OpenDirect(message.from.clone()));
+                }
+                if let Some(input) = self.refs.chat_input.cast::<HtmlInputElement>() {
+                    let mention = format!("@{} ", message.from);
+                    if !input.value().starts_with(&mention) {
+                        input.set_value(&format!("{}{}", mention, input.value()));
+                    }
+                }
+                focus::focus(FocusTarget::Composer);
+            }
+            Msg::CopyMessage(id) => {
+                let text = match self.store.room().messages.iter().find(|m| m.id == Some(id)) {
+                    Some(message) => state.plain_text(message),
+                    None => return,
+                };
+                let toasts = self.toasts.clone();
+                spawn_local(async move {
+                    match copy_to_clipboard(&text).await {
+                        Ok(()) => toasts.info(t("chat-copied")),
+                        Err(e) => {
+                            log::error!("failed to copy a message: {:?}", e);
+                            toasts.error(t("chat-copy-failed"));
+                        }
+                    }
+                });
+                telemetry::feature_used("copy");
+            }
+            Msg::ShareMessage(id) => {
+                let text = match self.store.room().messages.iter().find(|m| m.id == Some(id)) {
+                    Some(message) => state.plain_text(message),
+                    None => return,
+                };
+                let url = absolute_url(&permalink(id));
+                let toasts = self.toasts.clone();
+                spawn_local(async move {
+                    let shared = match share(&text, &url) {
+                        Some(shared) => shared.await,
+                        // Browsers without the Web Share API get the link to paste.
+                        None => {
+                            return match copy_to_clipboard(&url).await {
+                                Ok(()) => toasts.info(t("chat-link-copied")),
+                                Err(e) => {
+                                    log::error!("failed to copy a permalink: {:?}", e);
+                                    toasts.error(t("chat-copy-failed"));
+                                }
+                            };
+                        }
+                    };
+                    // Closing the share sheet rejects with `AbortError`; that's not a failure.
+                    if let Err(e) = shared {
+                        if Reflect::get(&e, &"name".into()).ok().and_then(|name| name.as_string()).as_deref() != Some("AbortError") {
+                            log::error!("failed to share a message: {:?}", e);
+                            toasts.error(t("chat-share-failed"));
+                        }
+                    }
+                });
+                telemetry::feature_used("share");
+            }
+            Msg::OpenReport(id) => state.dispatch(ChatAction::OpenReport(id)),
+            Msg::CancelReport => state.dispatch(ChatAction::CancelReport),
+            Msg::SubmitReport(reason) => {
+                if let Some(message_id) = state.reporting {
+                    let report = Report { message_id, reason };
+                    if self.send(MsgTypes::Report, serde_json::to_string(&report).unwrap()) {
+                        telemetry::feature_used("report");
+                        self.toasts.info(t("chat-reported"));
+                        state.dispatch(ChatAction::Reported);
+                    }
+                }
+            }
+            Msg::ToggleProfanityFilter => state.dispatch(ChatAction::ToggleProfanityFilter),
+            Msg::AddFilterWord => {
+                if let Some(input) = self.refs.filter_word_input.cast::<HtmlInputElement>() {
+                    state.dispatch(ChatAction::AddFilterWord(input.value()));
+                    input.set_value("");
+                }
+            }
+            Msg::RemoveFilterWord(word) => state.dispatch(ChatAction::RemoveFilterWord(word)),
+            Msg::AddBlockedWord => {
+                if let Some(input) = self.refs.blocked_word_input.cast::<HtmlInputElement>() {
+                    let word = input.value().trim().to_lowercase();
+                    if !word.is_empty() && !state.blocked_words.contains(&word) {
+                        let mut words = state.blocked_words.clone();
+                        words.push(word);
+                        self.send_blocked_words(words);
+                    }
+                    input.set_value("");
+                }
+            }
+            Msg::RemoveBlockedWord(word) => {
+                let words = state.blocked_words.iter().filter(|w| **w != word).cloned().collect();
+                self.send_blocked_words(words);
+            }
+            Msg::ToggleAuditPanel => state.dispatch(ChatAction::ToggleAuditPanel),
+            Msg::SwitchRoom(step) => {
+                if let Some(room) = self.store.neighbouring_room(step) {
+                    self.store.dispatch(StoreAction::SwitchRoom(room.to_string()));
+                    focus::focus(FocusTarget::Composer);
+                }
+            }
+            Msg::OpenRoom(room) => {
+                self.store.dispatch(StoreAction::SwitchRoom(room));
+                focus::focus(FocusTarget::Composer);
+            }
+            Msg::SetRoomAlerts(room, level) => state.dispatch(ChatAction::SetRoomAlerts(room, level)),
+            Msg::Navigate(route) => {
+                if self.has_unsent() {
+                    state.dispatch(ChatAction::ConfirmLeave(Some(route)));
+                } else if let Some(history) = &self.history {
+                    history.push(route);
+                }
+            }
+            Msg::Leave => {
+                if let (Some(route), Some(history)) = (state.leaving.clone(), &self.history) {
+                    state.dispatch(ChatAction::ConfirmLeave(None));
+                    history.push(route);
+                }
+            }
+            Msg::StayHere => state.dispatch(ChatAction::ConfirmLeave(None)),
+            Msg::AllowNotifications => {
+                state.dispatch(ChatAction::HideNotificationPrompt);
+                let allowed = self.callback(|_: ()| Msg::NotificationsAllowed);
+                spawn_local(async move {
+                    if settings::ask_notification_permission().await {
+                        allowed.emit(());
+                    }
+                });
+            }
+            Msg::NotificationsAllowed => state.dispatch(ChatAction::EnableNotifications),
+            Msg::NotNowNotifications => {
+                onboarding::dismiss_notification_prompt();
+                state.dispatch(ChatAction::HideNotificationPrompt);
+            }
+            Msg::SharePushSubscription(subscription) => {
+                self.send(MsgTypes::Push, subscription);
+            }
+            Msg::OutboxLoaded(queued) => state.dispatch(ChatAction::OutboxLoaded(queued)),
+            Msg::Reconnect => state.dispatch(ChatAction::Reconnect),
+            Msg::SocketFailed(error) => {
+                self.store.dispatch(StoreAction::Disconnect);
+                self.toasts.error(error);
+            }
+            Msg::ToggleShortcuts => state.dispatch(ChatAction::ToggleShortcuts),
+            Msg::TogglePalette => {
+                if !state.palette_visible {
+                    telemetry::feature_used("command_palette");
+                }
+                state.dispatch(ChatAction::TogglePalette);
+            }
+            Msg::ClearComposer => {
+                if let Some(input) = self.refs.chat_input.cast::<HtmlInputElement>() {
+                    input.set_value("");
+                }
+            }
+            Msg::SetAuditActor(actor) => state.dispatch(ChatAction::SetAuditActor(actor)),
+            Msg::SetAuditWindow(seconds) => state.dispatch(ChatAction::SetAuditWindow(seconds)),
+            Msg::Reauthenticate => {
+                // A password sign-in has to show its account token again.
+                let mut frame = WebSocketMessage::new(MsgTypes::Reauth, state.username.clone());
+                frame.data_array = self.user.account_token.clone().map(|token| vec![token]);
+                self.send_frame(frame);
+            }
+            Msg::FocusMessage(id) => {
+                let replaced = gloo::utils::window().history().and_then(|history| {
+                    history.replace_state_with_url(&JsValue::NULL, "", Some(&permalink(id)))
+                });
+                if let Err(e) = replaced {
+                    log::error!("failed to update the address bar: {:?}", e);
+                }
+                state.dispatch(ChatAction::FocusMessage(id));
+            }
+            Msg::SignOut => self.sign_out(None),
+            Msg::ToggleSidebar => state.dispatch(ChatAction::ToggleSidebar),
+            Msg::ResizeSidebar(width) => state.dispatch(ChatAction::ResizeSidebar(width)),
+            Msg::SortUsers(sort) => state.dispatch(ChatAction::SortUsers(sort)),
+            Msg::ToggleReadAloud => {
+                let room = self.store.active_room.clone();
+                if !state.speech.is_enabled(&room) {
+                    telemetry::feature_used("read_aloud");
+                }
+                state.dispatch(ChatAction::ToggleReadAloud(room));
+            }
+            Msg::ToggleSpeechPanel => state.dispatch(ChatAction::ToggleSpeechPanel(speech::voices())),
+            Msg::SetSpeechRate(rate) => state.dispatch(ChatAction::SetSpeechRate(rate)),
+            Msg::SetSpeechVoice(voice) => state.dispatch(ChatAction::SetSpeechVoice(voice)),
+        }
+    }
+}
+
+impl ChatHandler {
+    fn view_reauth_dialog(&self) -> Html {
+        let reauthenticate = self.callback(|_| Msg::Reauthenticate);
+        let sign_out = self.callback(|_| Msg::SignOut);
+
+        html! {
+            <Modal
+                title={t("reauth-title")}
+                description={t("reauth-body")}
+                centered=true
+            >
+                <button onclick={reauthenticate} class="mt-4 w-full py-2 rounded-lg bg-accent hover:bg-accent-dark text-white text-sm">
+                    {t_with("reauth-continue", &[("name", &self.state.username)])}
+                </button>
+                <button onclick={sign_out} class="mt-2 w-full py-2 rounded-lg text-sm text-gray-500 hover:bg-gray-100">
+                    {t("reauth-other-name")}
+                </button>
+            </Modal>
+        }
+    }
+
+    fn view_user_menu(&self, name: &str, x: i32, y: i32) -> Html {
+        let close = self.callback(|_| Msg::CloseUserMenu);
+        let on_context_menu = self.callback(|e: MouseEvent| {
+            e.prevent_default();
+            Msg::CloseUserMenu
+        });
+        let kick = {
+            let name = name.to_string();
+            self.callback(move |_| Msg::KickUser(name.clone()))
+        };
+        let ban = {
+            let name = name.to_string();
+            self.callback(move |_| Msg::BanUser(name.clone()))
+        };
+
+        html! {
+            <div onclick={close} oncontextmenu={on_context_menu} class="fixed inset-0 z-30">
+                <FocusTrap
+                    label={self.display_name_of(name)}
+                    role="menu"
+                    on_close={self.callback(|_: ()| Msg::CloseUserMenu)}
+                    class="absolute w-40 bg-white rounded-lg shadow-lg border border-gray-200 py-1 text-sm"
+                    style={format!("left: {}px; top: {}px;", x, y)}
+                >
+                    <p class="px-3 py-1 text-xs text-gray-400 truncate">{self.display_name_of(name)}</p>
+                    <button onclick={kick} role="menuitem" class="block w-full text-start px-3 py-1.5 text-gray-700 hover:bg-gray-100">{t("menu-kick")}</button>
+                    <button onclick={ban} role="menuitem" class="block w-full text-start px-3 py-1.5 text-red-600 hover:bg-red-50">{t("menu-ban")}</button>
+                </FocusTrap>
+            </div>
+        }
+    }
+
+    fn view_report_dialog(&self) -> Html {
+        let cancel = self.callback(|_| Msg::CancelReport);
+
+        html! {
+            <Modal title={t("report-title")} description={t("report-body")} on_close={self.callback(|_: ()| Msg::CancelReport)}>
+                <div class="mt-4">
+                    {
+                        ReportReason::ALL.iter().map(|reason| {
+                            let reason = *reason;
+                            html! {
+                                <button
+                                    onclick={self.callback(move |_| Msg::SubmitReport(reason))}
+                                    class="block w-full text-start mb-2 px-3 py-2 rounded-lg border border-gray-200 text-sm text-gray-700 hover:border-orange-300 hover:bg-orange-50"
+                                >
+                                    {reason.label()}
+                                </button>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+                <button onclick={cancel} class="mt-2 w-full py-2 rounded-lg text-sm text-gray-500 hover:bg-gray-100">{t("report-cancel")}</button>
+            </Modal>
+        }
+    }
+
+    /// Everything the command palette offers: rooms, people and actions.
+    fn commands(&self, theme: &ThemeContext) -> Vec<Command> {
+        let state = &self.state;
+        let room = &self.store.active_room;
+        let rooms = self.store.rooms.keys().map(|name| {
+            let name = name.clone();
+            Command::new(format!("#{}", name), "palette-room", self.callback(move |_| Msg::OpenRoom(name.clone())))
+        });
+        let people = self
+            .store
+            .users
+            .iter()
+            .filter(|u| u.name != state.username && !state.blocked.contains(&u.name))
+            .flat_map(|u| {
+                let (direct, profile) = (u.name.clone(), u.name.clone());
+                [
+                    Command::new(u.display_name().to_string(), "palette-message", self.callback(move |_| Msg::OpenDirect(direct.clone())))
+                        .keywords(&u.name),
+                    Command::new(u.display_name().to_string(), "palette-profile", self.callback(move |_| Msg::ShowProfile(profile.clone())))
+                        .keywords(&u.name),
+                ]
+            });
+        let toggle_theme = {
+            let theme = theme.clone();
+            Callback::from(move |_| theme.toggle())
+        };
+        let room_alerts = AlertLevel::ALL
+            .into_iter()
+            .filter(|level| *level != state.room_alerts.level(room))
+            .map(|level| {
+                let room = room.clone();
+                Command::new(
+                    t_with(level.command(), &[("room", room.as_str())]),
+                    "palette-action",
+                    self.callback(move |_| Msg::SetRoomAlerts(room.clone(), level)),
+                )
+            });
+        let actions = [
+            Command::new(t(if theme.dark { "header-light-mode" } else { "header-dark-mode" }), "palette-action", toggle_theme),
+            Command::new(
+                t(if state.speech.is_enabled(room) { "header-stop-reading" } else { "header-read-aloud" }),
+                "palette-action",
+                self.callback(|_| Msg::ToggleReadAloud),
+            ),
+            Command::new(t("palette-open-settings"), "palette-action", self.callback(|_| Msg::Navigate(Route::Settings))),
+            Command::new(t("header-shortcuts"), "palette-action", self.callback(|_| Msg::ToggleShortcuts)),
+        ];
+        rooms.chain(people).chain(room_alerts).chain(actions).collect()
+    }
+
+    fn view_confirmation(&self, confirmation: &Confirmation) -> Html {
+        let (title, description, confirm_label) = match confirmation {
+            Confirmation::Delete(_) => (t("confirm-delete-title"), t("confirm-delete-body"), t("confirm-delete")),
+            Confirmation::Ban(name) => {
+                let name = self.display_name_of(name);
+                (
+                    t_with("confirm-ban-title", &[("name", &name)]),
+                    t("confirm-ban-body"),
+                    t("confirm-ban"),
+                )
+            }
+        };
+
+        html! {
+            <ConfirmDialog
+                {title}
+                {description}
+                {confirm_label}
+                destructive=true
+                on_confirm={self.callback(|_: ()| Msg::Confirmed)}
+                on_cancel={self.callback(|_: ()| Msg::CancelConfirmation)}
+            />
+        }
+    }
+
+    fn view_leave_confirmation(&self) -> Html {
+        html! {
+            <ConfirmDialog
+                title={t("confirm-leave-title")}
+                description={t("confirm-leave-body")}
+                confirm_label={t("confirm-leave")}
+                destructive=true
+                on_confirm={self.callback(|_: ()| Msg::Leave)}
+                on_cancel={self.callback(|_: ()| Msg::StayHere)}
+            />
+        }
+    }
+
+    /// Shown while the browser has no network, over the messages kept from before.
+    fn view_offline_banner(&self, colors: &ThemeClasses) -> Html {
+        let retry = self.callback(|_: MouseEvent| Msg::Reconnect);
+        html! {
+            <div role="status" class={classes!("flex", "items-center", "px-6", "py-2", "border-b", "text-sm", colors.border, colors.surface, colors.muted)}>
+                <p class="flex-1">{t("chat-offline-banner")}</p>
+                <button onclick={retry} class={classes!("ms-3", "px-3", "py-1", "rounded-lg", colors.hover)}>
+                    {t("chat-offline-retry")}
+                </button>
+            </div>
+        }
+    }
+
+    /// Messages written offline, listed above the composer until the server has them.
+    fn view_outbox(&self, colors: &ThemeClasses) -> Html {
+        html! {
+            <div role="status" class={classes!("mx-4", "mb-2", "px-4", "py-2", "rounded-lg", "border", "border-dashed", "text-sm", colors.border, colors.muted)}>
+                <p class="font-medium">{t_count("chat-outbox", self.state.outbox.len())}</p>
+                <ul class="mt-1">
+                    {
+                        self.state.outbox.iter().map(|queued| html! {
+                            <li class="truncate">
+                                if let Some(to) = &queued.to {
+                                    <span class="me-1">{t_with("chat-outbox-to", &[("name", &self.display_name_of(to))])}</span>
+                                }
+                                {&queued.message}
+                            </li>
+                        }).collect::<Html>()
+                    }
+                </ul>
+            </div>
+        }
+    }
+
+    /// Says what notifications are for before the browser asks about them.
+    fn view_notification_prompt(&self, colors: &ThemeClasses) -> Html {
+        html! {
+            <div role="region" aria-label={t("notify-prompt-title")} class={classes!("flex", "items-center", "mx-4", "mb-2", "px-4", "py-3", "rounded-lg", "border", "text-sm", colors.border, colors.surface)}>
+                <div class="flex-1">
+                    <p class={classes!("font-medium", colors.text)}>{t("notify-prompt-title")}</p>
+                    <p class={classes!("mt-0.5", colors.muted)}>{t("notify-prompt-body")}</p>
+                </div>
+                <button onclick={self.callback(|_| Msg::NotNowNotifications)} class={classes!("ms-3", "px-3", "py-1.5", "rounded-lg", colors.muted, colors.hover)}>
+                    {t("notify-prompt-not-now")}
+                </button>
+                <button onclick={self.callback(|_| Msg::AllowNotifications)} class="ms-2 px-3 py-1.5 rounded-lg bg-accent text-white hover:bg-accent-dark">
+                    {t("notify-prompt-allow")}
+                </button>
+            </div>
+        }
+    }
+
+    fn view_shortcuts_dialog(&self) -> Html {
+        let close = self.callback(|_| Msg::ToggleShortcuts);
+
+        html! {
+            <Modal title={t("shortcuts-title")} on_close={self.callback(|_: ()| Msg::ToggleShortcuts)}>
+                {
+                    shortcuts::registered().into_iter().map(|(area, shortcuts)| html! {
+                        <section class="mt-4">
+                            <h4 class="text-xs font-semibold uppercase tracking-wide text-gray-500">{area.label()}</h4>
+                            <dl class="mt-1 text-sm">
+                                {
+                                    shortcuts.into_iter().map(|(chord, description)| html! {
+                                        <div class="flex items-center justify-between py-1.5">
+                                            <dt class="text-gray-700">{description}</dt>
+                                            <dd><kbd class="px-2 py-0.5 rounded border border-gray-300 bg-gray-50 font-mono text-xs text-gray-700">{chord}</kbd></dd>
+                                        </div>
+                                    }).collect::<Html>()
+                                }
+                            </dl>
+                        </section>
+                    }).collect::<Html>()
+                }
+                <button onclick={close} class="mt-4 w-full py-2 rounded-lg text-sm text-gray-500 hover:bg-gray-100">{t("shortcuts-close")}</button>
+            </Modal>
+        }
+    }
+
+    fn view_audit_panel(&self) -> Html {
+        let now = js_sys::Date::now();
+        let on_actor_change = self.callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let actor = select.value();
+            Msg::SetAuditActor(if actor.is_empty() { None } else { Some(actor) })
+        });
+        let on_window_change = self.batch_callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            select.value().parse::<u64>().ok().map(Msg::SetAuditWindow)
+        });
+        let actors: BTreeSet<&String> = self.state.audit_log.iter().map(|e| &e.actor).collect();
+        let events: Vec<&AuditEvent> = self
+            .state
+            .audit_log
+            .iter()
+            .rev()
+            .filter(|e| self.state.audit_actor.as_ref().is_none_or(|actor| &e.actor == actor))
+            .filter(|e| self.state.audit_window == 0 || now - e.time <= self.state.audit_window as f64 * 1000.0)
+            .collect();
+
+        html! {
+            <Modal
+                title={t("audit-title")}
+                close_button=true
+                class={classes!("bg-white", "w-full", "max-w-lg", "p-6")}
+                on_close={self.callback(|_: ()| Msg::ToggleAuditPanel)}
+            >
+                <div class="flex mt-3 text-sm">
+                    <select onchange={on_actor_change} class="px-2 py-1 bg-gray-100 rounded">
+                        <option value="" selected={self.state.audit_actor.is_none()}>{t("audit-all-moderators")}</option>
+                        {
+                            actors.into_iter().map(|actor| html! {
+                                <option value={actor.clone()} selected={self.state.audit_actor.as_ref() == Some(actor)}>{self.display_name_of(actor)}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                    <select onchange={on_window_change} class="ms-2 px-2 py-1 bg-gray-100 rounded">
+                        {
+                            AUDIT_WINDOWS.iter().map(|(seconds, label)| html! {
+                                <option value={seconds.to_string()} selected={*seconds == self.state.audit_window}>{t(label)}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                </div>
+                <div class="mt-3 max-h-96 overflow-y-auto divide-y divide-gray-100">
+                    if events.is_empty() {
+                        <p class="py-6 text-center text-sm text-gray-400">{t("audit-empty")}</p>
+                    }
+                    {
+                        events.into_iter().map(|event| html! {
+                            <div class="py-2 text-sm">
+                                <div class="flex justify-between">
+                                    <span class="text-gray-800">
+                                        <span class="font-medium">{self.display_name_of(&event.actor)}</span>
+                                        {format!(" {} ", event.action.verb())}
+                                        <span class="font-medium">{event.target.as_deref().map(|t| self.display_name_of(t)).unwrap_or_default()}</span>
+                                    </span>
+                                    <span class="ms-2 flex-shrink-0 text-xs text-gray-400">{time::relative(now - event.time)}</span>
+                                </div>
+                                if let Some(detail) = &event.detail {
+                                    <p class="text-xs text-gray-500 truncate">{format!("“{}”", detail)}</p>
+                                }
+                            </div>
+                        }).collect::<Html>()
+                    }
+                </div>
+            </Modal>
+        }
+    }
+
+    fn view_disconnected(&self, disconnect: &Disconnect) -> Html {
+        let (title, detail) = match disconnect.reason {
+            DisconnectReason::Kick => (t("removed-kick-title"), t("removed-kick-body")),
+            DisconnectReason::Ban => (t("removed-ban-title"), t("removed-ban-body")),
+        };
+
+        html! {
+            <div class="flex h-screen w-full items-center justify-center bg-gray-50">
+                <div class="max-w-md bg-white rounded-xl shadow-lg p-8 text-center">
+                    <svg xmlns="http://www.w3.org/2000/svg" class="h-12 w-12 mx-auto mb-4 text-red-400" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M18.364 18.364A9 9 0 005.636 5.636m12.728 12.728A9 9 0 015.636 5.636m12.728 12.728L5.636 5.636" />
+                    </svg>
+                    <h2 class="text-xl font-semibold text-gray-800">{title}</h2>
+                    <p class="mt-2 text-gray-600">{detail}</p>
+                    if let Some(by) = &disconnect.by {
+                        <p class="mt-1 text-sm text-gray-400">{t_with("removed-by", &[("name", &self.display_name_of(by))])}</p>
+                    }
+                    <Link<Route> to={Route::Login} classes="inline-block mt-6 px-4 py-2 rounded-lg bg-accent hover:bg-accent-dark text-white text-sm">
+                        {t("removed-back")}
+                    </Link<Route>>
+                </div>
+            </div>
+        }
+    }
+
+    fn view_profile_card(&self, name: &str) -> Html {
+        let default_profile = UserProfile {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let user = self.store.users.iter().find(|u| u.name == name).map(Rc::as_ref).unwrap_or(&default_profile);
+        let online = self.store.users.iter().any(|u| u.name == name);
+        let is_self = name == self.state.username;
+        let save_profile = self.callback(|_| Msg::SaveProfile);
+        let on_provider_change = self.batch_callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            AvatarProvider::ALL
+                .into_iter()
+                .find(|p| p.label() == select.value())
+                .map(Msg::SetAvatarProvider)
+        });
+        let open_direct = {
+            let name = name.to_string();
+            self.callback(move |_| Msg::OpenDirect(name.clone()))
+        };
+        let block = {
+            let name = name.to_string();
+            self.callback(move |_| Msg::BlockUser(name.clone()))
+        };
+        let toggle_mute = {
+            let name = name.to_string();
+            self.callback(move |_| Msg::ToggleMute(name.clone()))
+        };
+        let toggle_moderator = {
+            let name = name.to_string();
+            let role = if user.role == Role::Moderator { Role::Member } else { Role::Moderator };
+            self.callback(move |_| Msg::SetRole(name.clone(), role))
+        };
+
+        html! {
+            <Modal
+                title={user.display_name().to_string()}
+                hide_title=true
+                close_button=true
+                on_close={self.callback(|_: ()| Msg::HideProfile)}
+            >
+                <div class="flex flex-col items-center">
+                    <Avatar name={user.name.clone()} src={user.avatar()} class="w-20 h-20 rounded-full border-2 border-white shadow text-2xl"/>
+                    <h3 dir="auto" class="mt-3 text-lg font-semibold text-gray-800">{user.display_name()}</h3>
+                    <p class="text-xs text-gray-400">
+                        {format!("@{}", user.name)}
+                        { view_role_badge(user.role) }
+                    </p>
+                    if let Some(joined_at) = user.joined_at {
+                        <p class="text-xs text-gray-500">
+                            {t_with("user-joined-at", &[("time", &time::clock_time(joined_at))])}
+                        </p>
+                    } else if !online {
+                        <p class="text-xs text-gray-500">{last_seen_label(&self.store.last_seen, name)}</p>
+                    }
+                    if let Some(status) = user.status.as_ref().filter(|_| !is_self) {
+                        <p dir="auto" class="mt-1 text-sm text-gray-600 italic">{status.clone()}</p>
+                    }
+                </div>
+                if is_self {
+                    <div class="mt-4">
+                        <AvatarPicker
+                            name={self.state.username.clone()}
+                            value={self.state.pending_avatar.clone().unwrap_or_else(|| self.user.avatar.clone())}
+                            on_change={self.callback(Msg::SetAvatar)}
+                        />
+                        <label class="flex items-center justify-between mt-3 text-sm text-gray-600">
+                            {t("user-generated-avatars")}
+                            <select onchange={on_provider_change} class="ms-2 px-2 py-1 bg-gray-100 rounded">
+                                {
+                                    AvatarProvider::ALL.iter().map(|p| html! {
+                                        <option value={p.label()} selected={*p == self.state.avatar_provider}>{p.label()}</option>
+                                    }).collect::<Html>()
+                                }
+                            </select>
+                        </label>
+                    </div>
+                    <input
+                        ref={self.refs.display_name_input.clone()}
+                        type="text"
+                        dir="auto"
+                        value={user.display_name.clone().unwrap_or_default()}
+                        placeholder={t("settings-display-name")}
+                        maxlength="32"
+                        class="mt-4 w-full px-3 py-2 text-sm bg-gray-100 rounded-lg outline-none focus:ring-2 focus:ring-blue-400 focus:bg-white"
+                    />
+                    <input
+                        ref={self.refs.status_input.clone()}
+                        type="text"
+                        dir="auto"
+                        value={user.status.clone().unwrap_or_default()}
+                        placeholder={t("settings-status")}
+                        maxlength="60"
+                        class="mt-2 w-full px-3 py-2 text-sm bg-gray-100 rounded-lg outline-none focus:ring-2 focus:ring-blue-400 focus:bg-white"
+                    />
+                    <textarea
+                        ref={self.refs.bio_input.clone()}
+                        dir="auto"
+                        value={user.bio.clone().unwrap_or_default()}
+                        placeholder={t("settings-bio")}
+                        maxlength="160"
+                        class="mt-2 w-full h-20 px-3 py-2 text-sm bg-gray-100 rounded-lg outline-none focus:ring-2 focus:ring-blue-400 focus:bg-white resize-none"
+                    />
+                    <button onclick={save_profile} class="mt-2 w-full py-2 rounded-lg bg-accent hover:bg-accent-dark text-white text-sm">{t("user-save-profile")}</button>
+                } else {
+                    <p dir="auto" class="mt-4 text-sm text-center text-gray-600">
+                        {user.bio.clone().unwrap_or_else(|| t("user-no-bio"))}
+                    </p>
+                    <div class="mt-4 flex">
+                        <button
+                            onclick={open_direct}
+                            disabled={!online}
+                            class="flex-1 py-2 rounded-lg bg-accent hover:bg-accent-dark text-white text-sm disabled:opacity-50 disabled:cursor-not-allowed"
+                        >
+                            {t("user-message")}
+                        </button>
+                        <button onclick={toggle_mute} class="flex-1 ms-2 py-2 rounded-lg border border-gray-300 text-gray-700 hover:bg-gray-50 text-sm">
+                            {t(if self.state.muted.contains(name) { "user-unmute" } else { "user-mute" })}
+                        </button>
+                        <button onclick={block} class="flex-1 ms-2 py-2 rounded-lg border border-red-300 text-red-500 hover:bg-red-50 text-sm">
+                            {t("user-block")}
+                        </button>
+                    </div>
+                    if self.my_role() >= Role::Owner && user.role < Role::Owner && online {
+                        <button onclick={toggle_moderator} class="mt-2 w-full py-2 rounded-lg border border-gray-300 text-gray-700 hover:bg-gray-50 text-sm">
+                            {t(if user.role == Role::Moderator { "user-remove-moderator" } else { "user-make-moderator" })}
+                        </button>
+                    }
+                }
+            </Modal>
+        }
+    }
+}
+
+/// Time ranges for the moderation log, in seconds, with the keys of their labels.
+const AUDIT_WINDOWS: [(u64, &str); 4] = [
+    (0, "audit-any-time"),
+    (3600, "audit-last-hour"),
+    (86400, "audit-last-day"),
+    (604800, "audit-last-week"),
+];
+
+/// Splits a command line into words, treating double-quoted runs as a single word.
+fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+fn parse_poll(args: &str) -> Result<NewPoll, String> {
+    let mut words = split_args(args).into_iter();
+    let question = words
+        .next()
+        .ok_or_else(|| t("poll-usage"))?;
+    let options: Vec<String> = words.collect();
+    if options.len() < 2 {
+        return Err(t("poll-too-few-options"));
+    }
+    Ok(NewPoll { question, options })
+}
+
+/// Writes `value` back to local storage whenever it changes.
+fn use_persisted<T: Clone + PartialEq + 'static>(value: &T, save: fn(&T)) {
+    use_effect_with_deps(
+        move |value| {
+            save(value);
+            || ()
+        },
+        value.clone(),
+    );
+}
+
+/// Where a click on a link within the app would go. Clicks that open a new
+/// tab or window leave the chat where it is, so they don't count.
+fn in_app_link(e: &Event) -> Option<Route> {
+    let click = e.dyn_ref::<MouseEvent>()?;
+    if click.button() != 0 || click.ctrl_key() || click.meta_key() || click.shift_key() || click.alt_key() {
+        return None;
+    }
+    let link = e.target()?.dyn_into::<Element>().ok()?.closest("a[href]").ok()??;
+    if link.get_attribute("target").is_some_and(|target| target != "_self") {
+        return None;
+    }
+    let href = link.get_attribute("href")?;
+    if !href.starts_with('/') || href.starts_with("//") {
+        return None;
+    }
+    Route::recognize(href.split(['?', '#']).next()?)
+}
+
+/// The chat room. It connects when it mounts and hangs up when it unmounts.
+#[function_component(Chat)]
+pub fn chat() -> Html {
+    let user = use_context::<User>().expect("context to be set");
+    let store = use_context::<ChatStore>().expect("chat store to be provided");
+    let theme = use_theme();
+    let history = use_history();
+    let location = use_location();
+    let state = {
+        let username = user.username.clone();
+        use_reducer_eq(move || {
+            // The profile page opens a conversation with `/chat?dm=<name>`.
+            let dm_target = location
+                .and_then(|location| location.query::<DirectLink>().ok())
+                .and_then(|link| link.dm);
+            ChatState::new(username, dm_target)
+        })
+    };
+    let refs = ChatRefs {
+        chat_input: use_node_ref(),
+        filter_word_input: use_node_ref(),
+        blocked_word_input: use_node_ref(),
+        display_name_input: use_node_ref(),
+        status_input: use_node_ref(),
+        bio_input: use_node_ref(),
+    };
+    let socket = use_mut_ref(|| None::<WebsocketService>);
+    let handler = ChatHandler {
+        state: state.clone(),
+        store: store.clone(),
+        user: user.clone(),
+        history,
+        reporter: use_context::<ErrorReporter>(),
+        socket: socket.clone(),
+        incoming: use_incoming_messages(),
+        refs: refs.clone(),
+        toasts: use_toast(),
+    };
+
+    {
+        let handler = handler.clone();
+        use_bridge::<EventBus, _>(move |s| handler.handle(Msg::HandleMsg(s)));
+    }
+
+    // Bubbles only re-render when their props change, and a callback equals
+    // only itself, so every bubble gets the same one for the life of the
+    // chat. It hands each action to the handler of the latest render.
+    let latest_handler = use_mut_ref(|| None::<ChatHandler>);
+    *latest_handler.borrow_mut() = Some(handler.clone());
+    let on_message_action = (*use_state(move || {
+        Callback::from(move |action: MessageAction| {
+            let handler = latest_handler.borrow().clone();
+            if let Some(handler) = handler {
+                handler.handle(Msg::from(action));
+            }
+        })
+    }))
+    .clone();
+
+    // Connect and register on mount, and again on each reconnect; dropping
+    // the service closes the socket.
+    {
+        let user = user.clone();
+        let store = store.clone();
+        let on_failure = handler.callback(Msg::SocketFailed);
+        use_effect_with_deps(
+            move |_| {
+                store.dispatch(StoreAction::Connect);
+                sound::preload();
+                if onboarding::first_visit(DEFAULT_ROOM) {
+                    store.dispatch(StoreAction::Notice(onboarding::welcome_text()));
+                }
+                // Without a network the socket won't get far; show what this device kept.
+                if !offline::online() {
+                    store.dispatch(StoreAction::HistoryReceived(offline::cached_history(DEFAULT_ROOM)));
+                }
+                let wss = WebsocketService::new(on_failure);
+                let message = WebSocketMessage {
+                    message_type: MsgTypes::Register,
+                    data: Some(user.username.clone()),
+                    // The chosen avatar rides along so others never see the generated one
+                    // first, followed by the CAPTCHA token from the login screen and the
+                    // token proving a password sign-in.
+                    data_array: match (user.avatar.clone(), user.captcha.clone(), user.account_token.clone()) {
+                        (None, None, None) => None,
+                        (avatar, captcha, token) => Some(vec![
+                            avatar.unwrap_or_default(),
+                            captcha.unwrap_or_default(),
+                            token.unwrap_or_default(),
+                        ]),
+                    },
+                };
+                if user.captcha.is_some() {
+                    user.dispatch(UserAction::SpendCaptcha);
+                }
+                if wss.tx.clone().try_send(serde_json::to_string(&message).unwrap()).is_ok() {
+                    log::debug!("message sent successfully");
+                }
+                *socket.borrow_mut() = Some(wss);
+                move || {
+                    drop(socket.borrow_mut().take());
+                    store.dispatch(StoreAction::Disconnect);
+                }
+            },
+            state.connect_attempt,
+        );
+    }
+
+    // Messages written offline in an earlier visit are still waiting.
+    {
+        let loaded = handler.callback(Msg::OutboxLoaded);
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move { loaded.emit(outbox::queued().await) });
+                || ()
+            },
+            (),
+        );
+    }
+
+    {
+        let (dispatcher, store) = (state.dispatcher(), store.dispatcher());
+        use_effect_with_deps(
+            move |_| {
+                let ticker = Interval::new(1000, move || {
+                    store.dispatch(StoreAction::Tick(js_sys::Date::now()));
+                    dispatcher.dispatch(ChatAction::Tick);
+                });
+                move || drop(ticker)
+            },
+            (),
+        );
+    }
+
+    // Ready to type on arrival, and where focus returns once dialogs close.
+    focus::use_focus_target(FocusTarget::Composer, &refs.chat_input, true);
+
+    {
+        let chat_input = refs.chat_input.clone();
+        use_effect_with_deps(
+            move |_| {
+                if let (Some(draft), Some(input)) = (
+                    session::take_draft(DEFAULT_ROOM),
+                    chat_input.cast::<HtmlInputElement>(),
+                ) {
+                    input.set_value(&draft);
+                }
+                || ()
+            },
+            (),
+        );
+    }
+
+    {
+        let handler = handler.clone();
+        use_effect_with_deps(
+            move |reauth_visible| {
+                if *reauth_visible {
+                    handler.keep_draft();
+                }
+                || ()
+            },
+            state.reauth_visible,
+        );
+    }
+
+    // Ask before a reload, a closed tab or an in-app link loses what's unsent.
+    {
+        let handler = handler.clone();
+        use_effect_with_deps(
+            move |_| {
+                let unload = {
+                    let handler = handler.clone();
+                    EventListener::new_with_options(
+                        &gloo::utils::window(),
+                        "beforeunload",
+                        EventListenerOptions::enable_prevent_default(),
+                        move |e| {
+                            if handler.has_unsent() {
+                                e.prevent_default();
+                                // Some browsers only ask once a return value is set.
+                                let _ = Reflect::set(e, &"returnValue".into(), &"".into());
+                            }
+                        },
+                    )
+                };
+                // Before the router's own handler, so the click can be held back.
+                let links = EventListener::new_with_options(
+                    &gloo::utils::document(),
+                    "click",
+                    EventListenerOptions {
+                        phase: EventListenerPhase::Capture,
+                        passive: false,
+                    },
+                    move |e| {
+                        if let Some(route) = in_app_link(e).filter(|_| handler.has_unsent()) {
+                            e.prevent_default();
+                            e.stop_propagation();
+                            handler.handle(Msg::Navigate(route));
+                        }
+                    },
+                );
+                move || drop((unload, links))
+            },
+            (state.settings.warn_unsent, state.pending_avatar.is_some()),
+        );
+    }
+
+    use_persisted(&state.contacts, NameList::save);
+    use_persisted(&state.blocked, NameList::save);
+    use_persisted(&state.muted, NameList::save);
+    use_persisted(&state.room_alerts, RoomAlerts::save);
+    use_persisted(&state.speech, SpeechSettings::save);
+    use_persisted(&state.profanity, ProfanityFilter::save);
+    use_persisted(&state.settings, SettingsStore::save);
+    use_persisted(&state.layout, Layout::save);
+
+    use_shortcut(Chord::ctrl("k"), ShortcutArea::General, "shortcut-palette", handler.callback(|_| Msg::TogglePalette));
+    use_shortcut(Chord::ctrl("/"), ShortcutArea::General, "shortcut-help", handler.callback(|_| Msg::ToggleShortcuts));
+    use_shortcut(Chord::key("Escape"), ShortcutArea::General, "shortcut-close", handler.callback(|_| Msg::ClearComposer));
+    use_shortcut(Chord::alt("ArrowUp"), ShortcutArea::Rooms, "shortcut-previous-room", handler.callback(|_| Msg::SwitchRoom(-1)));
+    use_shortcut(Chord::alt("ArrowDown"), ShortcutArea::Rooms, "shortcut-next-room", handler.callback(|_| Msg::SwitchRoom(1)));
+    // Escape closes these before clearing the composer, newest first along
+    // with the dialogs and menus, which put themselves on the stack.
+    use_dismissable(
+        state.layout.sidebar_visible && layout::narrow_screen(),
+        handler.callback(|_: ()| Msg::ToggleSidebar),
+    );
+    use_dismissable(state.speech_panel_visible, handler.callback(|_: ()| Msg::ToggleSpeechPanel));
+
+    // Count what arrives while the tab is in the background in its title.
+    title::use_unread_title(&store, &state.room_alerts);
+
+    // Keep the newest messages for when there is no network.
+    {
+        let deps = (store.active_room.clone(), store.room().messages.len(), store.room().messages.last().and_then(|m| m.id));
+        let store = store.clone();
+        use_effect_with_deps(
+            move |_| {
+                offline::keep_history(&store.active_room, &store.room().messages);
+                || ()
+            },
+            deps,
+        );
+    }
+    let online = offline::use_online();
+    // Coming back online after starting without a network: try the server again.
+    {
+        let handler = handler.clone();
+        let was_online = use_mut_ref(|| online);
+        let connected = store.connection == Connection::Connected;
+        use_effect_with_deps(
+            move |&online| {
+                let came_back = online && !*was_online.borrow();
+                *was_online.borrow_mut() = online;
+                if came_back && !connected {
+                    handler.handle(Msg::Reconnect);
+                }
+                || ()
+            },
+            online,
+        );
+    }
+
+    // Scroll to a permalinked message as soon as it has been rendered.
+    {
+        let state = state.clone();
+        use_effect(move || {
+            if let Some(id) = state.pending_anchor {
+                if let Some(element) = gloo::utils::document().get_element_by_id(&message_anchor(id)) {
+                    let options = ScrollIntoViewOptions::new();
+                    options.set_behavior(if theme::reduced_motion() { ScrollBehavior::Auto } else { ScrollBehavior::Smooth });
+                    options.set_block(ScrollLogicalPosition::Center);
+                    element.scroll_into_view_with_scroll_into_view_options(&options);
+                    state.dispatch(ChatAction::AnchorReached(id));
+                }
+            }
+            || ()
+        });
+    }
+
+    if let Some(disconnect) = &state.disconnected {
+        return handler.view_disconnected(disconnect);
+    }
+    let role = handler.my_role();
+    let now = js_sys::Date::now();
+
+    html! {
+        <div class={classes!("themed", "flex", "h-screen", "w-full", theme.classes().page)}>
+            <UserSidebar
+                visible={state.layout.sidebar_visible}
+                width={state.layout.sidebar_width}
+                sort={state.settings.user_sort}
+                username={state.username.clone()}
+                role={role}
+                contacts={state.contacts.clone()}
+                blocked={state.blocked.clone()}
+                muted={state.muted.clone()}
+                on_show_profile={handler.callback(Msg::ShowProfile)}
+                on_open_menu={handler.callback(|(name, x, y)| Msg::OpenUserMenu(name, x, y))}
+                on_open_direct={handler.callback(Msg::OpenDirect)}
+                on_add_contact={handler.callback(Msg::AddContact)}
+                on_remove_contact={handler.callback(Msg::RemoveContact)}
+                on_block={handler.callback(Msg::BlockUser)}
+                on_unblock={handler.callback(Msg::UnblockUser)}
+                on_resize={handler.callback(Msg::ResizeSidebar)}
+                on_sort={handler.callback(Msg::SortUsers)}
+            />
+
+            <div class="flex-1 flex flex-col w-full">
+                <ChatHeader
+                    username={state.username.clone()}
+                    role={role}
+                    ephemeral_seconds={state.ephemeral_seconds}
+                    slow_mode_seconds={state.slow_mode_seconds}
+                    read_aloud={state.speech.is_enabled(&store.active_room)}
+                    speech_panel_visible={state.speech_panel_visible}
+                    speech={state.speech.clone()}
+                    voices={state.voices.clone()}
+                    profanity={state.profanity.clone()}
+                    blocked_words={state.blocked_words.clone()}
+                    filter_word_input={refs.filter_word_input.clone()}
+                    blocked_word_input={refs.blocked_word_input.clone()}
+                    on_toggle_sidebar={handler.callback(|_| Msg::ToggleSidebar)}
+                    on_sign_out={handler.callback(|_| Msg::SignOut)}
+                    on_set_ephemeral={handler.callback(Msg::SetEphemeral)}
+                    on_set_slow_mode={handler.callback(Msg::SetSlowMode)}
+                    on_toggle_audit_panel={handler.callback(|_| Msg::ToggleAuditPanel)}
+                    on_show_shortcuts={handler.callback(|_| Msg::ToggleShortcuts)}
+                    on_toggle_read_aloud={handler.callback(|_| Msg::ToggleReadAloud)}
+                    on_toggle_speech_panel={handler.callback(|_| Msg::ToggleSpeechPanel)}
+                    on_set_speech_rate={handler.callback(Msg::SetSpeechRate)}
+                    on_set_speech_voice={handler.callback(Msg::SetSpeechVoice)}
+                    on_toggle_profanity_filter={handler.callback(|_| Msg::ToggleProfanityFilter)}
+                    on_add_filter_word={handler.callback(|_| Msg::AddFilterWord)}
+                    on_remove_filter_word={handler.callback(Msg::RemoveFilterWord)}
+                    on_add_blocked_word={handler.callback(|_| Msg::AddBlockedWord)}
+                    on_remove_blocked_word={handler.callback(Msg::RemoveBlockedWord)}
+                />
+                if !online {
+                    { handler.view_offline_banner(theme.classes()) }
+                }
+                <MessageList
+                    username={state.username.clone()}
+                    role={role}
+                    blocked={state.blocked.clone()}
+                    muted={state.muted.clone()}
+                    revealed={state.revealed.clone()}
+                    highlighted={state.highlighted.map(|(id, _)| id)}
+                    profanity={state.profanity.clone()}
+                    blocked_words={state.blocked_words.clone()}
+                    autoload_media={state.settings.autoload_media}
+                    density={state.settings.density}
+                    translations={state.translations.clone()}
+                    on_action={on_message_action}
+                />
+                if !state.outbox.is_empty() {
+                    { handler.view_outbox(theme.classes()) }
+                }
+                if state.notification_prompt {
+                    { handler.view_notification_prompt(theme.classes()) }
+                }
+                <MessageComposer
+                    input_ref={refs.chat_input.clone()}
+                    error={state.composer_error.clone()}
+                    direct_to={state.dm_target.as_deref().map(|to| handler.display_name_of(to))}
+                    cooldown={state.cooldown_remaining(now)}
+                    on_submit={handler.callback(|_| Msg::SubmitMessage)}
+                    on_share_location={handler.callback(|_| Msg::ShareLocation)}
+                    on_close_direct={handler.callback(|_| Msg::CloseDirect)}
+                />
+            </div>
+
+            if let Some(name) = &state.profile_card {
+                { handler.view_profile_card(name) }
+            }
+            if let Some((name, x, y)) = &state.user_menu {
+                { handler.view_user_menu(name, *x, *y) }
+            }
+            if state.reporting.is_some() {
+                { handler.view_report_dialog() }
+            }
+            if state.audit_panel_visible {
+                { handler.view_audit_panel() }
+            }
+            if state.shortcuts_visible {
+                { handler.view_shortcuts_dialog() }
+            }
+            if let Some(confirmation) = &state.confirming {
+                { handler.view_confirmation(confirmation) }
+            }
+            if state.leaving.is_some() {
+                { handler.view_leave_confirmation() }
+            }
+            if state.palette_visible {
+                <CommandPalette commands={handler.commands(&theme)} on_close={handler.callback(|_: ()| Msg::TogglePalette)}/>
+            }
+            if state.reauth_visible {
+                { handler.view_reauth_dialog() }
+            }
+            <div aria-live="polite" aria-relevant="additions" class="sr-only">
+                { for state.announcements.iter().map(|(id, text)| html! { <p key={*id}>{text.clone()}</p> }) }
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_args_keeps_quoted_runs_together() {
+        let cases: &[(&str, &[&str])] = &[
+            (r#""Lunch?" Noodles Pizza"#, &["Lunch?", "Noodles", "Pizza"]),
+            (r#""Where to?" "Noodle bar" "Pizza place""#, &["Where to?", "Noodle bar", "Pizza place"]),
+            ("  spaced   out  ", &["spaced", "out"]),
+            (r#""" a """#, &["a"]),
+            (r#""""#, &[]),
+            (r#""never closed"#, &["never closed"]),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(split_args(input), *expected, "splitting {:?}", input);
+        }
+    }
+
+    /// The question and options, or the key of the error.
+    type ParsedPoll = Result<(&'static str, &'static [&'static str]), &'static str>;
+
+    #[test]
+    fn parse_poll_takes_a_question_and_at_least_two_options() {
+        let cases: &[(&str, ParsedPoll)] = &[
+            (r#""Lunch?" Noodles Pizza"#, Ok(("Lunch?", &["Noodles", "Pizza"]))),
+            (r#""Where to?" "Noodle bar" "Pizza place""#, Ok(("Where to?", &["Noodle bar", "Pizza place"]))),
+            (r#""Lunch?" Noodles "" Pizza"#, Ok(("Lunch?", &["Noodles", "Pizza"]))),
+            ("", Err("poll-usage")),
+            (r#""Lunch?""#, Err("poll-too-few-options")),
+            (r#""Lunch?" Noodles"#, Err("poll-too-few-options")),
+            (r#""Lunch?" "" """#, Err("poll-too-few-options")),
+        ];
+        for (input, expected) in cases {
+            let parsed = parse_poll(input);
+            match expected {
+                Ok((question, options)) => {
+                    let poll = parsed.unwrap_or_else(|e| panic!("parsing {:?}: {}", input, e));
+                    assert_eq!(poll.question, *question, "parsing {:?}", input);
+                    assert_eq!(poll.options, *options, "parsing {:?}", input);
+                }
+                Err(key) => assert_eq!(parsed.err(), Some(t(key)), "parsing {:?}", input),
+            }
+        }
+    }
+}
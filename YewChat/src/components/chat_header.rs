@@ -0,0 +1,350 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::chat::Role;
+use crate::components::theme_provider::use_theme;
+use crate::services::chat_store::{ChatStore, Connection};
+use crate::services::i18n::{self, t, t_count, t_with};
+use crate::services::install_prompt::use_install_prompt;
+use crate::services::profanity::ProfanityFilter;
+use crate::services::speech::SpeechSettings;
+use crate::Route;
+
+/// Disappearing-message durations offered in the header, in seconds, with
+/// the keys of their labels.
+const EPHEMERAL_OPTIONS: [(u64, &str); 5] = [
+    (0, "header-ephemeral-off"),
+    (30, "header-ephemeral-30s"),
+    (300, "header-ephemeral-5m"),
+    (3600, "header-ephemeral-1h"),
+    (86400, "header-ephemeral-1d"),
+];
+
+const SLOW_MODE_OPTIONS: [(u64, &str); 5] = [
+    (0, "header-slow-mode-off"),
+    (5, "header-slow-mode-5s"),
+    (10, "header-slow-mode-10s"),
+    (30, "header-slow-mode-30s"),
+    (60, "header-slow-mode-1m"),
+];
+
+#[derive(Default, Properties, PartialEq)]
+pub struct ChatHeaderProps {
+    pub username: String,
+    pub role: Role,
+    pub ephemeral_seconds: u64,
+    pub slow_mode_seconds: u64,
+    pub read_aloud: bool,
+    pub speech_panel_visible: bool,
+    pub speech: SpeechSettings,
+    /// Names of the voices the browser can read aloud with.
+    pub voices: Vec<String>,
+    pub profanity: ProfanityFilter,
+    pub blocked_words: Vec<String>,
+    /// Inputs for new filter and blocked words, read by the parent on add.
+    pub filter_word_input: NodeRef,
+    pub blocked_word_input: NodeRef,
+    pub on_toggle_sidebar: Callback<()>,
+    pub on_sign_out: Callback<()>,
+    pub on_set_ephemeral: Callback<u64>,
+    pub on_set_slow_mode: Callback<u64>,
+    pub on_toggle_audit_panel: Callback<()>,
+    pub on_show_shortcuts: Callback<()>,
+    pub on_toggle_read_aloud: Callback<()>,
+    pub on_toggle_speech_panel: Callback<()>,
+    pub on_set_speech_rate: Callback<f32>,
+    pub on_set_speech_voice: Callback<Option<String>>,
+    pub on_toggle_profanity_filter: Callback<()>,
+    pub on_add_filter_word: Callback<()>,
+    pub on_remove_filter_word: Callback<String>,
+    pub on_add_blocked_word: Callback<()>,
+    pub on_remove_blocked_word: Callback<String>,
+}
+
+/// `Enter` in a text field does the same as the button next to it.
+fn on_enter(callback: &Callback<()>) -> Callback<KeyboardEvent> {
+    let callback = callback.clone();
+    Callback::from(move |e: KeyboardEvent| {
+        if e.key() == "Enter" {
+            callback.emit(());
+        }
+    })
+}
+
+/// Parses the chosen `<option>` and passes it on if it is valid.
+fn on_select<T: std::str::FromStr + 'static>(callback: &Callback<T>) -> Callback<Event> {
+    let callback = callback.clone();
+    Callback::from(move |e: Event| {
+        let select: HtmlSelectElement = e.target_unchecked_into();
+        if let Ok(value) = select.value().parse() {
+            callback.emit(value);
+        }
+    })
+}
+
+fn view_word_chip(word: &str, on_remove: &Callback<String>, colors: &'static str, button_colors: &'static str) -> Html {
+    let remove = {
+        let word = word.to_string();
+        on_remove.reform(move |_| word.clone())
+    };
+    html! {
+        <span class={classes!("inline-flex", "items-center", "me-1", "mb-1", "px-2", "py-0.5", "rounded-full", "text-xs", colors)}>
+            {word.to_string()}
+            <button onclick={remove} title={t("header-remove-word")} aria-label={t("header-remove-word")} class={classes!("ms-1", button_colors)}>{"✕"}</button>
+        </span>
+    }
+}
+
+/// Speech, profanity filter and blocked word settings under the header's gear button.
+fn view_settings_popover(props: &ChatHeaderProps) -> Html {
+    let on_rate_change = {
+        let callback = props.on_set_speech_rate.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(rate) = input.value().parse::<f32>() {
+                callback.emit(rate);
+            }
+        })
+    };
+    let on_voice_change = props.on_set_speech_voice.reform(|e: Event| {
+        let select: HtmlSelectElement = e.target_unchecked_into();
+        let voice = select.value();
+        if voice.is_empty() { None } else { Some(voice) }
+    });
+
+    html! {
+        <div class="absolute end-0 top-12 z-10 w-64 bg-white rounded-lg shadow-lg border border-gray-200 p-4">
+            <label class="block text-sm font-medium text-gray-700">
+                {t_with("header-speech-rate", &[("rate", &i18n::number(props.speech.rate.into()))])}
+                <input
+                    type="range" min="0.5" max="2" step="0.1"
+                    value={props.speech.rate.to_string()}
+                    onchange={on_rate_change}
+                    class="w-full mt-1"
+                />
+            </label>
+            <label class="block text-sm font-medium text-gray-700 mt-3">
+                {t("header-voice")}
+                <select onchange={on_voice_change} class="w-full mt-1 px-2 py-1 bg-gray-100 rounded">
+                    <option value="" selected={props.speech.voice.is_none()}>{t("header-default-voice")}</option>
+                    {
+                        props.voices.iter().map(|v| html! {
+                            <option value={v.clone()} selected={props.speech.voice.as_ref() == Some(v)}>{v.clone()}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+            </label>
+            <div class="mt-4 pt-3 border-t border-gray-200">
+                <label class="flex items-center text-sm font-medium text-gray-700">
+                    <input
+                        type="checkbox"
+                        checked={props.profanity.enabled}
+                        onchange={props.on_toggle_profanity_filter.reform(|_| ())}
+                        class="me-2"
+                    />
+                    {t("header-filter-profanity")}
+                </label>
+                <div class="flex flex-wrap mt-2">
+                    {
+                        props.profanity.extra_words.iter().map(|word| {
+                            view_word_chip(word, &props.on_remove_filter_word, "bg-gray-100 text-gray-600", "text-gray-400 hover:text-red-500")
+                        }).collect::<Html>()
+                    }
+                </div>
+                <div class="flex mt-1">
+                    <input
+                        ref={props.filter_word_input.clone()}
+                        type="text"
+                        placeholder={t("header-add-word")}
+                        onkeypress={on_enter(&props.on_add_filter_word)}
+                        class="flex-1 min-w-0 px-2 py-1 text-sm bg-gray-100 rounded outline-none focus:ring-2 focus:ring-blue-400 focus:bg-white"
+                    />
+                    <button onclick={props.on_add_filter_word.reform(|_| ())} class="ms-2 px-2 py-1 text-sm text-blue-500 hover:underline">{t("header-add")}</button>
+                </div>
+            </div>
+            if props.role.can_moderate() {
+                <div class="mt-4 pt-3 border-t border-gray-200">
+                    <p class="text-sm font-medium text-gray-700">{t("header-blocked-words")}</p>
+                    <p class="text-xs text-gray-400">{t("header-blocked-words-hint")}</p>
+                    <div class="flex flex-wrap mt-2">
+                        {
+                            props.blocked_words.iter().map(|word| {
+                                view_word_chip(word, &props.on_remove_blocked_word, "bg-red-50 text-red-600", "text-red-300 hover:text-red-600")
+                            }).collect::<Html>()
+                        }
+                    </div>
+                    <div class="flex mt-1">
+                        <input
+                            ref={props.blocked_word_input.clone()}
+                            type="text"
+                            placeholder={t("header-block-word")}
+                            onkeypress={on_enter(&props.on_add_blocked_word)}
+                            class="flex-1 min-w-0 px-2 py-1 text-sm bg-gray-100 rounded outline-none focus:ring-2 focus:ring-blue-400 focus:bg-white"
+                        />
+                        <button onclick={props.on_add_blocked_word.reform(|_| ())} class="ms-2 px-2 py-1 text-sm text-blue-500 hover:underline">{t("header-add")}</button>
+                    </div>
+                </div>
+            }
+            <div class="mt-4 pt-3 border-t border-gray-200 text-sm">
+                <Link<Route> to={Route::Settings} classes="text-blue-500 hover:underline">{t("header-account-settings")}</Link<Route>>
+            </div>
+        </div>
+    }
+}
+
+/// Room title, participant count and the room-wide controls.
+#[function_component(ChatHeader)]
+pub fn chat_header(props: &ChatHeaderProps) -> Html {
+    let store = use_context::<ChatStore>().expect("chat store to be provided");
+    let theme = use_theme();
+    let colors = theme.classes();
+    let install = use_install_prompt();
+    let toggle_theme = {
+        let theme = theme.clone();
+        Callback::from(move |_| theme.toggle())
+    };
+    let can_moderate = props.role.can_moderate();
+    let presence = match store.connection {
+        Connection::Connected => t_count("header-participants", store.users.len()),
+        Connection::Connecting => t("header-connecting"),
+        Connection::Offline | Connection::Removed => t("header-offline"),
+    };
+
+    html! {
+        <div class={classes!("border-b", "px-6", "py-4", "shadow-sm", colors.surface, colors.border)}>
+            <div class="flex items-center justify-between">
+                <div class="flex items-center">
+                    // Mobile toggle for sidebar
+                    <button
+                        onclick={props.on_toggle_sidebar.reform(|_| ())}
+                        aria-label={t("header-toggle-sidebar")}
+                        class="md:hidden me-4 text-gray-500 hover:text-gray-700 focus:outline-none"
+                    >
+                        <svg xmlns="http://www.w3.org/2000/svg" class="h-6 w-6" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M4 6h16M4 12h16M4 18h16" />
+                        </svg>
+                    </button>
+                    <div class="h-10 w-10 rounded-full bg-blue-100 flex items-center justify-center text-blue-500">
+                        <svg xmlns="http://www.w3.org/2000/svg" class="h-6 w-6" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 12h.01M12 12h.01M16 12h.01M21 12c0 4.418-4.03 8-9 8a9.863 9.863 0 01-4.255-.949L3 20l1.395-3.72C3.512 15.042 3 13.574 3 12c0-4.418 4.03-8 9-8s9 3.582 9 8z" />
+                        </svg>
+                    </div>
+                    <div class="ms-4">
+                        <h2 class={classes!("text-lg", "font-semibold", colors.text)}>{t("header-title")}</h2>
+                        <p class={classes!("text-sm", colors.muted)}>
+                            {t_with("header-signed-in-as", &[("presence", &presence), ("name", &props.username)])}
+                            <button onclick={props.on_sign_out.reform(|_| ())} class="text-blue-500 hover:underline">{t("header-not-you")}</button>
+                        </p>
+                    </div>
+                </div>
+                <div class="relative flex items-center">
+                    <select
+                        onchange={on_select(&props.on_set_ephemeral)}
+                        disabled={!can_moderate}
+                        title={t("header-disappearing")}
+                        aria-label={t("header-disappearing")}
+                        class={classes!("me-2", "px-2", "py-1", "text-sm", "rounded", "focus:outline-none", colors.input)}
+                    >
+                        {
+                            EPHEMERAL_OPTIONS.iter().map(|(seconds, label)| html! {
+                                <option value={seconds.to_string()} selected={*seconds == props.ephemeral_seconds}>{t(label)}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                    <select
+                        onchange={on_select(&props.on_set_slow_mode)}
+                        disabled={!can_moderate}
+                        title={t("header-slow-mode")}
+                        aria-label={t("header-slow-mode")}
+                        class={classes!("me-2", "px-2", "py-1", "text-sm", "rounded", "focus:outline-none", colors.input)}
+                    >
+                        {
+                            SLOW_MODE_OPTIONS.iter().map(|(seconds, label)| html! {
+                                <option value={seconds.to_string()} selected={*seconds == props.slow_mode_seconds}>{t(label)}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                    if props.role == Role::Admin {
+                        <Link<Route> to={Route::Admin} classes="p-2 rounded-full text-gray-400 hover:bg-gray-100 hover:text-gray-600">
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-6 w-6" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M9 19v-6a2 2 0 00-2-2H5a2 2 0 00-2 2v6a2 2 0 002 2h2a2 2 0 002-2zm0 0V9a2 2 0 012-2h2a2 2 0 012 2v10m-6 0a2 2 0 002 2h2a2 2 0 002-2m0 0V5a2 2 0 012-2h2a2 2 0 012 2v14a2 2 0 01-2 2h-2a2 2 0 01-2-2z" />
+                            </svg>
+                        </Link<Route>>
+                        <button
+                            onclick={props.on_toggle_audit_panel.reform(|_| ())}
+                            title={t("header-moderation-log")}
+                            aria-label={t("header-moderation-log")}
+                            class="p-2 rounded-full text-gray-400 hover:bg-gray-100 hover:text-gray-600 focus:outline-none"
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-6 w-6" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M9 12l2 2 4-4m5.618-4.016A11.955 11.955 0 0112 2.944a11.955 11.955 0 01-8.618 3.04A12.02 12.02 0 003 9c0 5.591 3.824 10.29 9 11.622 5.176-1.332 9-6.03 9-11.622 0-1.042-.133-2.052-.382-3.016z" />
+                            </svg>
+                        </button>
+                    }
+                    <button
+                        onclick={props.on_toggle_read_aloud.reform(|_| ())}
+                        title={t(if props.read_aloud { "header-stop-reading" } else { "header-read-aloud" })}
+                        aria-label={t(if props.read_aloud { "header-stop-reading" } else { "header-read-aloud" })}
+                        class={classes!(
+                            "p-2", "rounded-full", "hover:bg-gray-100", "focus:outline-none",
+                            if props.read_aloud { "text-blue-500" } else { "text-gray-400" }
+                        )}
+                    >
+                        <svg xmlns="http://www.w3.org/2000/svg" class="h-6 w-6" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M15.536 8.464a5 5 0 010 7.072m2.828-9.9a9 9 0 010 12.728M5.586 15H4a1 1 0 01-1-1v-4a1 1 0 011-1h1.586l4.707-4.707C10.923 3.663 12 4.109 12 5v14c0 .891-1.077 1.337-1.707.707L5.586 15z" />
+                        </svg>
+                    </button>
+                    <button
+                        onclick={toggle_theme}
+                        title={t(if theme.dark { "header-light-mode" } else { "header-dark-mode" })}
+                        aria-label={t(if theme.dark { "header-light-mode" } else { "header-dark-mode" })}
+                        class={classes!("p-2", "rounded-full", "focus:outline-none", colors.icon)}
+                    >
+                        if theme.dark {
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 3v1m0 16v1m9-9h-1M4 12H3m15.364 6.364l-.707-.707M6.343 6.343l-.707-.707m12.728 0l-.707.707M6.343 17.657l-.707.707M16 12a4 4 0 11-8 0 4 4 0 018 0z" />
+                            </svg>
+                        } else {
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M20.354 15.354A9 9 0 018.646 3.646 9.003 9.003 0 0012 21a9.003 9.003 0 008.354-5.646z" />
+                            </svg>
+                        }
+                    </button>
+                    <button
+                        onclick={props.on_show_shortcuts.reform(|_| ())}
+                        title={t("header-shortcuts")}
+                        aria-label={t("header-shortcuts")}
+                        class={classes!("p-2", "rounded-full", "focus:outline-none", colors.icon)}
+                    >
+                        <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                            <rect x="2" y="6" width="20" height="12" rx="2" stroke-width="2" />
+                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M6 10h.01M10 10h.01M14 10h.01M18 10h.01M8 14h8" />
+                        </svg>
+                    </button>
+                    if let Some(install) = install {
+                        <button
+                            onclick={install.reform(|_| ())}
+                            class="ms-1 me-1 px-3 py-1 text-sm rounded-full border border-blue-200 text-blue-500 hover:bg-blue-50 focus:outline-none"
+                        >
+                            {t("header-install")}
+                        </button>
+                    }
+                    <button
+                        onclick={props.on_toggle_speech_panel.reform(|_| ())}
+                        title={t("header-settings")}
+                        aria-label={t("header-settings")}
+                        class="p-2 rounded-full text-gray-400 hover:bg-gray-100 hover:text-gray-600 focus:outline-none"
+                    >
+                        <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 6V4m0 2a2 2 0 100 4m0-4a2 2 0 110 4m-6 8a2 2 0 100-4m0 4a2 2 0 110-4m0 4v2m0-6V4m6 6v10m6-2a2 2 0 100-4m0 4a2 2 0 110-4m0 4v2m0-6V4" />
+                        </svg>
+                    </button>
+                    if props.speech_panel_visible {
+                        { view_settings_popover(props) }
+                    }
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,180 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::modal::Modal;
+use crate::components::theme_provider::use_theme;
+use crate::services::i18n::t;
+
+/// How many matches are listed at once; typing more narrows them down.
+const RESULTS_SHOWN: usize = 12;
+
+/// Something the palette can jump to or do.
+#[derive(Clone, PartialEq)]
+pub struct Command {
+    pub label: String,
+    /// What kind of thing it is, e.g. a room or a person, shown beside the label.
+    pub group: String,
+    /// More text the query may match, such as the username behind a display name.
+    pub keywords: String,
+    pub action: Callback<()>,
+}
+
+impl Command {
+    pub fn new(label: String, group: &str, action: Callback<()>) -> Self {
+        Self {
+            label,
+            group: t(group),
+            keywords: String::new(),
+            action,
+        }
+    }
+
+    pub fn keywords(self, keywords: &str) -> Self {
+        Self {
+            keywords: keywords.to_string(),
+            ..self
+        }
+    }
+}
+
+/// How well `query` matches `text`: all of its characters must appear in
+/// order, ignoring case, and runs of them or ones starting a word count for
+/// more. `None` when it doesn't match at all.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut from = 0;
+    let mut previous = None;
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let at = from + text[from..].iter().position(|c| *c == wanted)?;
+        score += 1;
+        if previous == Some(at.wrapping_sub(1)) {
+            score += 3;
+        }
+        if at == 0 || !text[at - 1].is_alphanumeric() {
+            score += 2;
+        }
+        previous = Some(at);
+        from = at + 1;
+    }
+    Some(score)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct CommandPaletteProps {
+    pub commands: Vec<Command>,
+    pub on_close: Callback<()>,
+}
+
+/// Type to find a room, a person or an action, then Enter to go there.
+#[function_component(CommandPalette)]
+pub fn command_palette(props: &CommandPaletteProps) -> Html {
+    let colors = use_theme().classes();
+    let query = use_state(String::new);
+    let active = use_state(|| 0usize);
+
+    let mut matches: Vec<(i32, &Command)> = props
+        .commands
+        .iter()
+        .filter_map(|command| {
+            let label = fuzzy_score(&query, &command.label);
+            let keywords = fuzzy_score(&query, &command.keywords).map(|score| score - 1);
+            Some((label.max(keywords)?, command))
+        })
+        .collect();
+    // Stable, so equally good matches keep the order they were given in.
+    matches.sort_by_key(|(score, _)| -score);
+    matches.truncate(RESULTS_SHOWN);
+    let matches: Vec<Command> = matches.into_iter().map(|(_, command)| command.clone()).collect();
+    let active_index = (*active).min(matches.len().saturating_sub(1));
+
+    let run = {
+        let on_close = props.on_close.clone();
+        move |command: &Command| {
+            on_close.emit(());
+            command.action.emit(());
+        }
+    };
+    let on_input = {
+        let (query, active) = (query.clone(), active.clone());
+        Callback::from(move |e: InputEvent| {
+            query.set(e.target_unchecked_into::<HtmlInputElement>().value());
+            active.set(0);
+        })
+    };
+    let on_keydown = {
+        let (active, matches, run) = (active.clone(), matches.clone(), run.clone());
+        Callback::from(move |e: KeyboardEvent| {
+            let last = matches.len().saturating_sub(1);
+            match e.key().as_str() {
+                "ArrowDown" => active.set(if active_index >= last { 0 } else { active_index + 1 }),
+                "ArrowUp" => active.set(if active_index == 0 { last } else { active_index - 1 }),
+                "Enter" => match matches.get(active_index) {
+                    Some(command) => run(command),
+                    None => return,
+                },
+                _ => return,
+            }
+            e.prevent_default();
+        })
+    };
+
+    html! {
+        <Modal
+            title={t("palette-title")}
+            hide_title=true
+            top=true
+            class={classes!("w-full", "max-w-lg", "mx-4", "overflow-hidden", colors.surface)}
+            on_close={props.on_close.clone()}
+        >
+            <input
+                type="text"
+                role="combobox"
+                aria-expanded="true"
+                aria-controls="command-palette-results"
+                aria-activedescendant={(!matches.is_empty()).then(|| format!("command-{}", active_index))}
+                aria-label={t("palette-title")}
+                placeholder={t("palette-placeholder")}
+                value={(*query).clone()}
+                oninput={on_input}
+                onkeydown={on_keydown}
+                class={classes!("w-full", "px-4", "py-3", "text-base", "border-b", "outline-none", colors.border, colors.surface, colors.text)}
+            />
+            <ul id="command-palette-results" role="listbox" class="max-h-80 overflow-y-auto py-1">
+                {
+                    matches.iter().enumerate().map(|(i, command)| {
+                        let onclick = {
+                            let (run, command) = (run.clone(), command.clone());
+                            Callback::from(move |_: MouseEvent| run(&command))
+                        };
+                        let onmousemove = {
+                            let active = active.clone();
+                            Callback::from(move |_: MouseEvent| active.set(i))
+                        };
+                        html! {
+                            <li
+                                id={format!("command-{}", i)}
+                                role="option"
+                                aria-selected={(i == active_index).to_string()}
+                                {onclick}
+                                {onmousemove}
+                                class={classes!(
+                                    "flex", "items-center", "justify-between", "px-4", "py-2", "text-sm", "cursor-pointer",
+                                    if i == active_index { "bg-accent text-white" } else { colors.text }
+                                )}
+                            >
+                                <span class="truncate">{command.label.clone()}</span>
+                                <span class={classes!("ms-3", "text-xs", "shrink-0", if i == active_index { "text-white" } else { colors.muted })}>
+                                    {command.group.clone()}
+                                </span>
+                            </li>
+                        }
+                    }).collect::<Html>()
+                }
+                if matches.is_empty() {
+                    <li class={classes!("px-4", "py-3", "text-sm", colors.muted)}>{t("palette-no-matches")}</li>
+                }
+            </ul>
+        </Modal>
+    }
+}
@@ -0,0 +1,96 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::functional::*;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::field::{setter, Field};
+use crate::services::account::{self, NewAccount};
+use crate::services::i18n::t;
+use crate::services::username;
+use crate::Route;
+use crate::{User, UserAction};
+
+#[function_component(CreateAccount)]
+pub fn create_account() -> Html {
+    let user = use_context::<User>().expect("No context found.");
+    let history = use_history();
+    let email = use_state(String::new);
+    let name = use_state(String::new);
+    let password = use_state(String::new);
+    let confirm = use_state(String::new);
+    let submitting = use_state(|| false);
+    let server_error = use_state(|| None::<String>);
+
+    let email_error = account::validate_email(&email).err();
+    let name_error = username::validate(&name).err();
+    let password_error = account::validate_password(&password).err();
+    let confirm_error = (*confirm != *password).then(|| t("create-account-mismatch"));
+    let valid = [&email_error, &name_error, &password_error, &confirm_error]
+        .iter()
+        .all(|e| e.is_none())
+        && !confirm.is_empty();
+
+    let onsubmit = {
+        let (email, name, password) = (email.clone(), name.clone(), password.clone());
+        let (submitting, server_error) = (submitting.clone(), server_error.clone());
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            let new_account = NewAccount {
+                email: email.trim().to_string(),
+                username: username::normalize(&name),
+                password: (*password).clone(),
+            };
+            let (user, history) = (user.clone(), history.clone());
+            let (submitting, server_error) = (submitting.clone(), server_error.clone());
+            submitting.set(true);
+            spawn_local(async move {
+                match account::create(&new_account).await {
+                    Ok(created) => {
+                        user.dispatch(UserAction::SignIn {
+                            username: created.username,
+                            avatar: None,
+                            token: None,
+                            account_token: created.token,
+                            captcha: None,
+                        });
+                        if let Some(history) = history {
+                            history.push(Route::Chat);
+                        }
+                    }
+                    Err(e) => {
+                        server_error.set(Some(e));
+                        submitting.set(false);
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="bg-gradient-to-r from-indigo-600 to-purple-600 min-h-screen w-full flex items-center">
+            <div class="container mx-auto px-4">
+                <form onsubmit={onsubmit} class="max-w-md mx-auto bg-white rounded-xl shadow-lg p-6">
+                    <h1 class="text-2xl font-bold text-center text-gray-800 mb-6">{t("create-account-title")}</h1>
+                    if let Some(error) = &*server_error {
+                        <p class="mb-4 px-4 py-3 rounded-lg bg-red-50 text-sm text-red-700">{error.clone()}</p>
+                    }
+                    <Field label={t("field-email")} kind="email" value={(*email).clone()} error={email_error} oninput={setter(&email)}/>
+                    <Field label={t("field-username")} value={(*name).clone()} error={name_error} oninput={setter(&name)}/>
+                    <Field label={t("field-password")} kind="password" value={(*password).clone()} error={password_error} oninput={setter(&password)}/>
+                    <Field label={t("field-confirm-password")} kind="password" value={(*confirm).clone()} error={confirm_error} oninput={setter(&confirm)}/>
+                    <button
+                        type="submit"
+                        disabled={!valid || *submitting}
+                        class="w-full rounded-lg bg-purple-600 hover:bg-purple-700 text-white font-medium py-3 px-4 transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                    >
+                        {t(if *submitting { "create-account-submitting" } else { "create-account-submit" })}
+                    </button>
+                    <p class="mt-4 text-center text-sm text-gray-500">
+                        {t("create-account-have-name")}{" "}
+                        <Link<Route> to={Route::Login} classes="text-purple-600 hover:underline">{t("create-account-back")}</Link<Route>>
+                    </p>
+                </form>
+            </div>
+        </div>
+    }
+}
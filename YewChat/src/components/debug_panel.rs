@@ -0,0 +1,115 @@
+use std::str::FromStr;
+
+use log::LevelFilter;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlDocument, HtmlSelectElement, HtmlTextAreaElement};
+use yew::functional::*;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::services::i18n::t;
+use crate::services::{crash, logging};
+use crate::Route;
+
+/// Shows what the app has been logging and packs it up for a bug report, for
+/// when the browser's devtools aren't at hand.
+#[function_component(DebugPanel)]
+pub fn debug_panel() -> Html {
+    let level = use_state(logging::level);
+    let lines = use_state(logging::recent_lines);
+    let report = use_state(|| None::<String>);
+    let report_ref = use_node_ref();
+
+    let on_level_change = {
+        let (level, lines) = (level.clone(), lines.clone());
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Ok(new_level) = LevelFilter::from_str(&select.value()) {
+                logging::set_level(new_level);
+                log::info!("log level set to {}", new_level);
+                level.set(new_level);
+                lines.set(logging::recent_lines());
+            }
+        })
+    };
+    let refresh = {
+        let lines = lines.clone();
+        Callback::from(move |_| lines.set(logging::recent_lines()))
+    };
+    let prepare_report = {
+        let report = report.clone();
+        Callback::from(move |_| report.set(Some(crash::diagnostics("Reported from the debug panel."))))
+    };
+    let copy_report = {
+        let report_ref = report_ref.clone();
+        Callback::from(move |_| {
+            if let Some(textarea) = report_ref.cast::<HtmlTextAreaElement>() {
+                textarea.select();
+            }
+            let copied = gloo::utils::document()
+                .dyn_ref::<HtmlDocument>()
+                .is_some_and(|document| document.exec_command("copy").unwrap_or(false));
+            if !copied {
+                log::warn!("copying the bug report failed; it is selected for copying by hand");
+            }
+        })
+    };
+
+    html! {
+        <div class="w-full min-h-screen overflow-y-auto bg-gray-50">
+            <div class="max-w-3xl mx-auto px-6 py-8">
+                <div class="flex items-center justify-between mb-6">
+                    <h1 class="text-2xl font-bold text-gray-800">{t("debug-title")}</h1>
+                    <Link<Route> to={Route::Chat} classes="text-sm text-blue-500 hover:underline">{t("debug-back")}</Link<Route>>
+                </div>
+                <section class="bg-white rounded-xl shadow-sm p-6 mb-6">
+                    <div class="flex items-center justify-between mb-4">
+                        <h2 class="text-lg font-semibold text-gray-800">{t("debug-log")}</h2>
+                        <div class="flex items-center text-sm text-gray-700">
+                            <label>
+                                {t("debug-level")}
+                                <select onchange={on_level_change} class="ms-2 px-2 py-1 bg-gray-100 rounded">
+                                    {
+                                        logging::LEVELS.iter().map(|l| html! {
+                                            <option value={l.as_str()} selected={*l == *level}>{l.as_str().to_lowercase()}</option>
+                                        }).collect::<Html>()
+                                    }
+                                </select>
+                            </label>
+                            <button onclick={refresh} class="ms-3 text-blue-500 hover:underline">{t("debug-refresh")}</button>
+                        </div>
+                    </div>
+                    <p class="mb-2 text-xs text-gray-500">
+                        {t("debug-level-hint")}
+                    </p>
+                    if lines.is_empty() {
+                        <p class="text-sm text-gray-400">{t("debug-log-empty")}</p>
+                    } else {
+                        <pre class="h-80 p-2 overflow-auto text-xs text-gray-600 bg-gray-100 rounded whitespace-pre-wrap break-words">
+                            {lines.join("\n")}
+                        </pre>
+                    }
+                </section>
+                <section class="bg-white rounded-xl shadow-sm p-6">
+                    <h2 class="mb-1 text-lg font-semibold text-gray-800">{t("debug-report")}</h2>
+                    <p class="mb-4 text-sm text-gray-500">
+                        {t("debug-report-hint")}
+                    </p>
+                    if let Some(report) = (*report).clone() {
+                        <textarea ref={report_ref} readonly=true value={report}
+                            class="w-full h-40 p-2 text-xs text-gray-600 font-mono bg-gray-100 rounded resize-none"/>
+                        <div class="mt-2 flex justify-end">
+                            <button onclick={copy_report} class="px-4 py-2 rounded-lg bg-purple-600 hover:bg-purple-700 text-white">
+                                {t("debug-copy")}
+                            </button>
+                        </div>
+                    } else {
+                        <button onclick={prepare_report} class="px-4 py-2 rounded-lg border border-gray-300 text-gray-700 hover:bg-gray-50">
+                            {t("debug-prepare-report")}
+                        </button>
+                    }
+                </section>
+            </div>
+        </div>
+    }
+}
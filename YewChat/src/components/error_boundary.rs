@@ -0,0 +1,79 @@
+use std::fmt::Display;
+
+use yew::prelude::*;
+
+use crate::services::i18n::t;
+
+/// Handed down by an [`ErrorBoundary`] so the views below it can give up on a
+/// failure they can't recover from without taking the whole page down.
+#[derive(Clone, PartialEq)]
+pub struct ErrorReporter(Callback<String>);
+
+impl ErrorReporter {
+    pub fn report(&self, error: impl Display) {
+        self.0.emit(error.to_string());
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ErrorBoundaryProps {
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// Renders its children until one of them reports an error, then a card
+/// offering to reload instead of a blank or half-broken page.
+#[function_component(ErrorBoundary)]
+pub fn error_boundary(props: &ErrorBoundaryProps) -> Html {
+    let error = use_state(|| None::<String>);
+    let reporter = {
+        let error = error.clone();
+        ErrorReporter(Callback::from(move |message: String| {
+            log::error!("view failed: {}", message);
+            // Keep the first failure; later ones are usually its fallout.
+            if error.is_none() {
+                error.set(Some(message));
+            }
+        }))
+    };
+
+    let message = match (*error).clone() {
+        Some(message) => message,
+        None => {
+            return html! {
+                <ContextProvider<ErrorReporter> context={reporter}>
+                    { for props.children.iter() }
+                </ContextProvider<ErrorReporter>>
+            }
+        }
+    };
+    let reload = Callback::from(|_| {
+        if let Err(e) = gloo::utils::window().location().reload() {
+            log::error!("failed to reload: {:?}", e);
+        }
+    });
+    let retry = {
+        let error = error.clone();
+        Callback::from(move |_| error.set(None))
+    };
+
+    html! {
+        <div class="bg-gradient-to-r from-indigo-600 to-purple-600 min-h-screen w-full flex items-center">
+            <div class="container mx-auto px-4">
+                <div class="max-w-md mx-auto bg-white rounded-xl shadow-lg p-8 text-center">
+                    <h1 class="text-xl font-bold text-gray-800">{t("error-title")}</h1>
+                    <p class="mt-2 text-gray-600">{t("error-body")}</p>
+                    <p class="mt-4 px-3 py-2 rounded bg-gray-100 text-start text-xs text-gray-500 font-mono break-words">{message}</p>
+                    <div class="mt-6 flex justify-center">
+                        <button onclick={reload} class="px-4 py-2 rounded-lg bg-purple-600 hover:bg-purple-700 text-white">
+                            {t("error-reload")}
+                        </button>
+                        <button onclick={retry} class="ms-2 px-4 py-2 rounded-lg border border-gray-300 text-gray-700 hover:bg-gray-50">
+                            {t("error-retry")}
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </div>
+    }
+}
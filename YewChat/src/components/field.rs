@@ -0,0 +1,48 @@
+use web_sys::HtmlInputElement;
+use yew::functional::*;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct FieldProps {
+    pub label: String,
+    #[prop_or("text")]
+    pub kind: &'static str,
+    pub value: String,
+    /// Shown once the field has been filled in.
+    pub error: Option<String>,
+    pub oninput: Callback<String>,
+}
+
+/// A labelled text input with an inline validation message.
+#[function_component(Field)]
+pub fn field(props: &FieldProps) -> Html {
+    let oninput = props.oninput.reform(|e: InputEvent| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        input.value()
+    });
+    let error = props.error.as_ref().filter(|_| !props.value.is_empty());
+
+    html! {
+        <div class="mb-4">
+            <input
+                type={props.kind}
+                value={props.value.clone()}
+                oninput={oninput}
+                placeholder={props.label.clone()}
+                class={classes!(
+                    "w-full", "px-4", "py-3", "rounded-lg", "border", "focus:outline-none", "focus:ring-2", "focus:border-transparent",
+                    if error.is_some() { "border-red-400 focus:ring-red-400" } else { "border-gray-300 focus:ring-purple-500" }
+                )}
+            />
+            if let Some(error) = error {
+                <p class="mt-1 text-sm text-red-500">{error.clone()}</p>
+            }
+        </div>
+    }
+}
+
+/// Callback that stores a field's text in `state`.
+pub fn setter(state: &UseStateHandle<String>) -> Callback<String> {
+    let state = state.clone();
+    Callback::from(move |value| state.set(value))
+}
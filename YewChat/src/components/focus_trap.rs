@@ -0,0 +1,141 @@
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+use yew::prelude::*;
+
+use crate::services::focus;
+use crate::services::shortcuts::use_dismissable;
+
+const FOCUSABLE: &str = "a[href], button:not([disabled]), input:not([disabled]), select:not([disabled]), \
+    textarea:not([disabled]), [tabindex]:not([tabindex='-1'])";
+
+#[derive(Properties, PartialEq)]
+pub struct FocusTrapProps {
+    /// What screen readers announce the dialog as.
+    pub label: String,
+    /// Called on Escape. Dialogs that must be answered leave it out.
+    #[prop_or_default]
+    pub on_close: Option<Callback<()>>,
+    /// Overrides `dialog`, e.g. for a `menu`.
+    #[prop_or_default]
+    pub role: Option<&'static str>,
+    #[prop_or_default]
+    pub class: Classes,
+    #[prop_or_default]
+    pub style: Option<String>,
+    #[prop_or_default]
+    pub children: Children,
+}
+
+fn focusable(container: &Element) -> Vec<HtmlElement> {
+    let list = match container.query_selector_all(FOCUSABLE) {
+        Ok(list) => list,
+        Err(_) => return Vec::new(),
+    };
+    (0..list.length())
+        .filter_map(|i| list.item(i)?.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+fn on_body_of(element: &HtmlElement) -> bool {
+    gloo::utils::document().body().is_some_and(|body| body.is_same_node(Some(element)))
+}
+
+/// Holds keyboard focus inside a dialog while it is open: focus moves to its
+/// first control on mount, Tab wraps around its controls instead of reaching
+/// the page behind, Escape closes it, and focus goes back to wherever it was
+/// once the dialog is gone, or to the screen's main field if that place is
+/// gone too, unless it was sent somewhere else on purpose.
+#[function_component(FocusTrap)]
+pub fn focus_trap(props: &FocusTrapProps) -> Html {
+    let node = use_node_ref();
+    // Always on the Escape stack, so a press meant for a dialog that must be
+    // answered doesn't close whatever is behind it instead.
+    use_dismissable(true, props.on_close.clone().unwrap_or_default());
+
+    {
+        let node = node.clone();
+        use_effect_with_deps(
+            move |_| {
+                let previous = gloo::utils::document()
+                    .active_element()
+                    .and_then(|element| element.dyn_into::<HtmlElement>().ok());
+                if let Some(container) = node.cast::<HtmlElement>() {
+                    let target = focusable(&container).into_iter().next().unwrap_or(container);
+                    if let Err(e) = target.focus() {
+                        log::error!("failed to focus dialog: {:?}", e);
+                    }
+                }
+                move || {
+                    // Unless something outside, e.g. the composer, has been
+                    // focused on purpose in the meantime.
+                    let document = gloo::utils::document();
+                    let active = document.active_element();
+                    let on_body = document.body().is_some_and(|body| body.is_same_node(active.as_deref()));
+                    let inside = node.get().is_some_and(|container| container.contains(active.as_deref()));
+                    let stray = active.is_none() || on_body || inside;
+                    if !stray {
+                        return;
+                    }
+                    // The control that opened the dialog may have gone with
+                    // it, e.g. an item of a menu that has since closed.
+                    match previous.filter(|previous| previous.is_connected() && !on_body_of(previous)) {
+                        Some(previous) => {
+                            let _ = previous.focus();
+                        }
+                        None => {
+                            focus::restore();
+                        }
+                    }
+                }
+            },
+            (),
+        );
+    }
+
+    let onkeydown = {
+        let node = node.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() != "Tab" {
+                return;
+            }
+            let controls = match node.cast::<Element>() {
+                Some(container) => focusable(&container),
+                None => return,
+            };
+            let (first, last) = match (controls.first(), controls.last()) {
+                (Some(first), Some(last)) => (first, last),
+                _ => {
+                    e.prevent_default();
+                    return;
+                }
+            };
+            let active = gloo::utils::document().active_element();
+            let wrap_to = if e.shift_key() && first.is_same_node(active.as_deref()) {
+                Some(last)
+            } else if !e.shift_key() && last.is_same_node(active.as_deref()) {
+                Some(first)
+            } else {
+                None
+            };
+            if let Some(target) = wrap_to {
+                e.prevent_default();
+                let _ = target.focus();
+            }
+        })
+    };
+
+    html! {
+        <div
+            ref={node}
+            role={props.role.unwrap_or("dialog")}
+            aria-modal={props.role.is_none().then_some("true")}
+            aria-label={props.label.clone()}
+            tabindex="-1"
+            class={props.class.clone()}
+            style={props.style.clone()}
+            {onkeydown}
+        >
+            { for props.children.iter() }
+        </div>
+    }
+}
@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use yew::functional::*;
+use yew::prelude::*;
+
+use crate::components::avatar::Avatar;
+use crate::components::avatar_picker::AvatarPicker;
+use crate::components::chat::{Location, MessageData, Poll, PollOption, Role, UserProfile};
+use crate::components::chat_header::{ChatHeader, ChatHeaderProps};
+use crate::components::message_bubble::{MessageBubble, MessageBubbleProps};
+use crate::components::message_composer::{MessageComposer, MessageComposerProps};
+use crate::components::message_list::{MessageList, MessageListProps};
+use crate::components::modal::Modal;
+use crate::components::toast::use_toast;
+use crate::components::user_sidebar::{UserSidebar, UserSidebarProps};
+use crate::services::chat_store::{ChatStore, ChatStoreState, Received, StoreAction};
+use crate::services::layout::Layout;
+use crate::services::name_list::NameList;
+
+/// Who the gallery pretends is signed in.
+const READER: &str = "alice";
+
+fn user(name: &str, display_name: Option<&str>, role: Role, status: Option<&str>) -> UserProfile {
+    UserProfile {
+        name: name.to_string(),
+        display_name: display_name.map(str::to_string),
+        role,
+        status: status.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+fn sample_users() -> Vec<UserProfile> {
+    vec![
+        user(READER, None, Role::Owner, Some("Writing the release notes")),
+        user("bob", Some("Bobby"), Role::Moderator, None),
+        user("carol", None, Role::Member, Some("On the train")),
+        user("dave", None, Role::Member, None),
+    ]
+}
+
+fn text(id: u64, from: &str, message: &str) -> MessageData {
+    MessageData {
+        id: Some(id),
+        from: from.to_string(),
+        message: message.to_string(),
+        ..Default::default()
+    }
+}
+
+/// One message of every kind a bubble can show, with a label for each.
+fn sample_messages(now: f64) -> Vec<(&'static str, MessageData)> {
+    vec![
+        ("Text", text(1, "bob", "Morning! Did the deploy go out?")),
+        ("Own message", text(2, READER, "It did, around nine.")),
+        ("GIF", text(3, "carol", "https://media.giphy.com/media/3o7abKhOpu0NwenH3O/giphy.gif")),
+        (
+            "Location",
+            MessageData {
+                location: Some(Location {
+                    latitude: -6.3627,
+                    longitude: 106.8269,
+                }),
+                ..text(4, "dave", "shared a location")
+            },
+        ),
+        (
+            "Poll",
+            MessageData {
+                poll: Some(Poll {
+                    id: 1,
+                    creator: "bob".to_string(),
+                    question: "Lunch?".to_string(),
+                    options: vec![
+                        PollOption {
+                            text: "Noodles".to_string(),
+                            votes: vec![READER.to_string(), "carol".to_string()],
+                        },
+                        PollOption {
+                            text: "Pizza".to_string(),
+                            votes: vec!["dave".to_string()],
+                        },
+                    ],
+                    closed: false,
+                }),
+                ..text(5, "bob", "Lunch?")
+            },
+        ),
+        (
+            "Direct message",
+            MessageData {
+                to: Some(READER.to_string()),
+                ..text(6, "carol", "Can you look at my PR when you get a minute?")
+            },
+        ),
+        (
+            "Disappearing",
+            MessageData {
+                expires_at: Some(now + 90_000.0),
+                ..text(7, "dave", "This one deletes itself.")
+            },
+        ),
+        ("Room notice", MessageData::notice("carol joined".to_string())),
+    ]
+}
+
+#[derive(Properties, PartialEq)]
+struct SampleStoreProps {
+    /// `None` leaves the store waiting for the first user list.
+    #[prop_or_default]
+    users: Option<Vec<UserProfile>>,
+    #[prop_or_default]
+    messages: Vec<MessageData>,
+    #[prop_or_default]
+    children: Children,
+}
+
+/// A [`ChatStore`] filled with made-up data, for the components that read it.
+#[function_component(SampleStore)]
+fn sample_store(props: &SampleStoreProps) -> Html {
+    let store = {
+        let (users, messages) = (props.users.clone(), props.messages.clone());
+        use_reducer_eq(move || {
+            let mut state = Rc::new(ChatStoreState::default()).reduce(StoreAction::Connect);
+            if let Some(online) = users {
+                let last_seen = HashMap::from([("erin".to_string(), js_sys::Date::now() - 3.0 * 3_600_000.0)]);
+                state = state.reduce(StoreAction::Connected).reduce(StoreAction::UsersReceived {
+                    online,
+                    last_seen,
+                    quiet: NameList::default(),
+                });
+            }
+            let received = messages
+                .into_iter()
+                .map(|message| Received { message, mentioned: false })
+                .collect();
+            state = state.reduce(StoreAction::MessagesReceived(received));
+            (*state).clone()
+        })
+    };
+    html! {
+        <ContextProvider<ChatStore> context={store}>
+            { for props.children.iter() }
+        </ContextProvider<ChatStore>>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct SpecimenProps {
+    label: String,
+    #[prop_or_default]
+    children: Children,
+}
+
+#[function_component(Specimen)]
+fn specimen(props: &SpecimenProps) -> Html {
+    html! {
+        <div class="mb-4">
+            <p class="mb-1 text-xs font-semibold uppercase tracking-wide text-gray-400">{props.label.clone()}</p>
+            { for props.children.iter() }
+        </div>
+    }
+}
+
+fn names(list: &[&str]) -> NameList {
+    let mut names = NameList::default();
+    for name in list {
+        names.add(name);
+    }
+    names
+}
+
+/// Every piece of the chat UI with made-up data, for working on the look of
+/// things without a server. Not linked from anywhere; open `/gallery`.
+/// Props a specimen doesn't show off come from the component's `Default`
+/// props, so new callbacks and settings don't need adding here.
+#[function_component(Gallery)]
+pub fn gallery() -> Html {
+    let toasts = use_toast();
+    let modal_visible = use_state(|| false);
+    let avatar = use_state(|| None::<String>);
+    let now = js_sys::Date::now();
+    let users = sample_users();
+    let author = |name: &str| Rc::new(users.iter().find(|u| u.name == name).cloned().unwrap_or_else(|| user(name, None, Role::Member, None)));
+
+    let bubble = |label: &'static str, message: MessageData, collapsed: bool, highlighted: bool, autoload_media: bool| {
+        let (author, recipient, text) = (author(&message.from), message.to.clone(), message.message.clone());
+        html! {
+            <Specimen label={label}>
+                <MessageBubble
+                    author={author}
+                    text={text}
+                    message={Rc::new(message)}
+                    recipient={recipient}
+                    username={READER}
+                    role={Role::Owner}
+                    collapsed={collapsed}
+                    highlighted={highlighted}
+                    autoload_media={autoload_media}
+                    now={now}
+                    ..MessageBubbleProps::default()
+                />
+            </Specimen>
+        }
+    };
+    let messages = sample_messages(now);
+    let bubbles = messages
+        .iter()
+        .cloned()
+        .map(|(label, message)| bubble(label, message, false, false, true))
+        .collect::<Html>();
+    let gif = messages[2].1.clone();
+
+    let sidebar = |label: &'static str, users: Option<Vec<UserProfile>>| {
+        html! {
+            <Specimen label={label}>
+                <div class="h-96 overflow-hidden border border-gray-200 rounded-lg">
+                    <SampleStore users={users}>
+                        <UserSidebar
+                            visible=true
+                            width={Layout::default().sidebar_width}
+                            username={READER}
+                            role={Role::Owner}
+                            contacts={names(&["bob", "erin"])}
+                            blocked={names(&["mallory"])}
+                            ..UserSidebarProps::default()
+                        />
+                    </SampleStore>
+                </div>
+            </Specimen>
+        }
+    };
+    let message_list = |users: Option<Vec<UserProfile>>, messages: Vec<MessageData>| {
+        html! {
+            <div class="h-96 flex flex-col border border-gray-200 rounded-lg overflow-hidden">
+                <SampleStore users={users} messages={messages}>
+                    <MessageList
+                        username={READER}
+                        role={Role::Owner}
+                        autoload_media=true
+                        ..MessageListProps::default()
+                    />
+                </SampleStore>
+            </div>
+        }
+    };
+    let composer = |label: &'static str, error: Option<&str>, direct_to: Option<&str>, cooldown: Option<u64>| {
+        html! {
+            <Specimen label={label}>
+                <div class="border border-gray-200 rounded-lg overflow-hidden">
+                    <MessageComposer
+                        error={error.map(str::to_string)}
+                        direct_to={direct_to.map(str::to_string)}
+                        cooldown={cooldown}
+                        ..MessageComposerProps::default()
+                    />
+                </div>
+            </Specimen>
+        }
+    };
+    let toggle = |flag: &UseStateHandle<bool>| {
+        let flag = flag.clone();
+        Callback::from(move |_| flag.set(!*flag))
+    };
+    let on_avatar_change = {
+        let avatar = avatar.clone();
+        Callback::from(move |value: Option<String>| avatar.set(value))
+    };
+    let show_toast = {
+        let toasts = toasts.clone();
+        Callback::from(move |_: MouseEvent| toasts.info("Message reported. Thanks for letting us know.".to_string()))
+    };
+    let show_error_toast =
+        Callback::from(move |_: MouseEvent| toasts.error("Couldn't send that. Check your connection and try again.".to_string()));
+
+    html! {
+        <div class="w-full min-h-screen overflow-y-auto bg-gray-50">
+            <div class="max-w-5xl mx-auto px-6 py-8">
+                <h1 class="mb-6 text-2xl font-bold text-gray-800">{"Component gallery"}</h1>
+
+                <h2 class="mb-3 text-lg font-semibold text-gray-800">{"Header"}</h2>
+                <div class="mb-8 border border-gray-200 rounded-lg overflow-hidden">
+                    <SampleStore users={Some(users.clone())}>
+                        <ChatHeader
+                            username={READER}
+                            role={Role::Owner}
+                            ..ChatHeaderProps::default()
+                        />
+                    </SampleStore>
+                </div>
+
+                <h2 class="mb-3 text-lg font-semibold text-gray-800">{"Message bubbles"}</h2>
+                <div class="mb-8 p-4 bg-white rounded-lg border border-gray-200">
+                    {bubbles}
+                    { bubble("GIF with media autoload off", gif, false, false, false) }
+                    { bubble("Muted", text(8, "dave", "You shouldn't see this yet."), true, false, true) }
+                    { bubble("Highlighted from a permalink", text(9, "bob", "Here's the link you asked for."), false, true, true) }
+                </div>
+
+                <h2 class="mb-3 text-lg font-semibold text-gray-800">{"Message list"}</h2>
+                <div class="mb-8 grid grid-cols-1 md:grid-cols-2 gap-4">
+                    <Specimen label="Connecting">{ message_list(None, vec![]) }</Specimen>
+                    <Specimen label="Conversation">
+                        { message_list(Some(users.clone()), messages.into_iter().map(|(_, m)| m).collect()) }
+                    </Specimen>
+                </div>
+
+                <h2 class="mb-3 text-lg font-semibold text-gray-800">{"Sidebar"}</h2>
+                <div class="mb-8 grid grid-cols-1 md:grid-cols-3 gap-4">
+                    { sidebar("Loading", None) }
+                    { sidebar("Nobody online", Some(vec![])) }
+                    { sidebar("Online users", Some(users.clone())) }
+                </div>
+
+                <h2 class="mb-3 text-lg font-semibold text-gray-800">{"Composer"}</h2>
+                <div class="mb-8">
+                    { composer("Ready", None, None, None) }
+                    { composer("Direct message", None, Some("Bobby"), None) }
+                    { composer("Slow mode", None, None, Some(12)) }
+                    { composer("Error", Some("Messages can't be longer than 500 characters."), None, None) }
+                </div>
+
+                <h2 class="mb-3 text-lg font-semibold text-gray-800">{"Avatars"}</h2>
+                <div class="mb-8 p-4 bg-white rounded-lg border border-gray-200">
+                    <div class="flex items-center mb-4">
+                        <Avatar name="bob" src={crate::services::avatar::default_avatar("bob")} class="w-12 h-12 rounded-full"/>
+                        <Avatar name="carol" src="https://example.invalid/broken.png" class="ms-4 w-12 h-12 rounded-full"/>
+                    </div>
+                    <AvatarPicker name={READER} value={(*avatar).clone()} on_change={on_avatar_change}/>
+                </div>
+
+                <h2 class="mb-3 text-lg font-semibold text-gray-800">{"Overlays"}</h2>
+                <div class="mb-8 flex">
+                    <button
+                        onclick={show_toast}
+                        class="px-4 py-2 rounded-lg border border-gray-300 text-gray-700 hover:bg-gray-50"
+                    >
+                        {"Show toast"}
+                    </button>
+                    <button
+                        onclick={show_error_toast}
+                        class="ms-2 px-4 py-2 rounded-lg border border-gray-300 text-gray-700 hover:bg-gray-50"
+                    >
+                        {"Show error toast"}
+                    </button>
+                    <button onclick={toggle(&modal_visible)} class="ms-2 px-4 py-2 rounded-lg border border-gray-300 text-gray-700 hover:bg-gray-50">
+                        {"Show dialog"}
+                    </button>
+                </div>
+                if *modal_visible {
+                    <Modal title="A dialog" description={"Dialogs dim the page behind them.".to_string()} centered=true>
+                        <button onclick={toggle(&modal_visible)} class="mt-4 w-full py-2 rounded-lg bg-accent hover:bg-accent-dark text-white text-sm">
+                            {"Close"}
+                        </button>
+                    </Modal>
+                }
+            </div>
+        </div>
+    }
+}
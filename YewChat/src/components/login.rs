@@ -3,13 +3,40 @@ use yew::functional::*;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use crate::components::avatar_gallery::AvatarGallery;
+use crate::components::avatar_picker::AvatarPicker;
+use crate::components::password_sign_in::PasswordSignIn;
+use crate::components::theme_provider::use_theme;
+use crate::services::captcha;
+use crate::services::focus::{self, FocusTarget};
+use crate::services::i18n::{t, t_with};
+use crate::services::oauth::{self, Provider};
+use crate::services::session::Session;
+use crate::services::username;
 use crate::Route;
-use crate::User;
+use crate::{User, UserAction};
 
 #[function_component(Login)]
 pub fn login() -> Html {
-    let username = use_state(|| String::new());
+    let username = use_state(String::new);
+    let avatar = use_state(|| None::<String>);
     let user = use_context::<User>().expect("No context found.");
+    let theme = use_theme();
+    let colors = theme.classes();
+    // Kept for this visit; the context's copy is cleared so it only shows once.
+    let notice = use_state(|| user.notice.clone());
+    {
+        let user = user.clone();
+        use_effect_with_deps(
+            move |_| {
+                if user.notice.is_some() {
+                    user.dispatch(UserAction::ClearNotice);
+                }
+                || ()
+            },
+            (),
+        );
+    }
 
     let oninput = {
         let current_username = username.clone();
@@ -20,38 +47,150 @@ pub fn login() -> Html {
         })
     };
 
+    let captcha = use_state(captcha::configured);
+    let captcha_token = use_state(|| None::<String>);
+    let captcha_ref = use_node_ref();
+    let username_ref = use_node_ref();
+    focus::use_focus_target(FocusTarget::Username, &username_ref, true);
+    {
+        let (captcha, captcha_token, captcha_ref) = (captcha.clone(), captcha_token.clone(), captcha_ref.clone());
+        use_effect_with_deps(
+            move |_| {
+                if let (Some(captcha), Some(container)) = (&*captcha, captcha_ref.cast::<web_sys::Element>()) {
+                    captcha.render(container, Callback::from(move |token| captcha_token.set(token)));
+                }
+                || ()
+            },
+            (),
+        );
+    }
+
+    let on_avatar_change = {
+        let avatar = avatar.clone();
+        Callback::from(move |url: Option<String>| avatar.set(url))
+    };
+
+    // Nothing to complain about until the user starts typing.
+    let error = if username.is_empty() {
+        None
+    } else {
+        username::validate(&username).err()
+    };
+    let name_valid = !username.is_empty() && error.is_none();
+    let valid = name_valid && (captcha.is_none() || captcha_token.is_some());
+
     let onclick = {
         let username = username.clone();
+        let avatar = avatar.clone();
+        let captcha_token = captcha_token.clone();
         let user = user.clone();
-        Callback::from(move |_| *user.username.borrow_mut() = (*username).clone())
+        Callback::from(move |_| {
+            user.dispatch(UserAction::SignIn {
+                username: username::normalize(&username),
+                avatar: (*avatar).clone(),
+                token: None,
+                account_token: None,
+                captcha: (*captcha_token).clone(),
+            });
+        })
     };
 
+    let providers: Vec<Provider> = Provider::ALL
+        .into_iter()
+        .filter(|p| p.client_id().is_some())
+        .collect();
+
+    // A remembered session skips straight to the chat, which registers again.
+    if notice.is_none() && Session::load().is_some() {
+        return html! { <Redirect<Route> to={Route::Chat}/> };
+    }
+
     html! {
-        <div class="bg-gradient-to-r from-indigo-600 to-purple-600 min-h-screen flex items-center">
+        <div class={classes!(
+            "themed", "bg-gradient-to-r", "min-h-screen", "flex", "items-center",
+            if theme.dark { "from-gray-900 to-indigo-900" } else { "from-indigo-600 to-purple-600" }
+        )}>
             <div class="container mx-auto px-4">
-                <div class="max-w-md mx-auto bg-white rounded-xl shadow-lg p-6">
-                    <h1 class="text-2xl font-bold text-center text-gray-800 mb-6">{"Welcome to YewChat"}</h1>
+                <div class={classes!("max-w-md", "mx-auto", "rounded-xl", "shadow-lg", "p-6", colors.surface)}>
+                    <h1 class={classes!("text-2xl", "font-bold", "text-center", "mb-6", colors.text)}>{t("login-welcome")}</h1>
+                    if let Some(notice) = &*notice {
+                        <p class="mb-4 px-4 py-3 rounded-lg bg-red-50 text-sm text-red-700">{notice.clone()}</p>
+                    }
                     
                     <div class="flex flex-col">
                         <div class="mb-4">
                             <input 
+                                ref={username_ref}
                                 oninput={oninput} 
-                                class="w-full px-4 py-3 rounded-lg border border-gray-300 focus:outline-none focus:ring-2 focus:ring-purple-500 focus:border-transparent" 
-                                placeholder="Username"
+                                class={classes!(
+                                    "w-full", "px-4", "py-3", "rounded-lg", "border", "focus:outline-none", "focus:ring-2", "focus:border-transparent",
+                                    colors.surface, colors.text,
+                                    if error.is_some() { "border-red-400 focus:ring-red-400" } else { "border-gray-300 focus:ring-purple-500" }
+                                )}
+                                placeholder={t("field-username")}
+                                maxlength={username::MAX_LENGTH.to_string()}
                             />
+                            if let Some(error) = &error {
+                                <p class="mt-1 text-sm text-red-500">{error.clone()}</p>
+                            }
+                        </div>
+
+                        <div class="mb-4">
+                            <AvatarPicker name={username::normalize(&username)} value={(*avatar).clone()} on_change={on_avatar_change.clone()} />
+                            if name_valid {
+                                <p class="mt-3 mb-2 text-sm text-gray-500">{t("login-pick-look")}</p>
+                                <AvatarGallery name={username::normalize(&username)} value={(*avatar).clone()} on_select={on_avatar_change} />
+                            }
                         </div>
                         
+                        if captcha.is_some() {
+                            <div ref={captcha_ref} class="flex justify-center mb-4"></div>
+                        }
+
                         <div>
                             <Link<Route> to={Route::Chat} classes="block w-full">
                                 <button 
                                     onclick={onclick} 
-                                    disabled={username.len() < 1} 
+                                    disabled={!valid} 
                                     class="w-full rounded-lg bg-purple-600 hover:bg-purple-700 text-white font-medium py-3 px-4 transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
                                 >
-                                    {"Go Chatting!"}
+                                    {t("login-go")}
                                 </button>
                             </Link<Route>>
                         </div>
+
+                        <p class={classes!("mt-4", "text-center", "text-sm", colors.muted)}>
+                            {t("login-new-here")}{" "}
+                            <Link<Route> to={Route::CreateAccount} classes="text-purple-600 hover:underline">{t("login-create-account")}</Link<Route>>
+                        </p>
+
+                        <div class="flex items-center my-4 text-xs text-gray-400">
+                            <div class={classes!("flex-1", "border-t", colors.border)}></div>
+                            <span class="px-3">{t("login-or-account")}</span>
+                            <div class={classes!("flex-1", "border-t", colors.border)}></div>
+                        </div>
+                        <PasswordSignIn captcha_required={captcha.is_some()} captcha_token={(*captcha_token).clone()}/>
+                        <p class="mt-2 text-center text-sm">
+                            <Link<Route> to={Route::ForgotPassword} classes="text-purple-600 hover:underline">{t("login-forgot-password")}</Link<Route>>
+                        </p>
+
+                        if !providers.is_empty() {
+                            <div class="flex items-center my-4 text-xs text-gray-400">
+                                <div class={classes!("flex-1", "border-t", colors.border)}></div>
+                                <span class="px-3">{t("login-or")}</span>
+                                <div class={classes!("flex-1", "border-t", colors.border)}></div>
+                            </div>
+                            {
+                                providers.into_iter().map(|provider| html! {
+                                    <button
+                                        onclick={Callback::from(move |_| oauth::start(provider))}
+                                        class={classes!("w-full", "mb-2", "rounded-lg", "border", "font-medium", "py-3", "px-4", "transition-colors", colors.border, colors.hover, colors.text)}
+                                    >
+                                        {t_with("login-continue-with", &[("provider", provider.label())])}
+                                    </button>
+                                }).collect::<Html>()
+                            }
+                        }
                     </div>
                 </div>
             </div>
@@ -0,0 +1,437 @@
+use std::rc::Rc;
+
+use gloo::timers::callback::Timeout;
+use wasm_bindgen::JsValue;
+use web_sys::Element;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::avatar::Avatar;
+use crate::components::chat::{message_anchor, MessageData, Poll, Role, UserProfile};
+use crate::components::focus_trap::FocusTrap;
+use crate::components::theme_provider::{use_theme, ThemeClasses};
+use crate::services::i18n::{self, t, t_count, t_with};
+use crate::services::palette;
+use crate::services::settings::Density;
+use crate::services::time;
+use crate::services::translate::{self, Translation};
+use crate::{Route, User};
+
+const MENU_WIDTH: i32 = 176;
+const MENU_ITEM_HEIGHT: i32 = 32;
+/// How long a finger has to rest on a message to open its menu.
+const LONG_PRESS_MS: u32 = 500;
+
+/// Something the reader did to a message. One callback carries them all so
+/// `MessageList` can hand it to every bubble unchanged.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MessageAction {
+    ShowProfile(String),
+    Reveal(u64),
+    /// Answer the message: mention its author, or reply privately to a direct one.
+    Reply(u64),
+    /// Put the text on the clipboard, or the address of a GIF or map.
+    Copy(u64),
+    /// Hand the message and its permalink to another app.
+    Share(u64),
+    /// Scroll to the message and point the URL at its permalink.
+    Focus(u64),
+    Pin(u64),
+    Unpin,
+    Delete(u64),
+    Report(u64),
+    Vote(u64, usize),
+    ClosePoll(u64),
+    /// Show the message in the reader's language, or hide it again.
+    Translate(u64),
+}
+
+/// Where a message's translation has got to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Translated {
+    Loading,
+    Done(Translation),
+    Failed(String),
+}
+
+#[derive(Default, Properties, PartialEq)]
+pub struct MessageBubbleProps {
+    /// Shared with the store, so re-rendering the list doesn't copy it.
+    pub message: Rc<MessageData>,
+    /// The sender's profile, or a bare one if they have left.
+    pub author: Rc<UserProfile>,
+    /// Display name of the recipient, for direct messages.
+    #[prop_or_default]
+    pub recipient: Option<String>,
+    /// The text to show, already filtered for the reader.
+    pub text: String,
+    /// The signed-in user and their role, which decide the actions on offer.
+    pub username: String,
+    pub role: Role,
+    /// A muted sender's message the reader hasn't chosen to show.
+    #[prop_or_default]
+    pub collapsed: bool,
+    #[prop_or_default]
+    pub highlighted: bool,
+    pub autoload_media: bool,
+    #[prop_or_default]
+    pub density: Density,
+    /// Passed down so disappearing messages count down on every tick. Zero
+    /// for the rest, so the tick doesn't re-render them.
+    pub now: f64,
+    /// Whether a translation endpoint is configured.
+    #[prop_or_default]
+    pub translatable: bool,
+    #[prop_or_default]
+    pub translation: Option<Translated>,
+    /// The one message in the list that Tab stops on; arrow keys move it.
+    #[prop_or_default]
+    pub tab_stop: bool,
+    pub on_action: Callback<MessageAction>,
+}
+
+/// What the message's menu offers, limited to what the reader's role allows:
+/// label, action and whether it is destructive.
+fn menu_items(props: &MessageBubbleProps, own: bool) -> Vec<(String, MessageAction, bool)> {
+    let m = &props.message;
+    let id = match m.id {
+        Some(id) if !props.collapsed => id,
+        _ => return Vec::new(),
+    };
+    let gif = m.gif_url().is_some();
+    let text = m.poll.is_none() && m.location.is_none() && !gif;
+    let mut items = Vec::new();
+    if !own {
+        items.push((t("message-reply"), MessageAction::Reply(id), false));
+    }
+    if text {
+        items.push((t("message-copy"), MessageAction::Copy(id), false));
+    } else if gif {
+        items.push((t("message-copy-image"), MessageAction::Copy(id), false));
+    } else if m.location.is_some() {
+        items.push((t("message-copy-location"), MessageAction::Copy(id), false));
+    }
+    items.push((t("message-link-title"), MessageAction::Focus(id), false));
+    items.push((t("message-share"), MessageAction::Share(id), false));
+    if props.role.can_moderate() && m.to.is_none() {
+        items.push((t("message-pin"), MessageAction::Pin(id), false));
+    }
+    if props.translatable && text {
+        let label = t(if props.translation.is_some() { "message-show-original" } else { "message-translate" });
+        items.push((label, MessageAction::Translate(id), false));
+    }
+    if !own {
+        items.push((t("message-report"), MessageAction::Report(id), true));
+    }
+    if own || props.role.can_moderate() {
+        items.push((t("message-delete"), MessageAction::Delete(id), true));
+    }
+    items
+}
+
+/// The message's actions at viewport `(x, y)`, over a backdrop that closes it.
+fn view_menu(
+    items: Vec<(String, MessageAction, bool)>,
+    (x, y): (i32, i32),
+    colors: &ThemeClasses,
+    on_action: &Callback<MessageAction>,
+    close: Callback<()>,
+) -> Html {
+    // Keep the whole menu on screen when opened near the right or bottom edge.
+    let viewport = |size: Result<JsValue, JsValue>| {
+        size.ok().and_then(|size| size.as_f64()).map_or(i32::MAX, |size| size as i32)
+    };
+    let window = gloo::utils::window();
+    let left = x.min(viewport(window.inner_width()) - MENU_WIDTH).max(0);
+    let top = y.min(viewport(window.inner_height()) - items.len() as i32 * MENU_ITEM_HEIGHT - 8).max(0);
+    let on_backdrop = {
+        let close = close.clone();
+        Callback::from(move |e: PointerEvent| {
+            if e.target() == e.current_target() {
+                close.emit(());
+            }
+        })
+    };
+
+    html! {
+        // Closes on press rather than click, so lifting the finger after a long
+        // press doesn't close the menu it just opened.
+        <div onpointerdown={on_backdrop} oncontextmenu={close.reform(|e: MouseEvent| e.prevent_default())} class="fixed inset-0 z-30">
+            <FocusTrap
+                label={t("message-actions")}
+                role="menu"
+                on_close={close.clone()}
+                class={classes!("absolute", "py-1", "text-sm", "rounded-lg", "shadow-lg", "border", colors.surface, colors.border)}
+                style={format!("left: {}px; top: {}px; width: {}px;", left, top, MENU_WIDTH)}
+            >
+                {
+                    items.into_iter().map(|(label, action, destructive)| {
+                        let (on_action, close) = (on_action.clone(), close.clone());
+                        let onclick = Callback::from(move |_: MouseEvent| {
+                            close.emit(());
+                            on_action.emit(action.clone());
+                        });
+                        html! {
+                            <button
+                                role="menuitem"
+                                {onclick}
+                                class={classes!(
+                                    "block", "w-full", "text-start", "px-3", "py-1.5", colors.hover,
+                                    if destructive { "text-red-600" } else { colors.text }
+                                )}
+                            >
+                                {label}
+                            </button>
+                        }
+                    }).collect::<Html>()
+                }
+            </FocusTrap>
+        </div>
+    }
+}
+
+fn view_poll(poll: &Poll, username: &str, colors: &ThemeClasses, on_action: &Callback<MessageAction>) -> Html {
+    let total: usize = poll.options.iter().map(|o| o.votes.len()).sum();
+    let poll_id = poll.id;
+    let close = on_action.reform(move |_| MessageAction::ClosePoll(poll_id));
+
+    html! {
+        <div class={classes!("p-3", "rounded-bubble", "shadow-sm", "mt-1", "w-72", colors.bubble)}>
+            <p dir="auto" class="font-semibold mb-2">{poll.question.clone()}</p>
+            {
+                poll.options.iter().enumerate().map(|(i, option)| {
+                    let percent = (option.votes.len() * 100).checked_div(total).unwrap_or(0);
+                    let voted = option.votes.iter().any(|v| v == username);
+                    let vote = on_action.reform(move |_| MessageAction::Vote(poll_id, i));
+                    html! {
+                        <button
+                            onclick={vote}
+                            disabled={poll.closed}
+                            class={classes!(
+                                "relative", "block", "w-full", "text-start", "mb-1", "px-3", "py-2",
+                                "rounded", "border", "overflow-hidden", "disabled:cursor-default",
+                                if voted { "border-blue-400" } else { "border-gray-200" }
+                            )}
+                        >
+                            <div class="absolute inset-y-0 start-0 bg-blue-100" style={format!("width: {}%;", percent)}></div>
+                            <div class="relative flex justify-between text-sm">
+                                <span dir="auto" class="text-gray-800">{option.text.clone()}</span>
+                                <span class="text-gray-500">{i18n::number(option.votes.len() as f64)}</span>
+                            </div>
+                        </button>
+                    }
+                }).collect::<Html>()
+            }
+            <div class="flex justify-between items-center text-xs text-gray-500 mt-2">
+                <span>
+                    {t_count("poll-votes", total)}
+                    if poll.closed {
+                        {format!(" · {}", t("poll-closed"))}
+                    }
+                </span>
+                if poll.creator == username && !poll.closed {
+                    <button onclick={close} class="text-red-500 hover:underline">{t("poll-close")}</button>
+                }
+            </div>
+        </div>
+    }
+}
+
+fn view_translation(translation: Option<&Translated>, colors: &ThemeClasses) -> Html {
+    match translation {
+        None => html! {},
+        Some(Translated::Loading) => html! {
+            <p class={classes!("mt-1", "text-xs", "italic", colors.muted)}>{t("message-translating")}</p>
+        },
+        Some(Translated::Failed(error)) => html! {
+            <p class="mt-1 text-xs text-red-500">{error.clone()}</p>
+        },
+        Some(Translated::Done(translation)) => html! {
+            <div class={classes!("mt-1", "ps-3", "border-s-2", colors.border)}>
+                <p dir="auto" class={classes!("text-sm", colors.text)}>{translation.text.clone()}</p>
+                <p class={classes!("text-xs", colors.muted)}>
+                    {
+                        match &translation.source {
+                            Some(source) => t_with("message-translated-from", &[("language", &translate::language_name(source))]),
+                            None => t("message-translated"),
+                        }
+                    }
+                </p>
+            </div>
+        },
+    }
+}
+
+/// A single chat message: author, body (text, GIF, location or poll) and the
+/// actions available on it. Room notices render as a centred line instead.
+#[function_component(MessageBubble)]
+pub fn message_bubble(props: &MessageBubbleProps) -> Html {
+    let m = &props.message;
+    let colors = use_theme().classes();
+    let compact = props.density == Density::Compact;
+    // The reader's own messages sit on the other side, without their avatar and name.
+    let own = use_context::<User>().is_some_and(|user| user.username == m.from);
+    // Where the message's menu is open, from a right click, long press or its button.
+    let menu = use_state(|| None::<(i32, i32)>);
+    let press = use_mut_ref(|| None::<Timeout>);
+    if m.system {
+        return html! {
+            <div class={classes!("flex", "justify-center", if compact { "my-1" } else { "my-3" })}>
+                <span dir="auto" class="max-w-md px-3 py-1 rounded-lg bg-gray-100 text-xs text-center text-gray-500 italic whitespace-pre-line">{m.message.clone()}</span>
+            </div>
+        };
+    }
+    let author = &props.author;
+    let show_profile = {
+        let name = m.from.clone();
+        props.on_action.reform(move |_| MessageAction::ShowProfile(name.clone()))
+    };
+    // Muted messages stay in the history but are collapsed until clicked.
+    let reveal = m
+        .id
+        .filter(|_| props.collapsed)
+        .map(|id| props.on_action.reform(move |_| MessageAction::Reveal(id)));
+
+    let items = menu_items(props, own);
+    let gif_url = m.gif_url();
+    let has_menu = !items.is_empty();
+    let on_context_menu = {
+        let menu = menu.clone();
+        Callback::from(move |e: MouseEvent| {
+            // Messages without actions keep the browser's own menu.
+            if has_menu {
+                e.prevent_default();
+                menu.set(Some((e.client_x(), e.client_y())));
+            }
+        })
+    };
+    let on_pointer_down = {
+        let (menu, press) = (menu.clone(), press.clone());
+        Callback::from(move |e: PointerEvent| {
+            if has_menu && e.pointer_type() == "touch" {
+                let menu = menu.clone();
+                let at = (e.client_x(), e.client_y());
+                *press.borrow_mut() = Some(Timeout::new(LONG_PRESS_MS, move || menu.set(Some(at))));
+            }
+        })
+    };
+    // Lifting the finger or starting to scroll first makes it a tap or a swipe.
+    let cancel_press = {
+        let press = press.clone();
+        Callback::from(move |_: PointerEvent| {
+            press.borrow_mut().take();
+        })
+    };
+    let end_press = cancel_press.clone();
+    let open_menu = {
+        let menu = menu.clone();
+        Callback::from(move |e: MouseEvent| {
+            let rect = e.target_unchecked_into::<Element>().get_bounding_client_rect();
+            menu.set(Some((rect.left() as i32, rect.bottom() as i32)));
+        })
+    };
+    let close_menu = {
+        let menu = menu.clone();
+        Callback::from(move |_: ()| menu.set(None))
+    };
+
+    html! {
+        <>
+            <div
+                id={m.id.map(message_anchor)}
+                role="article"
+                aria-label={if own { t("message-own") } else { t_with("message-from", &[("name", author.display_name())]) }}
+                tabindex={m.id.map(|_| if props.tab_stop { "0" } else { "-1" })}
+                class={classes!(
+                    "group", "flex", if compact { "mb-1" } else { "mb-4" }, "items-end", "rounded-lg", "transition-colors", "duration-700",
+                    own.then_some("justify-end"),
+                    "focus:outline-none", "focus-visible:ring-2", "focus-visible:ring-blue-400",
+                    props.highlighted.then_some("bg-yellow-100")
+                )}
+                oncontextmenu={on_context_menu}
+                onpointerdown={on_pointer_down}
+                onpointerup={end_press}
+                onpointercancel={cancel_press}
+            >
+                if !own {
+                    <div onclick={show_profile} class="flex-shrink-0 cursor-pointer">
+                        <Avatar
+                            name={author.name.clone()}
+                            src={author.avatar()}
+                            class={classes!("rounded-full", if compact { "w-6 h-6 text-[10px]" } else { "w-8 h-8 text-xs" })}
+                        />
+                    </div>
+                }
+                <div class={classes!("max-w-xl", "lg:max-w-2xl", if own { "flex flex-col items-end" } else { "ms-2" })}>
+                    <div class="font-medium text-sm text-gray-700">
+                        if !own {
+                            <Link<Route> to={Route::Profile { name: author.name.clone() }} classes="hover:underline">
+                                <span dir="auto" style={format!("color: {};", palette::user_color(&author.name))}>{author.display_name()}</span>
+                            </Link<Route>>
+                        }
+                        if let Some(to) = &props.recipient {
+                            <span class={classes!("text-xs", "font-normal", "text-purple-500", (!own).then_some("ms-1"))}>{t_with("message-direct", &[("name", to)])}</span>
+                        }
+                        if let Some(sent) = m.time {
+                            <span title={time::date_time(sent)} class={classes!("text-xs", "font-normal", "text-gray-400", (!own || props.recipient.is_some()).then_some("ms-2"))}>{time::clock_time(sent)}</span>
+                        }
+                        if has_menu {
+                            <button
+                                onclick={open_menu}
+                                title={t("message-actions")}
+                                aria-label={t("message-actions")}
+                                aria-haspopup="menu"
+                                aria-expanded={menu.is_some().to_string()}
+                                class="ms-2 px-1 rounded opacity-0 group-hover:opacity-100 group-focus-within:opacity-100 transition-opacity text-gray-400 hover:text-gray-700 hover:bg-gray-100"
+                            >
+                                {"⋯"}
+                            </button>
+                        }
+                    </div>
+                    if let Some(reveal) = reveal {
+                        <button
+                            onclick={reveal}
+                            class="mt-1 px-3 py-2 rounded-lg border border-dashed border-gray-300 text-sm text-gray-400 italic hover:text-gray-600"
+                        >
+                            {t("message-muted")}
+                        </button>
+                    } else if let Some(poll) = &m.poll {
+                        { view_poll(poll, &props.username, colors, &props.on_action) }
+                    } else if let Some(location) = &m.location {
+                        <a href={location.map_url()} target="_blank" rel="noopener noreferrer" class="block mt-1">
+                            if props.autoload_media {
+                                <img class="rounded-lg shadow-sm w-72 h-36 object-cover bg-gray-200" src={location.thumbnail_url()} alt={t("message-location-alt")}/>
+                            } else {
+                                <span class="text-sm text-blue-500 hover:underline">{t("message-open-location")}</span>
+                            }
+                        </a>
+                    } else {
+                        <div class={classes!(
+                            if compact { "px-2 py-1" } else { "p-3" }, "rounded-bubble", "shadow-sm", if compact { "mt-0.5" } else { "mt-1" },
+                            if own { "bg-accent text-white" } else { colors.bubble }
+                        )}>
+                            if let Some(gif) = gif_url {
+                                if props.autoload_media {
+                                    <img class="rounded-lg max-w-full" src={gif}/>
+                                } else {
+                                    <a href={gif} target="_blank" rel="noopener noreferrer" class="text-sm text-blue-500 hover:underline">{t("message-open-gif")}</a>
+                                }
+                            } else {
+                                <p dir="auto" class="text-message">{props.text.clone()}</p>
+                            }
+                        </div>
+                    }
+                    { view_translation(props.translation.as_ref(), colors) }
+                    if let Some(expires_at) = m.expires_at {
+                        <div class="text-xs text-gray-400 mt-1">
+                            {t_with("message-disappears", &[("time", &time::remaining(expires_at - props.now))])}
+                        </div>
+                    }
+                </div>
+            </div>
+            if let Some(at) = *menu {
+                { view_menu(items, at, colors, &props.on_action, close_menu) }
+            }
+        </>
+    }
+}
@@ -0,0 +1,89 @@
+use yew::prelude::*;
+
+use crate::components::theme_provider::use_theme;
+use crate::services::i18n::{t, t_with};
+
+#[derive(Default, Properties, PartialEq)]
+pub struct MessageComposerProps {
+    /// The text field; the parent reads it on submit and restores drafts into it.
+    pub input_ref: NodeRef,
+    #[prop_or_default]
+    pub error: Option<String>,
+    /// Display name of the user a direct message goes to.
+    #[prop_or_default]
+    pub direct_to: Option<String>,
+    /// Seconds until slow mode lets us post again.
+    #[prop_or_default]
+    pub cooldown: Option<u64>,
+    pub on_submit: Callback<()>,
+    pub on_share_location: Callback<()>,
+    pub on_close_direct: Callback<()>,
+}
+
+/// The message input with its send and share-location buttons.
+#[function_component(MessageComposer)]
+pub fn message_composer(props: &MessageComposerProps) -> Html {
+    let on_keypress = {
+        let on_submit = props.on_submit.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Enter" {
+                on_submit.emit(());
+            }
+        })
+    };
+    let cooldown = props.cooldown;
+    let colors = use_theme().classes();
+
+    html! {
+        <div class={classes!("border-t", "px-6", "py-3", colors.surface, colors.border)}>
+            if let Some(error) = &props.error {
+                <p class="text-sm text-red-500 mb-2">{error.clone()}</p>
+            }
+            if let Some(to) = &props.direct_to {
+                <div class="inline-flex items-center mb-2 px-3 py-1 rounded-full bg-purple-100 text-purple-700 text-sm">
+                    {t_with("composer-direct-to", &[("name", to)])}
+                    <button onclick={props.on_close_direct.reform(|_| ())} title={t("composer-back-to-group")} aria-label={t("composer-back-to-group")} class="ms-2 hover:text-purple-900">{"✕"}</button>
+                </div>
+            }
+            <div class="flex items-center">
+                <input
+                    ref={props.input_ref.clone()}
+                    type="text"
+                    dir="auto"
+                    aria-label={t("composer-label")}
+                    placeholder={t(if cooldown.is_some() { "composer-slow-mode-placeholder" } else { "composer-placeholder" })}
+                    disabled={cooldown.is_some()}
+                    class={classes!("block", "w-full", "px-4", "py-3", "rounded-full", "text-message", "outline-none", "focus:ring-2", "focus:ring-blue-400", "disabled:cursor-not-allowed", colors.input)}
+                    onkeypress={on_keypress}
+                />
+                <button
+                    onclick={props.on_share_location.reform(|_| ())}
+                    disabled={cooldown.is_some()}
+                    title={t("composer-share-location")}
+                    aria-label={t("composer-share-location")}
+                    class="ms-3 p-3 text-gray-500 hover:text-blue-500 rounded-full hover:bg-gray-100 transition disabled:opacity-40 disabled:cursor-not-allowed"
+                >
+                    <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M17.657 16.657L13.414 20.9a1.998 1.998 0 01-2.827 0l-4.244-4.243a8 8 0 1111.314 0z" />
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M15 11a3 3 0 11-6 0 3 3 0 016 0z" />
+                    </svg>
+                </button>
+                <button
+                    onclick={props.on_submit.reform(|_| ())}
+                    disabled={cooldown.is_some()}
+                    title={t(if cooldown.is_some() { "composer-slow-mode" } else { "composer-send" })}
+                    aria-label={t(if cooldown.is_some() { "composer-slow-mode" } else { "composer-send" })}
+                    class="ms-3 px-4 py-3 bg-accent hover:bg-accent-dark rounded-full text-white shadow-sm transition disabled:opacity-60 disabled:cursor-not-allowed"
+                >
+                    if let Some(seconds) = cooldown {
+                        <span class="block h-5 w-5 text-sm leading-5 text-center font-medium">{seconds}</span>
+                    } else {
+                        <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 19l9 2-9-18-9 18 9-2zm0 0v-8" />
+                        </svg>
+                    }
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+use yew::prelude::*;
+
+use crate::components::chat::{
+    anchor_message_id, display_name_of, masked_text, message_anchor, MessageData, Role, UserProfile,
+};
+use crate::components::message_bubble::{MessageAction, MessageBubble, Translated};
+use crate::components::skeleton::SkeletonRows;
+use crate::components::theme_provider::use_theme;
+use crate::services::chat_store::{ChatStore, Connection, StoreAction};
+use crate::services::i18n::t;
+use crate::services::name_list::NameList;
+use crate::services::profanity::ProfanityFilter;
+use crate::services::settings::Density;
+use crate::services::time;
+use crate::services::translate;
+
+/// How far above the bottom, in pixels, still counts as reading the newest messages.
+const LATEST_SLACK: i32 = 40;
+
+#[derive(Default, Properties, PartialEq)]
+pub struct MessageListProps {
+    pub username: String,
+    pub role: Role,
+    pub blocked: NameList,
+    pub muted: NameList,
+    /// Ids of muted users' messages the reader chose to show anyway.
+    pub revealed: HashSet<u64>,
+    #[prop_or_default]
+    pub highlighted: Option<u64>,
+    pub profanity: ProfanityFilter,
+    /// The room's blocked words, masked in other people's messages.
+    pub blocked_words: Vec<String>,
+    pub autoload_media: bool,
+    pub density: Density,
+    /// Translations the reader asked for, by message id.
+    #[prop_or_default]
+    pub translations: HashMap<u64, Translated>,
+    pub on_action: Callback<MessageAction>,
+}
+
+/// Keeps each bubble with its message when older ones arrive above or some
+/// disappear, so only the bubbles whose props changed render again.
+fn bubble_key(index: usize, m: &MessageData) -> String {
+    match (m.id, &m.poll, m.notice_id) {
+        (Some(id), _, _) => message_anchor(id),
+        (None, Some(poll), _) => format!("poll-{}", poll.id),
+        (None, None, Some(notice_id)) => format!("notice-{}", notice_id),
+        // Only from servers too old to number their messages.
+        (None, None, None) => format!("message-{}", index),
+    }
+}
+
+/// The pinned message banner and the scrolling message history.
+#[function_component(MessageList)]
+pub fn message_list(props: &MessageListProps) -> Html {
+    let store = use_context::<ChatStore>().expect("chat store to be provided");
+    let colors = use_theme().classes();
+    let room = store.room();
+    let active = use_state(|| None::<u64>);
+    let compact = props.density == Density::Compact;
+    let text_of = |m: &MessageData| masked_text(m, &props.username, &props.profanity, &props.blocked_words);
+
+    // Only one message is a Tab stop at a time, the last one focused or else
+    // the newest; the arrow keys, Home and End move between the rest.
+    let ids: Vec<u64> = room
+        .messages
+        .iter()
+        .filter(|m| !props.blocked.contains(&m.from))
+        .filter_map(|m| m.id)
+        .collect();
+    let tab_stop = (*active).filter(|id| ids.contains(id)).or_else(|| ids.last().copied());
+    let onkeydown = {
+        let active = active.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            let current = e.target_dyn_into::<Element>().and_then(|target| anchor_message_id(&target.id()));
+            let index = match ids.iter().position(|&id| Some(id) == current) {
+                Some(index) => index,
+                None => return,
+            };
+            let next = match e.key().as_str() {
+                "ArrowUp" => index.saturating_sub(1),
+                "ArrowDown" => (index + 1).min(ids.len() - 1),
+                "Home" => 0,
+                "End" => ids.len() - 1,
+                _ => return,
+            };
+            e.prevent_default();
+            active.set(Some(ids[next]));
+            let element = gloo::utils::document()
+                .get_element_by_id(&message_anchor(ids[next]))
+                .and_then(|element| element.dyn_into::<HtmlElement>().ok());
+            if let Some(element) = element {
+                let _ = element.focus();
+            }
+        })
+    };
+    // Where the reader was in each room, so coming back to one picks up
+    // there instead of at the newest message.
+    let log = use_node_ref();
+    let offsets = use_mut_ref(HashMap::<String, i32>::new);
+    let onscroll = {
+        let (offsets, room_name) = (offsets.clone(), store.active_room.clone());
+        let (store, was_at_latest) = (store.dispatcher(), store.at_latest);
+        Callback::from(move |e: Event| {
+            let log = e.target_unchecked_into::<Element>();
+            offsets.borrow_mut().insert(room_name.clone(), log.scroll_top());
+            // What comes in while the reader is further up isn't read yet.
+            let at_latest = log.scroll_height() - log.scroll_top() - log.client_height() <= LATEST_SLACK;
+            if at_latest != was_at_latest {
+                store.dispatch(StoreAction::ScrolledToLatest(at_latest));
+            }
+        })
+    };
+    {
+        let log = log.clone();
+        use_effect_with_deps(
+            move |room_name: &String| {
+                if let Some(log) = log.cast::<Element>() {
+                    let top = offsets.borrow().get(room_name).copied();
+                    log.set_scroll_top(top.unwrap_or_else(|| log.scroll_height()));
+                }
+                || ()
+            },
+            store.active_room.clone(),
+        );
+    }
+    let onfocusin = {
+        let active = active.clone();
+        Callback::from(move |e: FocusEvent| {
+            let focused = e.target_dyn_into::<Element>().and_then(|target| anchor_message_id(&target.id()));
+            if focused.is_some() && *active != focused {
+                active.set(focused);
+            }
+        })
+    };
+
+    let pinned = room.pinned.as_ref().map(|pinned| {
+        html! {
+            <div class="flex items-center bg-yellow-50 border-b border-yellow-200 px-6 py-2 text-sm">
+                <span class="font-medium text-yellow-800 me-2">{t("messages-pinned")}</span>
+                <span dir="auto" class="flex-1 truncate text-gray-700">
+                    {format!("{}: {}", display_name_of(&store.users, &pinned.from), text_of(pinned))}
+                </span>
+                if props.role.can_moderate() {
+                    <button onclick={props.on_action.reform(|_| MessageAction::Unpin)} class="ms-2 text-xs text-gray-500 hover:text-gray-700">{t("messages-unpin")}</button>
+                }
+            </div>
+        }
+    });
+
+    let loading_label = match store.connection {
+        Connection::Connected => t("messages-joining"),
+        _ => t("messages-connecting"),
+    };
+    let spinner = html! {
+        <svg class="animate-spin h-5 w-5 text-blue-500" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24">
+            <circle class="opacity-25" cx="12" cy="12" r="10" stroke="currentColor" stroke-width="4"></circle>
+            <path class="opacity-75" fill="currentColor" d="M4 12a8 8 0 018-8V0C5.373 0 0 5.373 0 12h4z"></path>
+        </svg>
+    };
+
+    let messages = if room.messages.is_empty() && store.is_loading() {
+        html! {
+            <SkeletonRows count={6} label={loading_label.clone()} class={if compact { "py-2" } else { "py-3" }}/>
+        }
+    } else if room.messages.is_empty() {
+        html! {
+            <div class="flex flex-col items-center justify-center h-full text-gray-500">
+                <svg xmlns="http://www.w3.org/2000/svg" class="h-16 w-16 mb-4 text-gray-300" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 12h.01M12 12h.01M16 12h.01M21 12c0 4.418-4.03 8-9 8a9.863 9.863 0 01-4.255-.949L3 20l1.395-3.72C3.512 15.042 3 13.574 3 12c0-4.418 4.03-8 9-8s9 3.582 9 8z" />
+                </svg>
+                {t("messages-empty")}
+            </div>
+        }
+    } else {
+        // A day's messages start with its date; notices without a time stay in
+        // the day they came up in.
+        let mut last_day = None::<f64>;
+        let translatable = translate::endpoint().is_some();
+        room.messages.iter().enumerate().filter(|(_, m)| !props.blocked.contains(&m.from)).map(|(index, m)| {
+            let new_day = m.time.filter(|&sent| !last_day.is_some_and(|day| time::same_day(day, sent)));
+            last_day = m.time.or(last_day);
+            let author = store
+                .users
+                .iter()
+                .find(|u| u.name == m.from)
+                .cloned()
+                .unwrap_or_else(|| {
+                    Rc::new(UserProfile {
+                        name: m.from.clone(),
+                        ..Default::default()
+                    })
+                });
+            let collapsed = props.muted.contains(&m.from) && m.id.is_some_and(|id| !props.revealed.contains(&id));
+            let highlighted = m.id.is_some() && props.highlighted == m.id;
+
+            html! {
+                <key={bubble_key(index, m)}>
+                    if let Some(sent) = new_day {
+                        <div role="separator" class={classes!("flex", "items-center", if compact { "my-2" } else { "my-4" }, "text-xs", "font-medium", "text-gray-400")}>
+                            <div class={classes!("flex-1", "border-t", colors.border)}></div>
+                            <span class="mx-3">{time::day(sent, store.now)}</span>
+                            <div class={classes!("flex-1", "border-t", colors.border)}></div>
+                        </div>
+                    }
+                    <MessageBubble
+                        message={m.clone()}
+                        author={author}
+                        recipient={m.to.as_deref().map(|to| display_name_of(&store.users, to))}
+                        text={text_of(m)}
+                        username={props.username.clone()}
+                        role={props.role}
+                        collapsed={collapsed}
+                        highlighted={highlighted}
+                        autoload_media={props.autoload_media}
+                        density={props.density}
+                        now={if m.expires_at.is_some() { store.now } else { 0.0 }}
+                        translatable={translatable}
+                        translation={m.id.and_then(|id| props.translations.get(&id).cloned())}
+                        tab_stop={m.id.is_some() && m.id == tab_stop}
+                        on_action={props.on_action.clone()}
+                    />
+                </>
+            }
+        }).collect::<Html>()
+    };
+
+    html! {
+        <>
+            { for pinned }
+            if store.is_loading() && !room.messages.is_empty() {
+                <div class="flex items-center justify-center bg-blue-50 border-b border-blue-100 px-6 py-2 text-sm text-blue-700">
+                    {spinner}
+                    <span class="ms-2">{loading_label}</span>
+                </div>
+            }
+            <div
+                ref={log}
+                role="log"
+                aria-live="off"
+                aria-label={t("messages-label")}
+                class={classes!("flex-1", "overflow-y-auto", if compact { "px-4 py-2" } else { "p-6" }, colors.page)}
+                style="scrollbar-width: thin;"
+                {onscroll}
+                {onkeydown}
+                {onfocusin}
+            >
+                if store.is_loading_history() {
+                    <SkeletonRows count={3} label={t("messages-loading-history")} class={if compact { "py-2" } else { "py-3" }}/>
+                }
+                {messages}
+            </div>
+        </>
+    }
+}
@@ -1,2 +1,29 @@
+pub mod admin;
+pub mod avatar;
+pub mod avatar_gallery;
+pub mod avatar_picker;
 pub mod chat;
-pub mod login;
\ No newline at end of file
+pub mod chat_header;
+pub mod command_palette;
+pub mod create_account;
+pub mod debug_panel;
+pub mod error_boundary;
+pub mod field;
+pub mod focus_trap;
+pub mod gallery;
+pub mod login;
+pub mod message_bubble;
+pub mod message_composer;
+pub mod message_list;
+pub mod modal;
+pub mod not_found;
+pub mod oauth_callback;
+pub mod password_reset;
+pub mod password_sign_in;
+pub mod profile;
+pub mod require_user;
+pub mod settings;
+pub mod skeleton;
+pub mod theme_provider;
+pub mod toast;
+pub mod user_sidebar;
\ No newline at end of file
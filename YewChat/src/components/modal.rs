@@ -0,0 +1,158 @@
+use std::cell::Cell;
+
+use yew::prelude::*;
+
+use crate::components::focus_trap::FocusTrap;
+use crate::services::i18n::t;
+
+thread_local! {
+    /// How many modals are open; each new one goes on top of the rest.
+    static OPEN: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Keeps the page behind still while any modal is open.
+fn set_open(count: usize) {
+    OPEN.with(|open| open.set(count));
+    if let Some(body) = gloo::utils::document().body() {
+        let _ = body.class_list().toggle_with_force("overflow-hidden", count > 0);
+    }
+}
+
+fn default_card() -> Classes {
+    classes!("bg-white", "w-80", "p-6")
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ModalProps {
+    pub title: String,
+    #[prop_or_default]
+    pub description: Option<String>,
+    /// Centres the text, for dialogs that are just a question and buttons.
+    #[prop_or_default]
+    pub centered: bool,
+    /// Leaves the heading to the children, e.g. a profile card with its own;
+    /// screen readers still announce the title.
+    #[prop_or_default]
+    pub hide_title: bool,
+    /// A ✕ in the corner that calls `on_close`.
+    #[prop_or_default]
+    pub close_button: bool,
+    /// Near the top of the screen rather than the middle, for dialogs that
+    /// grow as you type.
+    #[prop_or_default]
+    pub top: bool,
+    /// Background, width and padding of the card.
+    #[prop_or_else(default_card)]
+    pub class: Classes,
+    /// Called on Escape and on a press outside the card; leave it out for
+    /// questions that must be answered.
+    #[prop_or_default]
+    pub on_close: Option<Callback<()>>,
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// A dialog card over a dimmed page, holding keyboard focus while open.
+/// It is drawn at the end of the body above any modal already open, so one
+/// can be opened from inside another, and only the top one hears Escape.
+#[function_component(Modal)]
+pub fn modal(props: &ModalProps) -> Html {
+    // Fixed when opened: a modal stays above the ones it was opened over.
+    let depth = use_state(|| OPEN.with(Cell::get));
+    use_effect_with_deps(
+        |_| {
+            set_open(OPEN.with(Cell::get) + 1);
+            || set_open(OPEN.with(Cell::get).saturating_sub(1))
+        },
+        (),
+    );
+
+    let on_backdrop = props.on_close.clone().map(|on_close| {
+        Callback::from(move |e: PointerEvent| {
+            if e.target() == e.current_target() {
+                on_close.emit(());
+            }
+        })
+    });
+
+    let modal = html! {
+        <div
+            onpointerdown={on_backdrop}
+            class={classes!(
+                "fixed", "inset-0", "flex", "justify-center", "bg-black", "bg-opacity-30",
+                if props.top { "items-start pt-24" } else { "items-center" }
+            )}
+            style={format!("z-index: {};", 40 + *depth * 10)}
+        >
+            <FocusTrap
+                label={props.title.clone()}
+                on_close={props.on_close.clone()}
+                class={classes!("relative", "rounded-xl", "shadow-xl", props.class.clone(), props.centered.then_some("text-center"))}
+            >
+                if let Some(on_close) = props.on_close.as_ref().filter(|_| props.close_button) {
+                    <button
+                        onclick={on_close.reform(|_| ())}
+                        title={t("dialog-close")}
+                        aria-label={t("dialog-close")}
+                        class="absolute top-3 end-3 text-gray-400 hover:text-gray-600"
+                    >
+                        {"✕"}
+                    </button>
+                }
+                if !props.hide_title {
+                    <h3 class="text-lg font-semibold text-gray-800">{props.title.clone()}</h3>
+                }
+                if let Some(description) = &props.description {
+                    <p class={classes!("text-sm", if props.centered { "mt-2 text-gray-600" } else { "mt-1 text-gray-500" })}>
+                        {description.clone()}
+                    </p>
+                }
+                { for props.children.iter() }
+            </FocusTrap>
+        </div>
+    };
+
+    create_portal(modal, gloo::utils::body().into())
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ConfirmDialogProps {
+    pub title: String,
+    #[prop_or_default]
+    pub description: Option<String>,
+    pub confirm_label: String,
+    /// Paints the confirm button red, for things that can't be undone.
+    #[prop_or_default]
+    pub destructive: bool,
+    pub on_confirm: Callback<()>,
+    pub on_cancel: Callback<()>,
+}
+
+/// Asks before doing something, with the safe choice first so Enter on the
+/// freshly opened dialog doesn't go through with it.
+#[function_component(ConfirmDialog)]
+pub fn confirm_dialog(props: &ConfirmDialogProps) -> Html {
+    html! {
+        <Modal
+            title={props.title.clone()}
+            description={props.description.clone()}
+            centered=true
+            on_close={props.on_cancel.clone()}
+        >
+            <div class="flex mt-4">
+                <button onclick={props.on_cancel.reform(|_| ())} class="flex-1 py-2 rounded-lg border border-gray-200 text-sm text-gray-700 hover:bg-gray-50">
+                    {t("dialog-cancel")}
+                </button>
+                <button
+                    onclick={props.on_confirm.reform(|_| ())}
+                    class={classes!(
+                        "flex-1", "ms-2", "py-2", "rounded-lg", "text-sm", "text-white",
+                        if props.destructive { "bg-red-600 hover:bg-red-700" } else { "bg-accent hover:bg-accent-dark" }
+                    )}
+                >
+                    {props.confirm_label.clone()}
+                </button>
+            </div>
+        </Modal>
+    }
+}
@@ -0,0 +1,23 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::services::i18n::t;
+use crate::Route;
+
+#[function_component(NotFound)]
+pub fn not_found() -> Html {
+    html! {
+        <div class="bg-gradient-to-r from-indigo-600 to-purple-600 min-h-screen w-full flex items-center">
+            <div class="container mx-auto px-4">
+                <div class="max-w-md mx-auto bg-white rounded-xl shadow-lg p-8 text-center">
+                    <p class="text-5xl font-bold text-purple-600">{"404"}</p>
+                    <h1 class="mt-2 text-xl font-bold text-gray-800">{t("not-found-title")}</h1>
+                    <p class="mt-2 mb-6 text-gray-600">{t("not-found-body")}</p>
+                    <Link<Route> to={Route::Chat} classes="inline-block px-4 py-2 rounded-lg bg-purple-600 hover:bg-purple-700 text-white">
+                        {t("not-found-back")}
+                    </Link<Route>>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,63 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::functional::*;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::services::i18n::t;
+use crate::services::oauth;
+use crate::Route;
+use crate::{User, UserAction};
+
+/// Landing page for the provider's redirect: exchanges the code, fills in the
+/// user context and continues to the chat.
+#[function_component(OAuthCallback)]
+pub fn oauth_callback() -> Html {
+    let user = use_context::<User>().expect("No context found.");
+    let history = use_history();
+    let error = use_state(|| None::<String>);
+
+    {
+        let error = error.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match oauth::complete().await {
+                        Ok(profile) => {
+                            user.dispatch(UserAction::SignIn {
+                                username: profile.name,
+                                avatar: profile.avatar_url,
+                                token: Some(profile.access_token),
+                                account_token: None,
+                                captcha: None,
+                            });
+                            if let Some(history) = history {
+                                history.replace(Route::Chat);
+                            }
+                        }
+                        Err(e) => error.set(Some(e)),
+                    }
+                });
+                || ()
+            },
+            (),
+        );
+    }
+
+    html! {
+        <div class="bg-gradient-to-r from-indigo-600 to-purple-600 min-h-screen w-full flex items-center">
+            <div class="container mx-auto px-4">
+                <div class="max-w-md mx-auto bg-white rounded-xl shadow-lg p-6 text-center">
+                    if let Some(error) = &*error {
+                        <h1 class="text-xl font-bold text-gray-800 mb-2">{t("oauth-failed")}</h1>
+                        <p class="text-gray-600 mb-6">{error.clone()}</p>
+                        <Link<Route> to={Route::Login} classes="inline-block px-4 py-2 rounded-lg bg-purple-600 hover:bg-purple-700 text-white">
+                            {t("create-account-back")}
+                        </Link<Route>>
+                    } else {
+                        <p class="text-gray-600">{t("oauth-signing-in")}</p>
+                    }
+                </div>
+            </div>
+        </div>
+    }
+}
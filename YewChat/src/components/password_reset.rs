@@ -0,0 +1,158 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::functional::*;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::field::{setter, Field};
+use crate::services::account;
+use crate::services::i18n::{t, t_with};
+use crate::Route;
+
+fn card(title: String, body: Html) -> Html {
+    html! {
+        <div class="bg-gradient-to-r from-indigo-600 to-purple-600 min-h-screen w-full flex items-center">
+            <div class="container mx-auto px-4">
+                <div class="max-w-md mx-auto bg-white rounded-xl shadow-lg p-6">
+                    <h1 class="text-2xl font-bold text-center text-gray-800 mb-6">{title}</h1>
+                    {body}
+                    <p class="mt-4 text-center text-sm text-gray-500">
+                        <Link<Route> to={Route::Login} classes="text-purple-600 hover:underline">{t("create-account-back")}</Link<Route>>
+                    </p>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Asks for an email address and has a reset link sent to it.
+#[function_component(ForgotPassword)]
+pub fn forgot_password() -> Html {
+    let email = use_state(String::new);
+    let submitting = use_state(|| false);
+    let sent = use_state(|| false);
+    let server_error = use_state(|| None::<String>);
+
+    let email_error = account::validate_email(&email).err();
+    let valid = !email.is_empty() && email_error.is_none();
+
+    let onsubmit = {
+        let email = email.clone();
+        let (submitting, sent, server_error) = (submitting.clone(), sent.clone(), server_error.clone());
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            let email = email.trim().to_string();
+            let (submitting, sent, server_error) = (submitting.clone(), sent.clone(), server_error.clone());
+            submitting.set(true);
+            spawn_local(async move {
+                match account::request_password_reset(&email).await {
+                    Ok(()) => sent.set(true),
+                    Err(e) => server_error.set(Some(e)),
+                }
+                submitting.set(false);
+            });
+        })
+    };
+
+    if *sent {
+        return card(
+            t("reset-sent-title"),
+            html! {
+                <p class="text-center text-gray-600">
+                    {t_with("reset-sent-body", &[("email", email.trim())])}
+                </p>
+            },
+        );
+    }
+
+    card(
+        t("reset-forgot-title"),
+        html! {
+            <form onsubmit={onsubmit}>
+                <p class="mb-4 text-sm text-gray-600">{t("reset-forgot-body")}</p>
+                if let Some(error) = &*server_error {
+                    <p class="mb-4 px-4 py-3 rounded-lg bg-red-50 text-sm text-red-700">{error.clone()}</p>
+                }
+                <Field label={t("field-email")} kind="email" value={(*email).clone()} error={email_error} oninput={setter(&email)}/>
+                <button
+                    type="submit"
+                    disabled={!valid || *submitting}
+                    class="w-full rounded-lg bg-purple-600 hover:bg-purple-700 text-white font-medium py-3 px-4 transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                >
+                    {t(if *submitting { "reset-sending" } else { "reset-send" })}
+                </button>
+            </form>
+        },
+    )
+}
+
+/// Landing page for the emailed link (`/reset-password?token=...`).
+#[function_component(ResetPassword)]
+pub fn reset_password() -> Html {
+    let token = use_state(account::reset_token);
+    let password = use_state(String::new);
+    let confirm = use_state(String::new);
+    let submitting = use_state(|| false);
+    let done = use_state(|| false);
+    let server_error = use_state(|| None::<String>);
+
+    let password_error = account::validate_password(&password).err();
+    let confirm_error = (*confirm != *password).then(|| t("create-account-mismatch"));
+    let valid = password_error.is_none() && confirm_error.is_none() && !confirm.is_empty();
+
+    let onsubmit = {
+        let (token, password) = (token.clone(), password.clone());
+        let (submitting, done, server_error) = (submitting.clone(), done.clone(), server_error.clone());
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            let token = (*token).clone().unwrap_or_default();
+            let password = (*password).clone();
+            let (submitting, done, server_error) = (submitting.clone(), done.clone(), server_error.clone());
+            submitting.set(true);
+            spawn_local(async move {
+                match account::reset_password(&token, &password).await {
+                    Ok(()) => done.set(true),
+                    Err(e) => server_error.set(Some(e)),
+                }
+                submitting.set(false);
+            });
+        })
+    };
+
+    if token.is_none() {
+        return card(
+            t("reset-title"),
+            html! {
+                <p class="text-center text-gray-600">
+                    {t("reset-missing-token")}{" "}
+                    <Link<Route> to={Route::ForgotPassword} classes="text-purple-600 hover:underline">{t("reset-request-new")}</Link<Route>>
+                </p>
+            },
+        );
+    }
+    if *done {
+        return card(
+            t("reset-done-title"),
+            html! { <p class="text-center text-gray-600">{t("reset-done-body")}</p> },
+        );
+    }
+
+    card(
+        t("reset-choose-title"),
+        html! {
+            <form onsubmit={onsubmit}>
+                if let Some(error) = &*server_error {
+                    <p class="mb-4 px-4 py-3 rounded-lg bg-red-50 text-sm text-red-700">{error.clone()}</p>
+                }
+                <Field label={t("field-new-password")} kind="password" value={(*password).clone()} error={password_error} oninput={setter(&password)}/>
+                <Field label={t("field-confirm-password")} kind="password" value={(*confirm).clone()} error={confirm_error} oninput={setter(&confirm)}/>
+                <button
+                    type="submit"
+                    disabled={!valid || *submitting}
+                    class="w-full rounded-lg bg-purple-600 hover:bg-purple-700 text-white font-medium py-3 px-4 transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                >
+                    {t(if *submitting { "reset-saving" } else { "reset-set-password" })}
+                </button>
+            </form>
+        },
+    )
+}
@@ -0,0 +1,134 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::functional::*;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::field::{setter, Field};
+use crate::services::account::{self, Account, Credentials, SignIn};
+use crate::services::i18n::t;
+use crate::Route;
+use crate::{User, UserAction};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// Whether the login screen shows a CAPTCHA that has to be solved first.
+    #[prop_or_default]
+    pub captcha_required: bool,
+    /// The solved CAPTCHA, spent when the chat registers.
+    #[prop_or_default]
+    pub captcha_token: Option<String>,
+}
+
+/// Email and password sign-in for registered accounts, followed by a TOTP
+/// code when the account has two-factor switched on.
+#[function_component(PasswordSignIn)]
+pub fn password_sign_in(props: &Props) -> Html {
+    let user = use_context::<User>().expect("No context found.");
+    let history = use_history();
+    let email = use_state(String::new);
+    let password = use_state(String::new);
+    let code = use_state(String::new);
+    // Set once the password was accepted and the server wants a code.
+    let challenge = use_state(|| None::<String>);
+    let submitting = use_state(|| false);
+    let server_error = use_state(|| None::<String>);
+
+    let signed_in = {
+        let (user, history) = (user.clone(), history.clone());
+        let captcha_token = props.captcha_token.clone();
+        move |account: Account| {
+            user.dispatch(UserAction::SignIn {
+                username: account.username,
+                avatar: None,
+                token: None,
+                account_token: account.token,
+                captcha: captcha_token.clone(),
+            });
+            if let Some(history) = &history {
+                history.push(Route::Chat);
+            }
+        }
+    };
+
+    let onsubmit = {
+        let (email, password, code, challenge) = (email.clone(), password.clone(), code.clone(), challenge.clone());
+        let (submitting, server_error) = (submitting.clone(), server_error.clone());
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            let credentials = Credentials {
+                email: email.trim().to_string(),
+                password: (*password).clone(),
+            };
+            let pending = (*challenge).clone();
+            let code = (*code).clone();
+            let (challenge, submitting, server_error) = (challenge.clone(), submitting.clone(), server_error.clone());
+            let signed_in = signed_in.clone();
+            submitting.set(true);
+            server_error.set(None);
+            spawn_local(async move {
+                let result = match pending {
+                    Some(pending) => account::verify_code(&pending, &code).await,
+                    None => account::sign_in(&credentials).await,
+                };
+                match result {
+                    Ok(SignIn::SignedIn(account)) => {
+                        signed_in(account);
+                        return;
+                    }
+                    Ok(SignIn::NeedsCode(pending)) => challenge.set(Some(pending)),
+                    Ok(SignIn::Expired) => {
+                        challenge.set(None);
+                        server_error.set(Some(t("account-challenge-expired")));
+                    }
+                    Err(e) => server_error.set(Some(e)),
+                }
+                submitting.set(false);
+            });
+        })
+    };
+
+    let (valid, fields) = if challenge.is_some() {
+        let code_error = account::validate_code(&code).err();
+        (
+            code_error.is_none(),
+            html! {
+                <>
+                    <p class="mb-4 text-sm text-gray-600">{t("sign-in-enter-code")}</p>
+                    <Field label={t("field-code")} value={(*code).clone()} error={code_error} oninput={setter(&code)}/>
+                </>
+            },
+        )
+    } else {
+        (
+            account::validate_email(&email).is_ok() && !password.is_empty(),
+            html! {
+                <>
+                    <Field label={t("field-email")} kind="email" value={(*email).clone()} error={account::validate_email(&email).err()} oninput={setter(&email)}/>
+                    <Field label={t("field-password")} kind="password" value={(*password).clone()} error={None::<String>} oninput={setter(&password)}/>
+                </>
+            },
+        )
+    };
+
+    html! {
+        <form onsubmit={onsubmit}>
+            if let Some(error) = &*server_error {
+                <p class="mb-4 px-4 py-3 rounded-lg bg-red-50 text-sm text-red-700">{error.clone()}</p>
+            }
+            {fields}
+            <button
+                type="submit"
+                disabled={!valid || *submitting || (props.captcha_required && props.captcha_token.is_none())}
+                class="w-full rounded-lg border border-purple-600 text-purple-600 hover:bg-purple-50 font-medium py-3 px-4 transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+            >
+                {
+                    match (*submitting, challenge.is_some()) {
+                        (true, _) => t("sign-in-submitting"),
+                        (false, true) => t("sign-in-verify"),
+                        (false, false) => t("sign-in"),
+                    }
+                }
+            </button>
+        </form>
+    }
+}
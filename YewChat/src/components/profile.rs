@@ -0,0 +1,121 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::functional::*;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::avatar::Avatar;
+use crate::components::chat::view_role_badge;
+use crate::services::i18n::{t, t_with};
+use crate::services::profile::{self as profile_service, PublicProfile};
+use crate::services::time;
+use crate::{Route, User, DEFAULT_ROOM};
+
+#[derive(Properties, PartialEq)]
+pub struct ProfileProps {
+    pub name: String,
+}
+
+/// Full-page profile for any user, reachable from the sidebar and from
+/// message author names.
+#[function_component(Profile)]
+pub fn profile(props: &ProfileProps) -> Html {
+    let user = use_context::<User>().expect("No context found.");
+    let history = use_history();
+    let loaded = use_state(|| None::<Result<PublicProfile, String>>);
+
+    {
+        let loaded = loaded.clone();
+        use_effect_with_deps(
+            move |name: &String| {
+                let name = name.clone();
+                loaded.set(None);
+                spawn_local(async move { loaded.set(Some(profile_service::fetch(&name).await)) });
+                || ()
+            },
+            props.name.clone(),
+        );
+    }
+
+    let body = match &*loaded {
+        None => html! { <p class="text-center text-gray-500">{t("profile-loading")}</p> },
+        Some(Err(e)) => html! { <p class="text-center text-gray-600">{e.clone()}</p> },
+        Some(Ok(profile)) => {
+            let is_self = user.username == profile.name;
+            // We're always in the default room while signed in.
+            let shared_rooms: Vec<&String> = profile.rooms.iter().filter(|r| r.as_str() == DEFAULT_ROOM).collect();
+            let open_direct = {
+                let (history, name) = (history.clone(), profile.name.clone());
+                Callback::from(move |_| {
+                    if let Some(history) = &history {
+                        if let Err(e) = history.push_with_query(Route::Chat, [("dm", name.as_str())]) {
+                            log::error!("failed to open direct message: {:?}", e);
+                        }
+                    }
+                })
+            };
+            let now = js_sys::Date::now();
+
+            html! {
+                <>
+                    <div class="flex flex-col items-center">
+                        <Avatar name={profile.name.clone()} src={profile.avatar()} class="w-28 h-28 rounded-full border-4 border-white shadow text-4xl"/>
+                        <h1 dir="auto" class="mt-4 text-2xl font-bold text-gray-800">{profile.display_name()}</h1>
+                        <p class="text-sm text-gray-400">
+                            {format!("@{}", profile.name)}
+                            { view_role_badge(profile.role) }
+                        </p>
+                        <p class="mt-1 text-sm text-gray-500">
+                            {
+                                match (profile.joined_at, profile.last_seen) {
+                                    (Some(joined_at), _) => t_with("profile-online-joined", &[("when", &time::relative(now - joined_at))]),
+                                    (None, Some(last_seen)) => t_with("profile-last-seen", &[("when", &time::last_seen(last_seen, now))]),
+                                    (None, None) => t("profile-offline"),
+                                }
+                            }
+                        </p>
+                        if let Some(status) = &profile.status {
+                            <p dir="auto" class="mt-2 text-gray-600 italic">{status.clone()}</p>
+                        }
+                    </div>
+
+                    <h2 class="mt-8 mb-2 text-sm font-semibold uppercase tracking-wide text-gray-400">{t("profile-about")}</h2>
+                    <p dir="auto" class="text-gray-700 whitespace-pre-line">{profile.bio.clone().filter(|b| !b.is_empty()).unwrap_or_else(|| t("profile-no-bio"))}</p>
+
+                    if !is_self {
+                        <h2 class="mt-6 mb-2 text-sm font-semibold uppercase tracking-wide text-gray-400">{t("profile-shared-rooms")}</h2>
+                        if shared_rooms.is_empty() {
+                            <p class="text-gray-500">{t("profile-no-shared-rooms")}</p>
+                        }
+                        {
+                            shared_rooms.into_iter().map(|room| html! {
+                                <Link<Route> to={Route::ChatRoom { room: room.clone() }} classes="inline-block me-2 px-3 py-1 rounded-full bg-blue-50 text-sm text-blue-600 hover:bg-blue-100">
+                                    {format!("#{}", room)}
+                                </Link<Route>>
+                            }).collect::<Html>()
+                        }
+                        <button
+                            onclick={open_direct}
+                            disabled={!profile.online()}
+                            class="mt-8 w-full py-3 rounded-lg bg-accent hover:bg-accent-dark text-white font-medium disabled:opacity-50 disabled:cursor-not-allowed"
+                        >
+                            {t(if profile.online() { "profile-message" } else { "profile-cant-message" })}
+                        </button>
+                    }
+                </>
+            }
+        }
+    };
+
+    html! {
+        <div class="w-full min-h-screen overflow-y-auto bg-gray-50">
+            <div class="max-w-xl mx-auto px-6 py-8">
+                <div class="mb-6">
+                    <Link<Route> to={Route::Chat} classes="text-sm text-blue-500 hover:underline">{t("profile-back")}</Link<Route>>
+                </div>
+                <div class="bg-white rounded-xl shadow-sm p-8">
+                    {body}
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,24 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::Route;
+use crate::User;
+
+#[derive(Properties, PartialEq)]
+pub struct RequireUserProps {
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// Renders its children only once a username has been chosen; otherwise
+/// sends the visitor to the login screen instead of joining anonymously.
+#[function_component(RequireUser)]
+pub fn require_user(props: &RequireUserProps) -> Html {
+    let user = use_context::<User>().expect("No context found.");
+
+    if user.username.is_empty() {
+        html! { <Redirect<Route> to={Route::Login}/> }
+    } else {
+        html! { <>{ for props.children.iter() }</> }
+    }
+}
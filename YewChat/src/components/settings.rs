@@ -0,0 +1,646 @@
+use qrcode::render::svg;
+use qrcode::QrCode;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::functional::*;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::field::{setter, Field};
+use crate::components::theme_provider::use_theme;
+use crate::services::account::{self, Credentials, TotpSecret, TotpSetup};
+use crate::services::feature_flags::{Feature, FeatureFlags};
+use crate::services::i18n::{self, t, t_with, Locale};
+use crate::services::install_prompt::use_install_prompt;
+use crate::services::push::{self, PushState};
+use crate::services::settings::{notifications_blocked, request_notification_permission, Density, SettingsStore};
+use crate::services::telemetry;
+use crate::services::theme::{Accent, Appearance, BubbleStyle, FontSize, Motion, Theme};
+use crate::services::time::{self, Clock};
+use crate::services::translate;
+use crate::Route;
+
+/// An `<img>`-ready data URL of `text` as a QR code.
+fn qr_data_url(text: &str) -> Option<String> {
+    let svg = QrCode::new(text.as_bytes())
+        .map_err(|e| log::error!("failed to encode QR code: {:?}", e))
+        .ok()?
+        .render::<svg::Color>()
+        .min_dimensions(180, 180)
+        .build();
+    Some(format!("data:image/svg+xml,{}", js_sys::encode_uri_component(&svg)))
+}
+
+/// Turns on TOTP for an account: confirm the password, scan the secret, then
+/// prove the app works by entering a code. Replacing an app that is already
+/// set up also takes a code from it.
+#[function_component(TwoFactorSetup)]
+pub fn two_factor_setup() -> Html {
+    let email = use_state(String::new);
+    let password = use_state(String::new);
+    let code = use_state(String::new);
+    let current_code = use_state(String::new);
+    let needs_current_code = use_state(|| false);
+    let secret = use_state(|| None::<TotpSecret>);
+    let enabled = use_state(|| false);
+    let submitting = use_state(|| false);
+    let server_error = use_state(|| None::<String>);
+
+    let onsubmit = {
+        let (email, password, code, secret, enabled) =
+            (email.clone(), password.clone(), code.clone(), secret.clone(), enabled.clone());
+        let (current_code, needs_current_code) = (current_code.clone(), needs_current_code.clone());
+        let (submitting, server_error) = (submitting.clone(), server_error.clone());
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            let credentials = Credentials {
+                email: email.trim().to_string(),
+                password: (*password).clone(),
+            };
+            let code = (*code).clone();
+            let current_code = needs_current_code.then(|| (*current_code).clone());
+            let (secret, enabled, needs_current_code, submitting, server_error) = (
+                secret.clone(),
+                enabled.clone(),
+                needs_current_code.clone(),
+                submitting.clone(),
+                server_error.clone(),
+            );
+            submitting.set(true);
+            server_error.set(None);
+            spawn_local(async move {
+                if secret.is_some() {
+                    match account::enable_totp(&credentials, &code).await {
+                        Ok(_) => enabled.set(true),
+                        Err(e) => server_error.set(Some(e)),
+                    }
+                } else {
+                    match account::begin_totp_setup(&credentials, current_code.as_deref()).await {
+                        Ok(TotpSetup::Secret(new_secret)) => secret.set(Some(new_secret)),
+                        Ok(TotpSetup::NeedsCurrentCode) => needs_current_code.set(true),
+                        Err(e) => server_error.set(Some(e)),
+                    }
+                }
+                submitting.set(false);
+            });
+        })
+    };
+
+    if *enabled {
+        return html! {
+            <p class="text-sm text-green-700">{t("two-factor-on")}</p>
+        };
+    }
+
+    let (valid, body) = match &*secret {
+        Some(secret) => {
+            let code_error = account::validate_code(&code).err();
+            (
+                code_error.is_none(),
+                html! {
+                    <>
+                        <p class="mb-3 text-sm text-gray-600">{t("two-factor-scan")}</p>
+                        <div class="flex flex-col items-center mb-4">
+                            if let Some(src) = qr_data_url(&secret.uri) {
+                                <img src={src} alt={t("two-factor-qr-alt")} class="w-44 h-44"/>
+                            }
+                            <code class="mt-2 px-3 py-1 rounded bg-gray-100 text-sm tracking-wider break-all">{secret.secret.clone()}</code>
+                        </div>
+                        <Field label={t("two-factor-code")} value={(*code).clone()} error={code_error} oninput={setter(&code)}/>
+                    </>
+                },
+            )
+        }
+        None => {
+            let current_code_error = needs_current_code.then(|| account::validate_code(&current_code).err()).flatten();
+            (
+                account::validate_email(&email).is_ok() && !password.is_empty() && current_code_error.is_none(),
+                html! {
+                    <>
+                        <p class="mb-3 text-sm text-gray-600">
+                            {t(if *needs_current_code { "account-code-required" } else { "two-factor-confirm" })}
+                        </p>
+                        <Field label={t("field-email")} kind="email" value={(*email).clone()} error={account::validate_email(&email).err()} oninput={setter(&email)}/>
+                        <Field label={t("field-password")} kind="password" value={(*password).clone()} error={None::<String>} oninput={setter(&password)}/>
+                        if *needs_current_code {
+                            <Field label={t("two-factor-current-code")} value={(*current_code).clone()} error={current_code_error} oninput={setter(&current_code)}/>
+                        }
+                    </>
+                },
+            )
+        }
+    };
+
+    html! {
+        <form onsubmit={onsubmit}>
+            if let Some(error) = &*server_error {
+                <p class="mb-4 px-4 py-3 rounded-lg bg-red-50 text-sm text-red-700">{error.clone()}</p>
+            }
+            {body}
+            <button
+                type="submit"
+                disabled={!valid || *submitting}
+                class="rounded-lg bg-purple-600 hover:bg-purple-700 text-white font-medium py-2 px-4 transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+            >
+                {t(if secret.is_some() { "two-factor-turn-on" } else { "two-factor-continue" })}
+            </button>
+        </form>
+    }
+}
+
+/// Stores `change` into `settings` and persists the result.
+fn update(settings: &UseStateHandle<SettingsStore>, change: impl Fn(&mut SettingsStore) + 'static) -> impl Fn() {
+    let settings = settings.clone();
+    move || {
+        let mut next = (*settings).clone();
+        change(&mut next);
+        next.save();
+        settings.set(next);
+    }
+}
+
+#[function_component(Settings)]
+pub fn settings() -> Html {
+    let settings = use_state(SettingsStore::load);
+    let features = use_state(FeatureFlags::load);
+    let theme = use_theme();
+    let appearance = use_state(Appearance::load);
+    let locale = use_state(i18n::locale);
+    // Unknown until the browser has been asked.
+    let install = use_install_prompt();
+    let push_state = use_state(|| None::<PushState>);
+    let push_failed = use_state(|| false);
+
+    {
+        let push_state = push_state.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move { push_state.set(Some(push::state().await)) });
+                || ()
+            },
+            (),
+        );
+    }
+
+    let on_locale_change = {
+        let locale = locale.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(next) = Locale::ALL.into_iter().find(|l| l.code() == select.value()) {
+                i18n::set_locale(next);
+                locale.set(next);
+            }
+        })
+    };
+    let on_theme_change = {
+        let set = theme.set.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            set.emit(Theme::ALL.into_iter().find(|choice| choice.label() == select.value()).unwrap_or(Theme::System));
+        })
+    };
+    let on_font_size_change = {
+        let set = theme.set_font_size.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            set.emit(FontSize::ALL.into_iter().find(|size| size.label() == select.value()).unwrap_or_default());
+        })
+    };
+    let save_appearance = {
+        let appearance = appearance.clone();
+        Callback::from(move |next: Appearance| {
+            next.save();
+            next.apply();
+            appearance.set(next);
+        })
+    };
+    let pick_accent = |accent: Accent| {
+        let next = Appearance { accent, ..*appearance };
+        save_appearance.reform(move |_: MouseEvent| next)
+    };
+    let on_bubbles_change = {
+        let current = *appearance;
+        save_appearance.reform(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let bubbles = BubbleStyle::ALL.into_iter().find(|b| b.label() == select.value()).unwrap_or(current.bubbles);
+            Appearance { bubbles, ..current }
+        })
+    };
+    let on_motion_change = {
+        let current = *appearance;
+        save_appearance.reform(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let motion = Motion::ALL.into_iter().find(|m| m.label() == select.value()).unwrap_or(current.motion);
+            Appearance { motion, ..current }
+        })
+    };
+    let on_density_change = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let density = Density::ALL.into_iter().find(|d| d.label() == select.value()).unwrap_or_default();
+            update(&settings, move |s| s.density = density)();
+        })
+    };
+    let on_clock_change = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let clock = Clock::ALL.into_iter().find(|c| c.label() == select.value()).unwrap_or_default();
+            time::set_preferences(clock, &settings.time_zone);
+            update(&settings, move |s| s.clock = clock)();
+        })
+    };
+    let on_time_zone_change = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let zone = e.target_unchecked_into::<HtmlSelectElement>().value();
+            time::set_preferences(settings.clock, &zone);
+            update(&settings, move |s| s.time_zone = zone.clone())();
+        })
+    };
+    let device_zone = match time::device_time_zone() {
+        Some(zone) => t_with("settings-time-zone-device-named", &[("zone", &zone)]),
+        None => t("settings-time-zone-device"),
+    };
+    let on_translate_to_change = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let language = e.target_unchecked_into::<HtmlSelectElement>().value();
+            update(&settings, move |s| s.translate_to = language.clone())();
+        })
+    };
+    let toggle_notifications = {
+        let toggle = update(&settings, |s| s.notifications = !s.notifications);
+        let enabling = !settings.notifications;
+        Callback::from(move |_: Event| {
+            if enabling {
+                request_notification_permission();
+            }
+            toggle();
+        })
+    };
+    let toggle_push = {
+        let push_state = push_state.clone();
+        let push_failed = push_failed.clone();
+        Callback::from(move |_: Event| {
+            let subscribing = *push_state != Some(PushState::On);
+            let (push_state, push_failed) = (push_state.clone(), push_failed.clone());
+            spawn_local(async move {
+                let result = if subscribing { push::subscribe().await } else { push::unsubscribe().await };
+                if let Err(e) = &result {
+                    log::error!("failed to change the push subscription: {:?}", e);
+                }
+                push_failed.set(result.is_err());
+                push_state.set(Some(push::state().await));
+            });
+        })
+    };
+    let toggle_sound_messages = {
+        let toggle = update(&settings, |s| s.sound_messages = !s.sound_messages);
+        Callback::from(move |_: Event| toggle())
+    };
+    let toggle_sound_mentions = {
+        let toggle = update(&settings, |s| s.sound_mentions = !s.sound_mentions);
+        Callback::from(move |_: Event| toggle())
+    };
+    let toggle_sound_direct = {
+        let toggle = update(&settings, |s| s.sound_direct = !s.sound_direct);
+        Callback::from(move |_: Event| toggle())
+    };
+    let toggle_do_not_disturb = {
+        let toggle = update(&settings, |s| s.do_not_disturb = !s.do_not_disturb);
+        Callback::from(move |_: Event| toggle())
+    };
+    let toggle_quiet_hours = {
+        let toggle = update(&settings, |s| s.quiet_hours = !s.quiet_hours);
+        Callback::from(move |_: Event| toggle())
+    };
+    let on_quiet_from_change = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let time = e.target_unchecked_into::<HtmlInputElement>().value();
+            update(&settings, move |s| s.quiet_from = time.clone())();
+        })
+    };
+    let on_quiet_until_change = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let time = e.target_unchecked_into::<HtmlInputElement>().value();
+            update(&settings, move |s| s.quiet_until = time.clone())();
+        })
+    };
+    let toggle_media = {
+        let toggle = update(&settings, |s| s.autoload_media = !s.autoload_media);
+        Callback::from(move |_: Event| toggle())
+    };
+    let toggle_announcements = {
+        let toggle = update(&settings, |s| s.announce_mentions_only = !s.announce_mentions_only);
+        Callback::from(move |_: Event| toggle())
+    };
+    let toggle_warn_unsent = {
+        let toggle = update(&settings, |s| s.warn_unsent = !s.warn_unsent);
+        Callback::from(move |_: Event| toggle())
+    };
+    let toggle_telemetry = {
+        let toggle = update(&settings, |s| s.telemetry = !s.telemetry);
+        let enabling = !settings.telemetry;
+        Callback::from(move |_: Event| {
+            telemetry::set_enabled(enabling);
+            toggle();
+        })
+    };
+    let text_input = |apply: fn(&mut SettingsStore, String)| {
+        let settings = settings.clone();
+        Callback::from(move |e: InputEvent| {
+            let value = e.target_unchecked_into::<HtmlInputElement>().value();
+            update(&settings, move |s| apply(s, value.clone()))();
+        })
+    };
+    let on_bio_input = {
+        let settings = settings.clone();
+        Callback::from(move |e: InputEvent| {
+            let value = e.target_unchecked_into::<HtmlTextAreaElement>().value();
+            update(&settings, move |s| s.bio = value.clone())();
+        })
+    };
+    let toggle_feature = |feature: Feature| {
+        let features = features.clone();
+        let enabled = !features.is_enabled(feature);
+        Callback::from(move |_: Event| {
+            let mut next = (*features).clone();
+            next.set(feature, enabled);
+            next.save();
+            features.set(next);
+        })
+    };
+
+    html! {
+        <div class="w-full min-h-screen overflow-y-auto bg-gray-50">
+            <div class="max-w-2xl mx-auto px-6 py-8">
+                <div class="flex items-center justify-between mb-6">
+                    <h1 class="text-2xl font-bold text-gray-800">{t("settings-title")}</h1>
+                    <Link<Route> to={Route::Chat} classes="text-sm text-blue-500 hover:underline">{t("settings-back")}</Link<Route>>
+                </div>
+                <section class="bg-white rounded-xl shadow-sm p-6 mb-6">
+                    <h2 class="mb-4 text-lg font-semibold text-gray-800">{t("settings-preferences")}</h2>
+                    <label class="flex items-center justify-between text-sm text-gray-700">
+                        {t("settings-language")}
+                        <select onchange={on_locale_change} class="ms-2 px-2 py-1 bg-gray-100 rounded">
+                            {
+                                Locale::ALL.iter().map(|l| html! {
+                                    <option value={l.code()} selected={*l == *locale}>{l.label()}</option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </label>
+                    <label class="flex items-center justify-between mt-4 text-sm text-gray-700">
+                        {t("settings-theme")}
+                        <select onchange={on_theme_change} class="ms-2 px-2 py-1 bg-gray-100 rounded">
+                            {
+                                Theme::ALL.iter().map(|choice| html! {
+                                    <option value={choice.label()} selected={*choice == theme.choice}>{choice.label()}</option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </label>
+                    <div class="flex items-center justify-between mt-4 text-sm text-gray-700">
+                        {t("settings-accent")}
+                        <div class="flex">
+                            {
+                                Accent::ALL.iter().map(|&accent| html! {
+                                    <button
+                                        onclick={pick_accent(accent)}
+                                        title={accent.label()}
+                                        aria-label={accent.label()}
+                                        aria-pressed={(accent == appearance.accent).to_string()}
+                                        style={format!("background-color: rgb({});", accent.channels().0)}
+                                        class={classes!(
+                                            "ms-2", "h-6", "w-6", "rounded-full", "focus:outline-none",
+                                            (accent == appearance.accent).then_some("ring-2 ring-offset-2 ring-gray-400")
+                                        )}
+                                    ></button>
+                                }).collect::<Html>()
+                            }
+                        </div>
+                    </div>
+                    <label class="flex items-center justify-between mt-4 text-sm text-gray-700">
+                        {t("settings-bubbles")}
+                        <select onchange={on_bubbles_change} class="ms-2 px-2 py-1 bg-gray-100 rounded">
+                            {
+                                BubbleStyle::ALL.iter().map(|b| html! {
+                                    <option value={b.label()} selected={*b == appearance.bubbles}>{b.label()}</option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </label>
+                    <label class="flex items-center justify-between mt-4 text-sm text-gray-700">
+                        {t("settings-font-size")}
+                        <select onchange={on_font_size_change} class="ms-2 px-2 py-1 bg-gray-100 rounded">
+                            {
+                                FontSize::ALL.iter().map(|size| html! {
+                                    <option value={size.label()} selected={*size == theme.font_size}>{size.label()}</option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </label>
+                    <label class="flex items-center justify-between mt-4 text-sm text-gray-700">
+                        {t("settings-density")}
+                        <select onchange={on_density_change} class="ms-2 px-2 py-1 bg-gray-100 rounded">
+                            {
+                                Density::ALL.iter().map(|d| html! {
+                                    <option value={d.label()} selected={*d == settings.density}>{d.label()}</option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </label>
+                    <label class="flex items-center justify-between mt-4 text-sm text-gray-700">
+                        {t("settings-motion")}
+                        <select onchange={on_motion_change} class="ms-2 px-2 py-1 bg-gray-100 rounded">
+                            {
+                                Motion::ALL.iter().map(|m| html! {
+                                    <option value={m.label()} selected={*m == appearance.motion}>{m.label()}</option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </label>
+                    <label class="flex items-center justify-between mt-4 text-sm text-gray-700">
+                        {t("settings-clock")}
+                        <select onchange={on_clock_change} class="ms-2 px-2 py-1 bg-gray-100 rounded">
+                            {
+                                Clock::ALL.iter().map(|c| html! {
+                                    <option value={c.label()} selected={*c == settings.clock}>{c.label()}</option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </label>
+                    <label class="flex items-center justify-between mt-4 text-sm text-gray-700">
+                        {t("settings-time-zone")}
+                        <select onchange={on_time_zone_change} class="ms-2 w-48 px-2 py-1 bg-gray-100 rounded">
+                            <option value="" selected={settings.time_zone.is_empty()}>{device_zone}</option>
+                            {
+                                time::time_zones().into_iter().map(|zone| html! {
+                                    <option selected={zone == settings.time_zone} value={zone.clone()}>{zone}</option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </label>
+                    if translate::endpoint().is_some() {
+                        <label class="flex items-center justify-between mt-4 text-sm text-gray-700">
+                            {t("settings-translate-to")}
+                            <select onchange={on_translate_to_change} class="ms-2 w-48 px-2 py-1 bg-gray-100 rounded">
+                                <option value="" selected={settings.translate_to.is_empty()}>{t("settings-translate-to-interface")}</option>
+                                {
+                                    translate::LANGUAGES.iter().map(|&code| html! {
+                                        <option value={code} selected={code == settings.translate_to}>{translate::language_name(code)}</option>
+                                    }).collect::<Html>()
+                                }
+                            </select>
+                        </label>
+                    }
+                    <label class="flex items-center mt-4 text-sm text-gray-700">
+                        <input type="checkbox" checked={settings.notifications} onchange={toggle_notifications} class="me-2"/>
+                        {t("settings-notifications")}
+                    </label>
+                    if settings.notifications && notifications_blocked() {
+                        <p class="mt-1 ms-6 text-sm text-red-600">{t("settings-notifications-blocked")}</p>
+                    }
+                    if let Some(state) = (*push_state).filter(|state| *state != PushState::Unconfigured) {
+                        <label class="flex items-center mt-3 text-sm text-gray-700">
+                            <input type="checkbox" checked={state == PushState::On} disabled={state == PushState::Unsupported} onchange={toggle_push} class="me-2"/>
+                            {t("settings-push")}
+                        </label>
+                        <p class="mt-1 ms-6 text-sm text-gray-500">
+                            {
+                                match state {
+                                    PushState::On => t("settings-push-on"),
+                                    PushState::Unsupported => t("settings-push-unsupported"),
+                                    _ => t("settings-push-off"),
+                                }
+                            }
+                        </p>
+                        if *push_failed {
+                            <p class="mt-1 ms-6 text-sm text-red-600">
+                                {if notifications_blocked() { t("settings-notifications-blocked") } else { t("settings-push-failed") }}
+                            </p>
+                        }
+                    }
+                    <label class="flex items-center mt-3 text-sm text-gray-700">
+                        <input type="checkbox" checked={settings.sound_messages} onchange={toggle_sound_messages} class="me-2"/>
+                        {t("settings-sound-messages")}
+                    </label>
+                    <label class="flex items-center mt-3 text-sm text-gray-700">
+                        <input type="checkbox" checked={settings.sound_mentions} onchange={toggle_sound_mentions} class="me-2"/>
+                        {t("settings-sound-mentions")}
+                    </label>
+                    <label class="flex items-center mt-3 text-sm text-gray-700">
+                        <input type="checkbox" checked={settings.sound_direct} onchange={toggle_sound_direct} class="me-2"/>
+                        {t("settings-sound-direct")}
+                    </label>
+                    <label class="flex items-center mt-3 text-sm text-gray-700">
+                        <input type="checkbox" checked={settings.do_not_disturb} onchange={toggle_do_not_disturb} class="me-2"/>
+                        {t("settings-do-not-disturb")}
+                    </label>
+                    <label class="flex items-center mt-3 text-sm text-gray-700">
+                        <input type="checkbox" checked={settings.quiet_hours} onchange={toggle_quiet_hours} class="me-2"/>
+                        {t("settings-quiet-hours")}
+                    </label>
+                    if settings.quiet_hours {
+                        <div class="flex items-center mt-2 ms-6 text-sm text-gray-700">
+                            <label class="flex items-center">
+                                {t("settings-quiet-from")}
+                                <input type="time" value={settings.quiet_from.clone()} onchange={on_quiet_from_change} class="ms-2 px-2 py-1 bg-gray-100 rounded"/>
+                            </label>
+                            <label class="flex items-center ms-4">
+                                {t("settings-quiet-until")}
+                                <input type="time" value={settings.quiet_until.clone()} onchange={on_quiet_until_change} class="ms-2 px-2 py-1 bg-gray-100 rounded"/>
+                            </label>
+                        </div>
+                    }
+                    <label class="flex items-center mt-3 text-sm text-gray-700">
+                        <input type="checkbox" checked={settings.autoload_media} onchange={toggle_media} class="me-2"/>
+                        {t("settings-autoload")}
+                    </label>
+                    <label class="flex items-center mt-3 text-sm text-gray-700">
+                        <input type="checkbox" checked={settings.announce_mentions_only} onchange={toggle_announcements} class="me-2"/>
+                        {t("settings-announce-mentions-only")}
+                    </label>
+                    <label class="flex items-center mt-3 text-sm text-gray-700">
+                        <input type="checkbox" checked={settings.warn_unsent} onchange={toggle_warn_unsent} class="me-2"/>
+                        {t("settings-warn-unsent")}
+                    </label>
+                    if telemetry::endpoint().is_some() {
+                        <label class="flex items-center mt-3 text-sm text-gray-700">
+                            <input type="checkbox" checked={settings.telemetry} onchange={toggle_telemetry} class="me-2"/>
+                            {t("settings-telemetry")}
+                        </label>
+                    }
+                </section>
+                <section class="bg-white rounded-xl shadow-sm p-6 mb-6">
+                    <h2 class="mb-1 text-lg font-semibold text-gray-800">{t("settings-profile")}</h2>
+                    <p class="mb-4 text-sm text-gray-500">{t("settings-profile-hint")}</p>
+                    <input
+                        type="text"
+                        dir="auto"
+                        value={settings.display_name.clone()}
+                        oninput={text_input(|s, v| s.display_name = v)}
+                        placeholder={t("settings-display-name")}
+                        maxlength="32"
+                        class="w-full px-3 py-2 text-sm bg-gray-100 rounded-lg outline-none focus:ring-2 focus:ring-blue-400 focus:bg-white"
+                    />
+                    <input
+                        type="text"
+                        dir="auto"
+                        value={settings.status.clone()}
+                        oninput={text_input(|s, v| s.status = v)}
+                        placeholder={t("settings-status")}
+                        maxlength="60"
+                        class="mt-2 w-full px-3 py-2 text-sm bg-gray-100 rounded-lg outline-none focus:ring-2 focus:ring-blue-400 focus:bg-white"
+                    />
+                    <textarea
+                        dir="auto"
+                        value={settings.bio.clone()}
+                        oninput={on_bio_input}
+                        placeholder={t("settings-bio")}
+                        maxlength="160"
+                        class="mt-2 w-full h-20 px-3 py-2 text-sm bg-gray-100 rounded-lg outline-none focus:ring-2 focus:ring-blue-400 focus:bg-white resize-none"
+                    />
+                </section>
+                <section class="bg-white rounded-xl shadow-sm p-6 mb-6">
+                    <h2 class="mb-4 text-lg font-semibold text-gray-800">{t("settings-two-factor")}</h2>
+                    <TwoFactorSetup/>
+                </section>
+                if let Some(install) = install {
+                    <section class="bg-white rounded-xl shadow-sm p-6 mb-6">
+                        <h2 class="mb-1 text-lg font-semibold text-gray-800">{t("settings-install")}</h2>
+                        <p class="mb-4 text-sm text-gray-500">{t("settings-install-hint")}</p>
+                        <button
+                            onclick={install.reform(|_| ())}
+                            class="rounded-lg bg-purple-600 hover:bg-purple-700 text-white font-medium py-2 px-4 transition-colors"
+                        >
+                            {t("settings-install-button")}
+                        </button>
+                    </section>
+                }
+                <section class="bg-white rounded-xl shadow-sm p-6 mb-6">
+                    <h2 class="mb-1 text-lg font-semibold text-gray-800">{t("settings-experimental")}</h2>
+                    <p class="mb-4 text-sm text-gray-500">{t("settings-experimental-hint")}</p>
+                    {
+                        Feature::ALL.iter().map(|&feature| html! {
+                            <label class="flex items-center mt-3 text-sm text-gray-700">
+                                <input type="checkbox" checked={features.is_enabled(feature)} onchange={toggle_feature(feature)} class="me-2"/>
+                                {feature.label()}
+                                if features.enabled_by_deployment(feature) {
+                                    <span class="ms-2 text-xs text-gray-400">{t("settings-on-by-default")}</span>
+                                }
+                            </label>
+                        }).collect::<Html>()
+                    }
+                </section>
+                <p class="text-center text-sm">
+                    <Link<Route> to={Route::Debug} classes="text-gray-400 hover:underline">{t("settings-debug")}</Link<Route>>
+                </p>
+            </div>
+        </div>
+    }
+}
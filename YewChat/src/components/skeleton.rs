@@ -0,0 +1,41 @@
+use yew::prelude::*;
+
+/// Widths of a row's name and text bars, varied so the rows read as content.
+const BAR_WIDTHS: [(&str, &str); 3] = [("w-24", "w-40"), ("w-32", "w-56"), ("w-20", "w-32")];
+
+#[derive(Properties, PartialEq)]
+pub struct SkeletonRowsProps {
+    pub count: usize,
+    /// What screen readers hear instead of the grey rows.
+    pub label: String,
+    /// Size of the avatar circle.
+    #[prop_or("w-10 h-10")]
+    pub avatar: &'static str,
+    /// Padding of each row.
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+/// Pulsing stand-ins for people or messages that haven't arrived yet, so a
+/// list that is still loading doesn't look empty.
+#[function_component(SkeletonRows)]
+pub fn skeleton_rows(props: &SkeletonRowsProps) -> Html {
+    html! {
+        <div role="status" aria-label={props.label.clone()}>
+            {
+                (0..props.count).map(|i| {
+                    let (name, text) = BAR_WIDTHS[i % BAR_WIDTHS.len()];
+                    html! {
+                        <div aria-hidden="true" class={classes!("flex", "items-center", "animate-pulse", props.class.clone())}>
+                            <div class={classes!("shrink-0", "rounded-full", "bg-gray-200", props.avatar)}></div>
+                            <div class="ms-3 flex-1">
+                                <div class={classes!("h-3", "rounded", "bg-gray-200", name)}></div>
+                                <div class={classes!("mt-2", "h-2", "rounded", "bg-gray-100", text)}></div>
+                            </div>
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
@@ -0,0 +1,180 @@
+use gloo::events::EventListener;
+use yew::prelude::*;
+
+use crate::services::theme::{self, FontSize, Theme};
+
+/// The Tailwind classes that change between light and dark, so views pick a
+/// set instead of spelling out both.
+#[derive(Debug, PartialEq)]
+pub struct ThemeClasses {
+    /// Behind everything else on the page.
+    pub page: &'static str,
+    /// Cards, bars and panels sitting on the page.
+    pub surface: &'static str,
+    pub border: &'static str,
+    pub text: &'static str,
+    pub muted: &'static str,
+    /// Rows that highlight under the pointer.
+    pub hover: &'static str,
+    /// Text fields.
+    pub input: &'static str,
+    /// Messages from other people; your own take the accent colour.
+    pub bubble: &'static str,
+    /// Icon buttons in bars.
+    pub icon: &'static str,
+    /// Presence dots for people who are and aren't online.
+    pub online: &'static str,
+    pub offline: &'static str,
+}
+
+pub const LIGHT: ThemeClasses = ThemeClasses {
+    page: "bg-gray-50",
+    surface: "bg-white",
+    border: "border-gray-200",
+    text: "text-gray-800",
+    muted: "text-gray-500",
+    hover: "hover:bg-gray-50",
+    input: "bg-gray-100 focus:bg-white text-gray-700",
+    bubble: "bg-white text-gray-800",
+    icon: "text-gray-500 hover:bg-gray-100",
+    online: "bg-green-400",
+    offline: "bg-gray-300",
+};
+
+pub const DARK: ThemeClasses = ThemeClasses {
+    page: "bg-gray-900",
+    surface: "bg-gray-800",
+    border: "border-gray-700",
+    text: "text-gray-100",
+    muted: "text-gray-400",
+    hover: "hover:bg-gray-700",
+    input: "bg-gray-700 focus:bg-gray-600 text-gray-100 placeholder-gray-400",
+    bubble: "bg-gray-700 text-gray-100",
+    icon: "text-gray-400 hover:bg-gray-700",
+    online: "bg-green-400",
+    offline: "bg-gray-500",
+};
+
+/// Every pairing here reaches 4.5:1 for text and 3:1 for borders and dots.
+pub const HIGH_CONTRAST: ThemeClasses = ThemeClasses {
+    page: "bg-white",
+    surface: "bg-white",
+    border: "border-gray-900",
+    text: "text-black",
+    muted: "text-gray-700",
+    hover: "hover:bg-gray-200",
+    input: "bg-white text-black border-2 border-gray-900 placeholder-gray-700",
+    bubble: "bg-white text-black border-2 border-gray-900",
+    icon: "text-black hover:bg-gray-200",
+    online: "bg-green-700",
+    offline: "bg-white border-2 border-gray-700",
+};
+
+/// Handed down by [`ThemeProvider`]: the user's choice, whether that works out
+/// dark right now, and ways to change it and the text size.
+#[derive(Clone, Default, PartialEq)]
+pub struct ThemeContext {
+    pub choice: Theme,
+    pub dark: bool,
+    pub high_contrast: bool,
+    pub set: Callback<Theme>,
+    pub font_size: FontSize,
+    pub set_font_size: Callback<FontSize>,
+}
+
+impl ThemeContext {
+    pub fn classes(&self) -> &'static ThemeClasses {
+        if self.high_contrast {
+            &HIGH_CONTRAST
+        } else if self.dark {
+            &DARK
+        } else {
+            &LIGHT
+        }
+    }
+
+    /// Flips to whichever of light and dark isn't showing, pinning it so it
+    /// stops following the system.
+    pub fn toggle(&self) {
+        self.set.emit(if self.dark { Theme::Light } else { Theme::Dark });
+    }
+}
+
+/// The current theme, or light outside a [`ThemeProvider`].
+pub fn use_theme() -> ThemeContext {
+    use_context::<ThemeContext>().unwrap_or_default()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ThemeProviderProps {
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// Keeps the theme for everything below it: the saved choice, or the system's
+/// `prefers-color-scheme` until the user picks one, following it as it changes.
+#[function_component(ThemeProvider)]
+pub fn theme_provider(props: &ThemeProviderProps) -> Html {
+    let choice = use_state(Theme::load);
+    let font_size = use_state(FontSize::load);
+    let system_dark = use_state(|| Theme::System.is_dark());
+
+    {
+        let system_dark = system_dark.clone();
+        use_effect_with_deps(
+            move |_| {
+                let listener = theme::system_query().map(|query| {
+                    let target = query.clone();
+                    EventListener::new(&query, "change", move |_| system_dark.set(target.matches()))
+                });
+                move || drop(listener)
+            },
+            (),
+        );
+    }
+
+    let dark = match *choice {
+        Theme::System => *system_dark,
+        other => other.is_dark(),
+    };
+    let high_contrast = *choice == Theme::HighContrast;
+    // Pages that don't use the class sets still follow along through `index.html`.
+    use_effect_with_deps(
+        |(dark, high_contrast)| {
+            theme::apply(*dark, *high_contrast);
+            || ()
+        },
+        (dark, high_contrast),
+    );
+    use_effect_with_deps(
+        |font_size| {
+            font_size.apply();
+            || ()
+        },
+        *font_size,
+    );
+
+    let context = ThemeContext {
+        choice: *choice,
+        dark,
+        high_contrast,
+        set: {
+            let choice = choice.clone();
+            Callback::from(move |theme: Theme| {
+                theme.save();
+                choice.set(theme);
+            })
+        },
+        font_size: *font_size,
+        set_font_size: Callback::from(move |size: FontSize| {
+            size.save();
+            font_size.set(size);
+        }),
+    };
+
+    html! {
+        <ContextProvider<ThemeContext> context={context}>
+            { for props.children.iter() }
+        </ContextProvider<ThemeContext>>
+    }
+}
@@ -0,0 +1,173 @@
+use std::rc::Rc;
+
+use gloo::timers::callback::Timeout;
+use yew::prelude::*;
+
+use crate::services::i18n::t;
+
+/// How long a toast stays up before going away by itself, in milliseconds.
+const TOAST_MS: u32 = 4000;
+
+/// Toasts on screen at once; a new one pushes out the oldest.
+const TOASTS_SHOWN: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToastKind {
+    /// Something worked, e.g. a link was copied.
+    Info,
+    /// Something the user tried didn't happen.
+    Error,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ToastProps {
+    pub text: String,
+    #[prop_or(ToastKind::Info)]
+    pub kind: ToastKind,
+    /// Called after a few seconds or from the close button. Without it the
+    /// toast stays until the parent removes it.
+    #[prop_or_default]
+    pub on_dismiss: Option<Callback<()>>,
+}
+
+/// A short message in a dark pill. Errors are read out straight away, the
+/// rest when the screen reader is done talking.
+#[function_component(Toast)]
+pub fn toast(props: &ToastProps) -> Html {
+    {
+        let on_dismiss = props.on_dismiss.clone();
+        use_effect_with_deps(
+            move |_| {
+                let timeout = on_dismiss.map(|on_dismiss| Timeout::new(TOAST_MS, move || on_dismiss.emit(())));
+                move || drop(timeout)
+            },
+            (),
+        );
+    }
+
+    html! {
+        <div
+            role={if props.kind == ToastKind::Error { "alert" } else { "status" }}
+            class={classes!(
+                "flex", "items-center", "px-4", "py-2", "rounded-lg", "text-white", "text-sm", "shadow-lg",
+                if props.kind == ToastKind::Error { "bg-red-700" } else { "bg-gray-800" }
+            )}
+        >
+            {props.text.clone()}
+            if let Some(on_dismiss) = &props.on_dismiss {
+                <button
+                    onclick={on_dismiss.reform(|_| ())}
+                    title={t("toast-dismiss")}
+                    aria-label={t("toast-dismiss")}
+                    class="ms-3 text-gray-300 hover:text-white"
+                >
+                    {"✕"}
+                </button>
+            }
+        </div>
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct ToastEntry {
+    id: u64,
+    kind: ToastKind,
+    text: String,
+}
+
+#[derive(Default, PartialEq)]
+struct ToastState {
+    toasts: Vec<ToastEntry>,
+    next_id: u64,
+}
+
+enum ToastAction {
+    Show(ToastKind, String),
+    Dismiss(u64),
+}
+
+impl Reducible for ToastState {
+    type Action = ToastAction;
+
+    fn reduce(self: Rc<Self>, action: ToastAction) -> Rc<Self> {
+        let mut toasts = self.toasts.clone();
+        let mut next_id = self.next_id;
+        match action {
+            ToastAction::Show(kind, text) => {
+                // Saying the same thing twice just starts its timer over.
+                toasts.retain(|toast| toast.text != text);
+                toasts.push(ToastEntry { id: next_id, kind, text });
+                next_id += 1;
+                let hidden = toasts.len().saturating_sub(TOASTS_SHOWN);
+                toasts.drain(..hidden);
+            }
+            ToastAction::Dismiss(id) => toasts.retain(|toast| toast.id != id),
+        }
+        Rc::new(ToastState { toasts, next_id })
+    }
+}
+
+/// Handed down by [`ToastProvider`] to show transient feedback from anywhere.
+#[derive(Clone, Default, PartialEq)]
+pub struct ToastContext {
+    show: Callback<(ToastKind, String)>,
+}
+
+impl ToastContext {
+    pub fn info(&self, text: String) {
+        self.show.emit((ToastKind::Info, text));
+    }
+
+    pub fn error(&self, text: String) {
+        self.show.emit((ToastKind::Error, text));
+    }
+}
+
+/// Shows toasts for everything below it, or drops them outside a [`ToastProvider`].
+pub fn use_toast() -> ToastContext {
+    use_context::<ToastContext>().unwrap_or_default()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ToastProviderProps {
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// Keeps the toasts for the whole app and draws them at the bottom of the
+/// page, above whatever dialog happens to be open.
+#[function_component(ToastProvider)]
+pub fn toast_provider(props: &ToastProviderProps) -> Html {
+    let state = use_reducer(ToastState::default);
+    let context = ToastContext {
+        show: {
+            let state = state.dispatcher();
+            Callback::from(move |(kind, text)| state.dispatch(ToastAction::Show(kind, text)))
+        },
+    };
+
+    let toasts = html! {
+        <div aria-live="polite" class="fixed bottom-24 inset-x-0 z-[100] flex flex-col items-center space-y-2 pointer-events-none">
+            {
+                state.toasts.iter().map(|toast| {
+                    let on_dismiss = {
+                        let (state, id) = (state.dispatcher(), toast.id);
+                        Callback::from(move |_| state.dispatch(ToastAction::Dismiss(id)))
+                    };
+                    html! {
+                        <div key={toast.id} class="pointer-events-auto">
+                            <Toast text={toast.text.clone()} kind={toast.kind} on_dismiss={on_dismiss}/>
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+        </div>
+    };
+
+    html! {
+        <ContextProvider<ToastContext> context={context}>
+            { for props.children.iter() }
+            { create_portal(toasts, gloo::utils::body().into()) }
+        </ContextProvider<ToastContext>>
+    }
+}
@@ -0,0 +1,407 @@
+use std::cmp::Reverse;
+use std::rc::Rc;
+
+use web_sys::{Element, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::avatar::Avatar;
+use crate::components::chat::{display_name_of, last_seen_label, view_role_badge, Role, UserProfile};
+use crate::components::skeleton::SkeletonRows;
+use crate::components::theme_provider::use_theme;
+use crate::services::chat_store::ChatStore;
+use crate::services::i18n::{self, t, t_with};
+use crate::services::layout::{clamp_sidebar_width, SIDEBAR_MAX_WIDTH, SIDEBAR_MIN_WIDTH};
+use crate::services::name_list::NameList;
+use crate::services::settings::UserSort;
+use crate::services::shortcuts::{use_shortcut, Chord, ShortcutArea};
+use crate::Route;
+
+#[derive(Default, Properties, PartialEq)]
+pub struct UserSidebarProps {
+    /// Whether the sidebar is open on small screens; it always shows from `md` up.
+    pub visible: bool,
+    /// In CSS pixels, from `md` up.
+    pub width: u32,
+    pub sort: UserSort,
+    pub username: String,
+    pub role: Role,
+    pub contacts: NameList,
+    pub blocked: NameList,
+    pub muted: NameList,
+    pub on_show_profile: Callback<String>,
+    /// The moderation menu for a user, at the viewport coordinates of the click.
+    pub on_open_menu: Callback<(String, i32, i32)>,
+    pub on_open_direct: Callback<String>,
+    pub on_add_contact: Callback<String>,
+    pub on_remove_contact: Callback<String>,
+    pub on_block: Callback<String>,
+    pub on_unblock: Callback<String>,
+    /// The width the sidebar was dragged or stepped to.
+    pub on_resize: Callback<u32>,
+    pub on_sort: Callback<UserSort>,
+}
+
+/// Puts `users` in `sort`'s order; ties fall back to the display name.
+fn sort_users(users: &mut [&UserProfile], sort: UserSort, contacts: &NameList) {
+    users.sort_by_cached_key(|u| {
+        let name = u.display_name().to_lowercase();
+        match sort {
+            UserSort::Name => (0, 0, Reverse(Role::Member), name),
+            // Join times are whole milliseconds; anyone without one goes last.
+            UserSort::Joined => (0, u.joined_at.map_or(u64::MAX, |at| at as u64), Reverse(Role::Member), name),
+            UserSort::Contacts => {
+                let group = if contacts.contains(&u.name) {
+                    0
+                } else if u.role.can_moderate() {
+                    1
+                } else {
+                    2
+                };
+                (group, 0, Reverse(u.role), name)
+            }
+        }
+    });
+}
+
+fn view_contact_toggle(props: &UserSidebarProps, name: &str) -> Html {
+    let is_contact = props.contacts.contains(name);
+    let toggle = {
+        let name = name.to_string();
+        let (add, remove) = (props.on_add_contact.clone(), props.on_remove_contact.clone());
+        Callback::from(move |e: MouseEvent| {
+            // The surrounding row opens the profile card.
+            e.stop_propagation();
+            if is_contact {
+                remove.emit(name.clone());
+            } else {
+                add.emit(name.clone());
+            }
+        })
+    };
+
+    html! {
+        <button
+            onclick={toggle}
+            title={t(if is_contact { "sidebar-remove-contact" } else { "sidebar-add-contact" })}
+            aria-label={t(if is_contact { "sidebar-remove-contact" } else { "sidebar-add-contact" })}
+            class={classes!("p-1", "hover:text-yellow-500", if is_contact { "text-yellow-400" } else { "text-gray-300" })}
+        >
+            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" viewBox="0 0 20 20" fill="currentColor">
+                <path d="M9.049 2.927c.3-.921 1.603-.921 1.902 0l1.07 3.292a1 1 0 00.95.69h3.462c.969 0 1.371 1.24.588 1.81l-2.8 2.034a1 1 0 00-.364 1.118l1.07 3.292c.3.921-.755 1.688-1.54 1.118l-2.8-2.034a1 1 0 00-1.175 0l-2.8 2.034c-.784.57-1.838-.197-1.539-1.118l1.07-3.292a1 1 0 00-.364-1.118L2.98 8.72c-.783-.57-.38-1.81.588-1.81h3.461a1 1 0 00.951-.69l1.07-3.292z" />
+            </svg>
+        </button>
+    }
+}
+
+fn view_block_button(props: &UserSidebarProps, name: &str) -> Html {
+    let block = {
+        let name = name.to_string();
+        props.on_block.reform(move |e: MouseEvent| {
+            e.stop_propagation();
+            name.clone()
+        })
+    };
+
+    html! {
+        <button onclick={block} title={t("sidebar-block")} aria-label={t("sidebar-block")} class="p-1 text-gray-300 hover:text-red-500">
+            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M18.364 18.364A9 9 0 005.636 5.636m12.728 12.728A9 9 0 015.636 5.636m12.728 12.728L5.636 5.636" />
+            </svg>
+        </button>
+    }
+}
+
+/// The sidebar's width if its far edge were at viewport `x`.
+fn width_at(sidebar: &NodeRef, x: i32) -> Option<u32> {
+    let rect = sidebar.cast::<Element>()?.get_bounding_client_rect();
+    let width = if i18n::locale().direction() == "rtl" {
+        rect.right() - x as f64
+    } else {
+        x as f64 - rect.left()
+    };
+    Some(clamp_sidebar_width(width))
+}
+
+/// Contacts, everyone online and the block list.
+#[function_component(UserSidebar)]
+pub fn user_sidebar(props: &UserSidebarProps) -> Html {
+    let colors = use_theme().classes();
+    let store = use_context::<ChatStore>().expect("chat store to be provided");
+    let sidebar = use_node_ref();
+    // The width while the edge is being dragged; it's only saved once let go.
+    let dragged = use_state(|| None::<u32>);
+    let width = dragged.unwrap_or(props.width);
+    let query = use_state(String::new);
+    let filter = use_node_ref();
+
+    {
+        let filter = filter.clone();
+        use_shortcut(Chord::key("/"), ShortcutArea::People, "shortcut-search", Callback::from(move |_| {
+            if let Some(input) = filter.cast::<HtmlInputElement>() {
+                let _ = input.focus();
+                input.select();
+            }
+        }));
+    }
+
+    let on_pointer_down = {
+        let dragged = dragged.clone();
+        Callback::from(move |e: PointerEvent| {
+            e.prevent_default();
+            let _ = e.target_unchecked_into::<Element>().set_pointer_capture(e.pointer_id());
+            dragged.set(Some(width));
+        })
+    };
+    let on_pointer_move = {
+        let (sidebar, dragged) = (sidebar.clone(), dragged.clone());
+        Callback::from(move |e: PointerEvent| {
+            if dragged.is_some() {
+                dragged.set(width_at(&sidebar, e.client_x()).or(*dragged));
+            }
+        })
+    };
+    let on_pointer_up = {
+        let (dragged, on_resize) = (dragged.clone(), props.on_resize.clone());
+        Callback::from(move |_: PointerEvent| {
+            if let Some(width) = *dragged {
+                on_resize.emit(width);
+                dragged.set(None);
+            }
+        })
+    };
+    // A cancelled drag keeps the width it got to, like a finished one.
+    let on_pointer_cancel = on_pointer_up.clone();
+    let on_resize_key = {
+        let on_resize = props.on_resize.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            let wider = if i18n::locale().direction() == "rtl" { "ArrowLeft" } else { "ArrowRight" };
+            let step = match e.key().as_str() {
+                key if key == wider => 16.0,
+                "ArrowLeft" | "ArrowRight" => -16.0,
+                _ => return,
+            };
+            e.prevent_default();
+            on_resize.emit(clamp_sidebar_width(width as f64 + step));
+        })
+    };
+    let contacts = props.contacts.iter().map(|name| {
+        let presence = store.users.iter().find(|u| &u.name == name);
+        let online = presence.is_some();
+        let status = match presence {
+            Some(user) => user.status.clone().unwrap_or_else(|| t("sidebar-online")),
+            None => last_seen_label(&store.last_seen, name),
+        };
+        let open_direct = {
+            let name = name.clone();
+            props.on_open_direct.reform(move |_| name.clone())
+        };
+        let remove = {
+            let name = name.clone();
+            props.on_remove_contact.reform(move |_| name.clone())
+        };
+        html! {
+            <div class="flex items-center px-5 py-2">
+                <div class={classes!("h-2", "w-2", "rounded-full", if online { colors.online } else { colors.offline })}></div>
+                <div class="ms-3 flex-1 min-w-0">
+                    <Link<Route> to={Route::Profile { name: name.clone() }} classes="block text-sm font-medium text-gray-800 truncate hover:underline">{display_name_of(&store.users, name)}</Link<Route>>
+                    <div class="text-xs text-gray-500 truncate">{status}</div>
+                </div>
+                <button
+                    onclick={open_direct}
+                    disabled={!online}
+                    title={t("sidebar-direct")}
+                    aria-label={t("sidebar-direct")}
+                    class="p-1 text-gray-400 hover:text-blue-500 disabled:opacity-40 disabled:cursor-not-allowed"
+                >
+                    <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 10h.01M12 10h.01M16 10h.01M9 16H5a2 2 0 01-2-2V6a2 2 0 012-2h14a2 2 0 012 2v8a2 2 0 01-2 2h-5l-5 5v-5z" />
+                    </svg>
+                </button>
+                <button onclick={remove} title={t("sidebar-forget-contact")} aria-label={t("sidebar-forget-contact")} class="p-1 text-gray-400 hover:text-red-500">
+                    <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M6 18L18 6M6 6l12 12" />
+                    </svg>
+                </button>
+            </div>
+        }
+    }).collect::<Html>();
+
+    let on_query = {
+        let query = query.clone();
+        Callback::from(move |e: InputEvent| query.set(e.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+    let on_sort = props.on_sort.reform(|e: Event| {
+        let select: HtmlSelectElement = e.target_unchecked_into();
+        UserSort::ALL.into_iter().find(|sort| sort.label() == select.value()).unwrap_or_default()
+    });
+    let on_query_key = {
+        let query = query.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Escape" && !query.is_empty() {
+                e.stop_propagation();
+                query.set(String::new());
+            }
+        })
+    };
+    let needle = query.trim().to_lowercase();
+    let mut matches = store
+        .users
+        .iter()
+        .map(Rc::as_ref)
+        .filter(|u| !props.blocked.contains(&u.name))
+        .filter(|u| {
+            needle.is_empty()
+                || u.name.to_lowercase().contains(&needle)
+                || u.display_name().to_lowercase().contains(&needle)
+        })
+        .collect::<Vec<_>>();
+    sort_users(&mut matches, props.sort, &props.contacts);
+    let no_matches = matches.is_empty();
+    let online = matches.into_iter().map(|u| {
+        let show_profile = {
+            let name = u.name.clone();
+            props.on_show_profile.reform(move |_| name.clone())
+        };
+        let open_menu = {
+            let name = u.name.clone();
+            // Moderators may kick or ban anyone ranked below them.
+            let removable = props.role.can_moderate() && u.role < props.role && u.name != props.username;
+            let on_open_menu = props.on_open_menu.clone();
+            Callback::from(move |e: MouseEvent| {
+                // Everyone else keeps the browser's own context menu.
+                if removable {
+                    e.prevent_default();
+                    on_open_menu.emit((name.clone(), e.client_x(), e.client_y()));
+                }
+            })
+        };
+        html! {
+            <div onclick={show_profile} oncontextmenu={open_menu} class={classes!("flex", "items-center", "px-5", "py-3", "transition-colors", "cursor-pointer", colors.hover)}>
+                <div class="relative">
+                    <Avatar name={u.name.clone()} src={u.avatar()} class="w-12 h-12 rounded-full border-2 border-white shadow-sm"/>
+                    <div class={classes!("absolute", "bottom-0", "end-0", "h-3", "w-3", "rounded-full", "border-2", "border-white", colors.online)}></div>
+                </div>
+                <div class="ms-3 flex-1 min-w-0">
+                    <div class={classes!("font-medium", colors.text)}>
+                        <span dir="auto">{u.display_name()}</span>
+                        { view_role_badge(u.role) }
+                        if props.muted.contains(&u.name) {
+                            <span class="ms-1 text-xs font-normal text-gray-400">{t("sidebar-muted")}</span>
+                        }
+                    </div>
+                    <div class="text-xs text-gray-500 truncate">{u.status.clone().unwrap_or_else(|| t("sidebar-online"))}</div>
+                </div>
+                <Link<Route> to={Route::Profile { name: u.name.clone() }} classes="p-1 text-gray-400 hover:text-blue-500">
+                    <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M16 7a4 4 0 11-8 0 4 4 0 018 0zM12 14a7 7 0 00-7 7h14a7 7 0 00-7-7z" />
+                    </svg>
+                </Link<Route>>
+                if u.name != props.username {
+                    { view_contact_toggle(props, &u.name) }
+                    { view_block_button(props, &u.name) }
+                }
+            </div>
+        }
+    }).collect::<Html>();
+
+    let blocked = props.blocked.iter().map(|name| {
+        let unblock = {
+            let name = name.clone();
+            props.on_unblock.reform(move |_| name.clone())
+        };
+        html! {
+            <div class="flex items-center justify-between px-5 py-2 text-sm">
+                <span class="text-gray-500 truncate">{name.clone()}</span>
+                <button onclick={unblock} class="text-xs text-blue-500 hover:underline">{t("sidebar-unblock")}</button>
+            </div>
+        }
+    }).collect::<Html>();
+
+    html! {
+        <div
+            ref={sidebar}
+            aria-label={t("sidebar-label")}
+            style={format!("width: {}px;", width)}
+            class={classes!(
+                colors.surface, "relative", "flex-shrink-0", "shadow-lg",
+                dragged.is_none().then_some("transition-all duration-300"),
+                "md:block", // Always show on medium screens and above
+                if !props.visible { "hidden" } else { "" }
+            )}
+        >
+            <div
+                role="separator"
+                aria-orientation="vertical"
+                aria-label={t("sidebar-resize")}
+                aria-valuenow={width.to_string()}
+                aria-valuemin={SIDEBAR_MIN_WIDTH.to_string()}
+                aria-valuemax={SIDEBAR_MAX_WIDTH.to_string()}
+                tabindex="0"
+                onpointerdown={on_pointer_down}
+                onpointermove={on_pointer_move}
+                onpointerup={on_pointer_up}
+                onpointercancel={on_pointer_cancel}
+                onkeydown={on_resize_key}
+                class="hidden md:block absolute inset-y-0 end-0 z-10 w-1.5 cursor-col-resize touch-none hover:bg-blue-300 focus:bg-blue-400 focus:outline-none"
+            ></div>
+            <div class={classes!("py-4", "px-5", "border-b", colors.border)}>
+                <h2 class={classes!("text-xl", "font-semibold", "flex", "items-center", colors.text)}>
+                    <svg xmlns="http://www.w3.org/2000/svg" class="h-6 w-6 me-2 text-blue-500" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M17 20h5v-2a3 3 0 00-5.356-1.857M17 20H7m10 0v-2c0-.656-.126-1.283-.356-1.857M7 20H2v-2a3 3 0 015.356-1.857M7 20v-2c0-.656.126-1.283.356-1.857m0 0a5.002 5.002 0 019.288 0M15 7a3 3 0 11-6 0 3 3 0 016 0zm6 3a2 2 0 11-4 0 2 2 0 014 0zM7 10a2 2 0 11-4 0 2 2 0 014 0z" />
+                    </svg>
+                    {t("sidebar-title")}
+                </h2>
+                <input
+                    ref={filter}
+                    type="search"
+                    value={(*query).clone()}
+                    oninput={on_query}
+                    onkeydown={on_query_key}
+                    placeholder={t("sidebar-filter")}
+                    aria-label={t("sidebar-filter")}
+                    class={classes!("mt-3", "w-full", "px-3", "py-1.5", "text-sm", "rounded-lg", "outline-none", "focus:ring-2", "focus:ring-blue-400", colors.input)}
+                />
+                <label class={classes!("flex", "items-center", "justify-between", "mt-2", "text-xs", colors.muted)}>
+                    {t("sidebar-sort")}
+                    <select onchange={on_sort} class={classes!("ms-2", "px-2", "py-1", "rounded", colors.input)}>
+                        {
+                            UserSort::ALL.iter().map(|sort| html! {
+                                <option value={sort.label()} selected={*sort == props.sort}>{sort.label()}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                </label>
+            </div>
+            <div class="overflow-y-auto" style="max-height: calc(100vh - 146px);">
+                <div class={classes!("border-b", "pb-2", colors.border)}>
+                    <h3 class="px-5 pt-3 pb-1 text-xs font-semibold uppercase tracking-wide text-gray-500">{t("sidebar-contacts")}</h3>
+                    if props.contacts.is_empty() {
+                        <p class="px-5 py-1 text-sm text-gray-400">{t("sidebar-no-contacts")}</p>
+                    } else {
+                        {contacts}
+                    }
+                </div>
+                if store.is_loading() {
+                    <SkeletonRows count={3} label={t("sidebar-loading")} avatar="w-12 h-12" class="px-5 py-3"/>
+                } else if store.users.is_empty() {
+                    <div class="py-8 px-5 text-center text-gray-500">
+                        {t("sidebar-empty")}
+                    </div>
+                } else if no_matches {
+                    <div class="py-8 px-5 text-center text-gray-500">
+                        {t_with("sidebar-no-matches", &[("query", query.trim())])}
+                    </div>
+                } else {
+                    {online}
+                }
+                if !props.blocked.is_empty() {
+                    <div class="border-t border-gray-200 pb-2">
+                        <h3 class="px-5 pt-3 pb-1 text-xs font-semibold uppercase tracking-wide text-gray-500">{t("sidebar-blocked")}</h3>
+                        {blocked}
+                    </div>
+                }
+            </div>
+        </div>
+    }
+}
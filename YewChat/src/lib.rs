@@ -1,22 +1,117 @@
 #![recursion_limit = "512"]
+// yew 0.19's `html!` checks props with statements that newer clippy reads as
+// needless expressions and unit bindings, at every component it renders.
+#![allow(clippy::let_unit_value, clippy::unnecessary_operation)]
 
 mod components;
 mod services;
+#[cfg(test)]
+mod tests;
 
+use components::admin::Admin;
+use components::error_boundary::ErrorBoundary;
+use components::gallery::Gallery;
 use components::login::Login;
+use components::not_found::NotFound;
+use components::oauth_callback::OAuthCallback;
+use components::password_reset::{ForgotPassword, ResetPassword};
+use components::profile::Profile;
+use components::require_user::RequireUser;
+use components::settings::Settings;
+use components::theme_provider::ThemeProvider;
+use components::toast::ToastProvider;
+use services::chat_store::{ChatStore, ChatStoreState};
+use services::session::Session;
+use services::settings::SettingsStore;
+use services::theme::{self, Appearance, FontSize, Theme};
 use components::chat::Chat;
+use components::create_account::CreateAccount;
+use components::debug_panel::DebugPanel;
 use wasm_bindgen::prelude::*;
 use yew::functional::*;
 use yew::prelude::*;
 use yew_router::prelude::*;
-use std::cell::RefCell;
 use std::rc::Rc;
 
-pub type User = Rc<UserInner>;
+/// The signed-in user, shared through context. Dispatching a [`UserAction`]
+/// re-renders everything that reads it.
+pub type User = UseReducerHandle<UserState>;
 
-#[derive(Debug, PartialEq)]
-pub struct UserInner {
-    pub username: RefCell<String>,
+/// The only room the server currently exposes; per-room preferences are keyed by it.
+pub const DEFAULT_ROOM: &str = "general";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UserState {
+    /// Empty until someone signs in.
+    pub username: String,
+    /// Avatar chosen at login; `None` uses the generated one.
+    pub avatar: Option<String>,
+    /// Access token from an OAuth login; `None` for plain username logins.
+    pub token: Option<String>,
+    /// Handed out by the server for a password sign-in, and sent back when
+    /// joining to prove it.
+    pub account_token: Option<String>,
+    /// Why the user was sent back to the login screen, shown there once.
+    pub notice: Option<String>,
+    /// CAPTCHA token solved on the login screen, spent by the next register.
+    pub captcha: Option<String>,
+}
+
+pub enum UserAction {
+    SignIn {
+        username: String,
+        avatar: Option<String>,
+        token: Option<String>,
+        account_token: Option<String>,
+        captcha: Option<String>,
+    },
+    SetAvatar(Option<String>),
+    /// Back to the login screen, optionally explaining why.
+    SignOut { notice: Option<String> },
+    /// The login screen has shown the notice.
+    ClearNotice,
+    /// The CAPTCHA token went out with a register and can't be reused.
+    SpendCaptcha,
+}
+
+impl Reducible for UserState {
+    type Action = UserAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let state = match action {
+            UserAction::SignIn {
+                username,
+                avatar,
+                token,
+                account_token,
+                captcha,
+            } => Self {
+                username,
+                avatar,
+                token,
+                account_token,
+                notice: None,
+                captcha,
+            },
+            UserAction::SetAvatar(avatar) => Self {
+                avatar,
+                ..(*self).clone()
+            },
+            UserAction::SignOut { notice } => Self {
+                notice,
+                ..Self::default()
+            },
+            UserAction::ClearNotice => Self {
+                notice: None,
+                ..(*self).clone()
+            },
+            UserAction::SpendCaptcha => Self {
+                captcha: None,
+                ..(*self).clone()
+            },
+        };
+        state.into()
+    }
 }
 
 // When the `wee_alloc` feature is enabled, this uses `wee_alloc` as the global
@@ -27,12 +122,32 @@ pub struct UserInner {
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-#[derive(Debug, Clone, Copy, PartialEq, Routable)]
+#[derive(Debug, Clone, PartialEq, Routable)]
 pub enum Route {
     #[at("/")]
     Login,
+    #[at("/register")]
+    CreateAccount,
     #[at("/chat")]
     Chat,
+    #[at("/chat/:room")]
+    ChatRoom { room: String },
+    #[at("/admin")]
+    Admin,
+    #[at("/settings")]
+    Settings,
+    #[at("/debug")]
+    Debug,
+    #[at("/gallery")]
+    Gallery,
+    #[at("/users/:name")]
+    Profile { name: String },
+    #[at("/oauth/callback")]
+    OAuthCallback,
+    #[at("/forgot-password")]
+    ForgotPassword,
+    #[at("/reset-password")]
+    ResetPassword,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -41,34 +156,85 @@ pub enum Route {
 fn switch(selected_route: &Route) -> Html {
     match selected_route {
         Route::Login => html! {<Login />},
-        Route::Chat => html! {<Chat/>},
-        Route::NotFound => html! {<h1>{"404 baby"}</h1>},
+        Route::CreateAccount => html! {<CreateAccount/>},
+        // There is only the one room for now; the segment keeps permalinks stable.
+        Route::Chat | Route::ChatRoom { .. } => html! {<RequireUser><Chat/></RequireUser>},
+        Route::Admin => html! {<RequireUser><Admin/></RequireUser>},
+        Route::Settings => html! {<Settings/>},
+        Route::Debug => html! {<DebugPanel/>},
+        Route::Gallery => html! {<Gallery/>},
+        Route::Profile { name } => html! {<RequireUser><Profile name={name.clone()}/></RequireUser>},
+        Route::OAuthCallback => html! {<OAuthCallback/>},
+        Route::ForgotPassword => html! {<ForgotPassword/>},
+        Route::ResetPassword => html! {<ResetPassword/>},
+        Route::NotFound => html! {<NotFound/>},
     }
 }
 
 #[function_component(Main)]
 fn main() -> Html {
 
-    let ctx = use_state(|| {
-        Rc::new(UserInner {
-            username: RefCell::new("initial".into()),
-        })
+    let ctx = use_reducer(|| match Session::load() {
+        Some(session) => UserState {
+            username: session.username,
+            avatar: session.avatar,
+            token: session.token,
+            account_token: session.account_token,
+            ..Default::default()
+        },
+        None => UserState::default(),
     });
 
+    // Keep the stored session in step with whoever is signed in.
+    use_effect_with_deps(
+        |user: &UserState| {
+            if user.username.is_empty() {
+                Session::forget();
+            } else {
+                Session::remember(user);
+            }
+            || ()
+        },
+        (*ctx).clone(),
+    );
+
+    // Outlives the chat screen, so going to a profile and back keeps the conversation.
+    let store = use_reducer_eq(ChatStoreState::default);
+
     html! {
-        <ContextProvider<User> context={(*ctx).clone()}>
+        <ContextProvider<User> context={ctx}>
+        <ContextProvider<ChatStore> context={store}>
+        <ThemeProvider>
+        <ToastProvider>
         <BrowserRouter>
             <div class="flex w-screen h-screen">
-                <Switch<Route> render={Switch::render(switch)}/>
+                <ErrorBoundary>
+                    <Switch<Route> render={Switch::render(switch)}/>
+                </ErrorBoundary>
             </div>
         </BrowserRouter>
+        </ToastProvider>
+        </ThemeProvider>
+        </ContextProvider<ChatStore>>
         </ContextProvider<User>>
     }
 }
 
 #[wasm_bindgen]
 pub fn run_app() -> Result<(), JsValue> {
-    wasm_logger::init(wasm_logger::Config::default());
+    services::logging::init();
+    services::crash::install();
+    services::i18n::init();
+    // Before the first render, so a dark page doesn't flash white.
+    let theme = Theme::load();
+    theme::apply(theme.is_dark(), theme == Theme::HighContrast);
+    FontSize::load().apply();
+    Appearance::load().apply();
+    let settings = SettingsStore::load();
+    services::time::set_preferences(settings.clock, &settings.time_zone);
+    services::telemetry::init(settings.telemetry);
+    services::offline::install();
+    services::install_prompt::capture();
     yew::start_app::<Main>();
     Ok(())
 }
\ No newline at end of file
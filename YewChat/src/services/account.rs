@@ -0,0 +1,206 @@
+use reqwasm::http::Request;
+use serde::{Deserialize, Serialize};
+
+use crate::components::chat::{MsgTypes, WebSocketMessage};
+use crate::services::config;
+use crate::services::i18n::{t, t_with};
+use crate::services::websocket::request;
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+#[derive(Serialize)]
+pub struct NewAccount {
+    pub email: String,
+    pub username: String,
+    pub password: String,
+}
+
+/// The account the server signed us in as.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Account {
+    pub username: String,
+    /// Proves the sign-in when joining the chat, which is what earns an
+    /// account listed in the server's `ADMINS` its role.
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// A loose sanity check; the confirmation flow is what really proves an address.
+pub fn validate_email(email: &str) -> Result<(), String> {
+    let valid = match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
+        }
+        None => false,
+    };
+    if valid && !email.contains(char::is_whitespace) {
+        Ok(())
+    } else {
+        Err(t("account-invalid-email"))
+    }
+}
+
+pub fn validate_password(password: &str) -> Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        Err(t_with("password-too-short", &[("count", &MIN_PASSWORD_LENGTH.to_string())]))
+    } else {
+        Ok(())
+    }
+}
+
+/// Turns an `error` reply into a message for the user.
+fn describe_error(code: Option<String>) -> String {
+    match code.as_deref() {
+        Some("email_taken") => t("account-email-taken"),
+        Some("name_taken") => t("account-name-taken"),
+        Some("invalid") => t("account-invalid"),
+        Some("bad_credentials") => t("account-bad-credentials"),
+        Some("bad_code") => t("account-bad-code"),
+        Some("challenge_expired") => t("account-challenge-expired"),
+        Some("code_required") => t("account-code-required"),
+        other => t_with("account-unknown-error", &[("code", other.unwrap_or("unknown error"))]),
+    }
+}
+
+fn expect_account(reply: WebSocketMessage) -> Result<Account, String> {
+    match reply.message_type {
+        MsgTypes::Account => serde_json::from_str(&reply.data.unwrap_or_default())
+            .map_err(|e| t_with("account-unexpected-reply", &[("error", &e.to_string())])),
+        _ => Err(describe_error(reply.data)),
+    }
+}
+
+pub async fn create(account: &NewAccount) -> Result<Account, String> {
+    expect_account(request(MsgTypes::CreateAccount, serde_json::to_string(account).unwrap()).await?)
+}
+
+#[derive(Clone, Serialize)]
+pub struct Credentials {
+    pub email: String,
+    pub password: String,
+}
+
+/// Outcome of a password sign-in.
+pub enum SignIn {
+    SignedIn(Account),
+    /// The account has two-factor on; pass the challenge to [`verify_code`].
+    NeedsCode(String),
+    /// The challenge ran out or had too many wrong codes; sign in again.
+    Expired,
+}
+
+pub async fn sign_in(credentials: &Credentials) -> Result<SignIn, String> {
+    let reply = request(MsgTypes::Login, serde_json::to_string(credentials).unwrap()).await?;
+    match reply.message_type {
+        MsgTypes::Totp => Ok(SignIn::NeedsCode(reply.data.unwrap_or_default())),
+        _ => expect_account(reply).map(SignIn::SignedIn),
+    }
+}
+
+pub fn validate_code(code: &str) -> Result<(), String> {
+    if code.len() == 6 && code.chars().all(|c| c.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err(t("account-enter-code"))
+    }
+}
+
+/// Finishes a sign-in that [`sign_in`] answered with [`SignIn::NeedsCode`].
+pub async fn verify_code(challenge: &str, code: &str) -> Result<SignIn, String> {
+    let data = serde_json::json!({ "challenge": challenge, "code": code });
+    let reply = request(MsgTypes::VerifyTotp, data.to_string()).await?;
+    match reply.data.as_deref() {
+        Some("challenge_expired") if reply.message_type == MsgTypes::Error => Ok(SignIn::Expired),
+        _ => expect_account(reply).map(SignIn::SignedIn),
+    }
+}
+
+/// A freshly generated authenticator secret, not active until confirmed.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct TotpSecret {
+    pub secret: String,
+    /// `otpauth://` URI for authenticator apps to scan.
+    pub uri: String,
+}
+
+/// Outcome of asking for a new authenticator secret.
+pub enum TotpSetup {
+    Secret(TotpSecret),
+    /// Two-factor is on already; ask again with a code from the current app.
+    NeedsCurrentCode,
+}
+
+pub async fn begin_totp_setup(credentials: &Credentials, current_code: Option<&str>) -> Result<TotpSetup, String> {
+    let data = serde_json::json!({ "email": credentials.email, "password": credentials.password, "code": current_code });
+    let reply = request(MsgTypes::TotpSetup, data.to_string()).await?;
+    match reply.message_type {
+        MsgTypes::TotpSecret => serde_json::from_str(&reply.data.unwrap_or_default())
+            .map(TotpSetup::Secret)
+            .map_err(|e| t_with("account-unexpected-reply", &[("error", &e.to_string())])),
+        _ if current_code.is_none() && reply.data.as_deref() == Some("code_required") => Ok(TotpSetup::NeedsCurrentCode),
+        _ => Err(describe_error(reply.data)),
+    }
+}
+
+/// Switches two-factor on once the user proves their app produces codes for
+/// the secret from [`begin_totp_setup`]. Signing in afterwards asks for a code.
+pub async fn enable_totp(credentials: &Credentials, code: &str) -> Result<(), String> {
+    let data = serde_json::json!({ "email": credentials.email, "password": credentials.password, "code": code });
+    let reply = request(MsgTypes::TotpEnable, data.to_string()).await?;
+    match reply.message_type {
+        MsgTypes::TotpEnabled => Ok(()),
+        _ => Err(describe_error(reply.data)),
+    }
+}
+
+#[derive(Serialize)]
+struct ResetRequest<'a> {
+    email: &'a str,
+}
+
+#[derive(Serialize)]
+struct ResetConfirmation<'a> {
+    token: &'a str,
+    password: &'a str,
+}
+
+fn reset_endpoint() -> Result<String, String> {
+    config::meta("yewchat-password-reset-endpoint")
+        .ok_or_else(|| t("reset-not-configured"))
+}
+
+async fn post(url: &str, body: String) -> Result<(), String> {
+    let response = Request::post(url)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| t_with("reset-unreachable", &[("error", &e.to_string())]))?;
+    match response.status() {
+        200..=299 => Ok(()),
+        400 | 404 | 410 => Err(t("reset-link-invalid")),
+        status => Err(t_with("reset-service-failed", &[("status", &status.to_string())])),
+    }
+}
+
+/// Asks the endpoint configured with `<meta name="yewchat-password-reset-endpoint">`
+/// to email a reset link. It answers the same whether or not the address has
+/// an account, so this can't be used to probe for members.
+pub async fn request_password_reset(email: &str) -> Result<(), String> {
+    post(&reset_endpoint()?, serde_json::to_string(&ResetRequest { email }).unwrap()).await
+}
+
+/// Sets a new password using the token from the emailed link, via `<endpoint>/confirm`.
+pub async fn reset_password(token: &str, password: &str) -> Result<(), String> {
+    let url = format!("{}/confirm", reset_endpoint()?.trim_end_matches('/'));
+    post(&url, serde_json::to_string(&ResetConfirmation { token, password }).unwrap()).await
+}
+
+/// The `token` query parameter of the current page, as put in reset emails.
+pub fn reset_token() -> Option<String> {
+    let search = gloo::utils::window().location().search().ok()?;
+    web_sys::UrlSearchParams::new_with_str(&search)
+        .ok()?
+        .get("token")
+        .filter(|token| !token.is_empty())
+}
@@ -0,0 +1,114 @@
+//! How much each room may interrupt, and Do Not Disturb over all of them.
+//! Notifications and sounds ask [`RoomAlerts::allows`] and
+//! [`do_not_disturb`]; the unread badge only counts what a room's level lets
+//! through.
+
+use std::collections::BTreeMap;
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::services::name_list::{self, NameList};
+use crate::services::settings::SettingsStore;
+
+const STORAGE_KEY: &str = "yewchat.room-alerts";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertLevel {
+    #[serde(rename = "all")]
+    #[default]
+    All,
+    /// Only messages that mention the user, or are sent to them directly.
+    #[serde(rename = "mentions")]
+    Mentions,
+    #[serde(rename = "muted")]
+    Muted,
+}
+
+impl AlertLevel {
+    pub const ALL: [AlertLevel; 3] = [Self::All, Self::Mentions, Self::Muted];
+
+    /// Catalog key of the palette command switching a room to this level.
+    pub fn command(self) -> &'static str {
+        match self {
+            Self::All => "palette-alerts-all",
+            Self::Mentions => "palette-alerts-mentions",
+            Self::Muted => "palette-alerts-muted",
+        }
+    }
+}
+
+/// Alert levels by room, kept in localStorage. Rooms not listed alert for
+/// everything.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoomAlerts {
+    levels: BTreeMap<String, AlertLevel>,
+}
+
+impl RoomAlerts {
+    pub fn load() -> Self {
+        if let Ok(alerts) = LocalStorage::get(STORAGE_KEY) {
+            return alerts;
+        }
+        // Rooms muted before there were levels stay muted.
+        let muted = NameList::load(name_list::MUTED_ROOMS);
+        Self {
+            levels: muted.iter().map(|room| (room.clone(), AlertLevel::Muted)).collect(),
+        }
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, self) {
+            log::error!("failed to persist room alerts: {:?}", e);
+        }
+    }
+
+    pub fn level(&self, room: &str) -> AlertLevel {
+        self.levels.get(room).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, room: &str, level: AlertLevel) {
+        if level == AlertLevel::All {
+            self.levels.remove(room);
+        } else {
+            self.levels.insert(room.to_string(), level);
+        }
+    }
+
+    /// Whether a message in `room` may interrupt, given whether it mentions the user.
+    pub fn allows(&self, room: &str, mentioned: bool) -> bool {
+        match self.level(room) {
+            AlertLevel::All => true,
+            AlertLevel::Mentions => mentioned,
+            AlertLevel::Muted => false,
+        }
+    }
+}
+
+/// Minutes since midnight of an `HH:MM` time, as `<input type="time">` gives it.
+fn minutes(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.split_once(':')?;
+    Some(hours.parse::<u32>().ok()? * 60 + minutes.parse::<u32>().ok()?)
+}
+
+/// Whether notifications and sounds are held back at `now`: Do Not Disturb
+/// is on, or it is within the quiet hours, which may run past midnight.
+pub fn do_not_disturb(settings: &SettingsStore, now: f64) -> bool {
+    if settings.do_not_disturb {
+        return true;
+    }
+    if !settings.quiet_hours {
+        return false;
+    }
+    let (from, until) = match (minutes(&settings.quiet_from), minutes(&settings.quiet_until)) {
+        (Some(from), Some(until)) => (from, until),
+        _ => return false,
+    };
+    let date = js_sys::Date::new(&now.into());
+    let now = date.get_hours() * 60 + date.get_minutes();
+    if from <= until {
+        from <= now && now < until
+    } else {
+        now >= from || now < until
+    }
+}
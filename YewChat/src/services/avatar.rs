@@ -0,0 +1,135 @@
+use std::cell::Cell;
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::services::palette;
+
+/// Largest image we accept for an uploaded avatar. Uploads travel as `data:` URLs
+/// inside every `Users` broadcast, so they need to stay small.
+pub const MAX_UPLOAD_BYTES: u64 = 200 * 1024;
+
+const STORAGE_KEY: &str = "yewchat.avatar_provider";
+
+/// DiceBear styles offered in the login gallery.
+const GALLERY_STYLES: [&str; 4] = ["adventurer-neutral", "bottts", "fun-emoji", "pixel-art"];
+
+/// Produces an avatar image URL for users who haven't picked their own.
+pub trait AvatarSource {
+    fn url(&self, name: &str) -> String;
+}
+
+/// The DiceBear HTTP API (v7); the old `avatars.dicebear.com` endpoint is gone.
+pub struct DiceBear {
+    pub style: &'static str,
+}
+
+impl AvatarSource for DiceBear {
+    fn url(&self, name: &str) -> String {
+        format!(
+            "https://api.dicebear.com/7.x/{}/svg?seed={}",
+            self.style,
+            js_sys::encode_uri_component(name)
+        )
+    }
+}
+
+/// Gravatar keyed by the username, falling back to an identicon for unknown hashes.
+pub struct Gravatar;
+
+impl AvatarSource for Gravatar {
+    fn url(&self, name: &str) -> String {
+        let hash = md5::compute(name.trim().to_lowercase());
+        format!("https://www.gravatar.com/avatar/{:x}?d=identicon&s=128", hash)
+    }
+}
+
+/// A locally rendered SVG with the user's initials, so no network request is needed.
+pub struct Initials;
+
+pub fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+impl AvatarSource for Initials {
+    fn url(&self, name: &str) -> String {
+        let background = palette::user_color(name);
+        let svg = format!(
+            "<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 64 64'>\
+             <rect width='64' height='64' fill='{}'/>\
+             <text x='50%' y='50%' dy='.35em' text-anchor='middle' fill='#fff' \
+             font-family='sans-serif' font-size='28'>{}</text></svg>",
+            background,
+            initials(name)
+        );
+        format!(
+            "data:image/svg+xml;charset=utf-8,{}",
+            js_sys::encode_uri_component(&svg)
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum AvatarProvider {
+    #[default]
+    DiceBear,
+    Gravatar,
+    Initials,
+}
+
+impl AvatarProvider {
+    pub const ALL: [AvatarProvider; 3] = [Self::DiceBear, Self::Gravatar, Self::Initials];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::DiceBear => "DiceBear",
+            Self::Gravatar => "Gravatar",
+            Self::Initials => "Initials",
+        }
+    }
+
+    pub fn source(self) -> Box<dyn AvatarSource> {
+        match self {
+            Self::DiceBear => Box::new(DiceBear {
+                style: "adventurer-neutral",
+            }),
+            Self::Gravatar => Box::new(Gravatar),
+            Self::Initials => Box::new(Initials),
+        }
+    }
+}
+
+thread_local! {
+    static PROVIDER: Cell<AvatarProvider> =
+        Cell::new(LocalStorage::get(STORAGE_KEY).unwrap_or_default());
+}
+
+pub fn provider() -> AvatarProvider {
+    PROVIDER.with(Cell::get)
+}
+
+/// Switches the provider used for generated avatars and remembers the choice.
+pub fn set_provider(provider: AvatarProvider) {
+    PROVIDER.with(|p| p.set(provider));
+    if let Err(e) = LocalStorage::set(STORAGE_KEY, provider) {
+        log::error!("failed to persist avatar provider: {:?}", e);
+    }
+}
+
+pub fn default_avatar(name: &str) -> String {
+    provider().source().url(name)
+}
+
+/// A handful of generated looks for `name`: each gallery style with a couple
+/// of seeds derived from the name, so the picks stay stable while typing.
+pub fn gallery(name: &str) -> Vec<String> {
+    let seeds = [name.to_string(), format!("{}-alt", name)];
+    GALLERY_STYLES
+        .iter()
+        .flat_map(|style| seeds.iter().map(move |seed| DiceBear { style }.url(seed)))
+        .collect()
+}
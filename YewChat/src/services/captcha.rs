@@ -0,0 +1,104 @@
+use js_sys::{Function, Object, Reflect};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::Element;
+use yew::Callback;
+
+use crate::services::config;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Provider {
+    HCaptcha,
+    Turnstile,
+}
+
+impl Provider {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "hcaptcha" => Some(Self::HCaptcha),
+            "turnstile" => Some(Self::Turnstile),
+            _ => None,
+        }
+    }
+
+    fn script_url(self) -> &'static str {
+        match self {
+            Self::HCaptcha => "https://js.hcaptcha.com/1/api.js?render=explicit",
+            Self::Turnstile => "https://challenges.cloudflare.com/turnstile/v0/api.js?render=explicit",
+        }
+    }
+
+    /// The global the provider's script defines. Both expose the same
+    /// `render(container, options)` call.
+    fn global(self) -> &'static str {
+        match self {
+            Self::HCaptcha => "hcaptcha",
+            Self::Turnstile => "turnstile",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Captcha {
+    pub provider: Provider,
+    pub site_key: String,
+}
+
+/// The CAPTCHA set up with `<meta name="yewchat-captcha-provider">` and
+/// `<meta name="yewchat-captcha-site-key">`, if this deployment uses one.
+pub fn configured() -> Option<Captcha> {
+    Some(Captcha {
+        provider: Provider::from_name(&config::meta("yewchat-captcha-provider")?)?,
+        site_key: config::meta("yewchat-captcha-site-key")?,
+    })
+}
+
+impl Captcha {
+    /// Draws the widget into `container`, loading the provider's script first
+    /// if needed. `on_token` gets the token once solved and `None` when it expires.
+    pub fn render(&self, container: Element, on_token: Callback<Option<String>>) {
+        let api = Reflect::get(&gloo::utils::window(), &self.provider.global().into()).unwrap_or(JsValue::UNDEFINED);
+        if !api.is_undefined() {
+            self.render_widget(&api, &container, on_token);
+            return;
+        }
+
+        let script = match gloo::utils::document().create_element("script") {
+            Ok(script) => script,
+            Err(e) => {
+                log::error!("failed to create captcha script: {:?}", e);
+                return;
+            }
+        };
+        let _ = script.set_attribute("src", self.provider.script_url());
+        let _ = script.set_attribute("async", "");
+        let captcha = self.clone();
+        let onload = Closure::once_into_js(move || {
+            let api = Reflect::get(&gloo::utils::window(), &captcha.provider.global().into()).unwrap_or(JsValue::UNDEFINED);
+            captcha.render_widget(&api, &container, on_token);
+        });
+        let _ = Reflect::set(&script, &"onload".into(), &onload);
+        if let Err(e) = gloo::utils::body().append_child(&script) {
+            log::error!("failed to load captcha script: {:?}", e);
+        }
+    }
+
+    fn render_widget(&self, api: &JsValue, container: &Element, on_token: Callback<Option<String>>) {
+        let solved = {
+            let on_token = on_token.clone();
+            Closure::wrap(Box::new(move |token: String| on_token.emit(Some(token))) as Box<dyn FnMut(String)>)
+        };
+        let expired = Closure::wrap(Box::new(move || on_token.emit(None)) as Box<dyn FnMut()>);
+
+        let options = Object::new();
+        let _ = Reflect::set(&options, &"sitekey".into(), &self.site_key.as_str().into());
+        // The widget outlives this call, so its callbacks are handed over to JS.
+        let _ = Reflect::set(&options, &"callback".into(), &solved.into_js_value());
+        let _ = Reflect::set(&options, &"expired-callback".into(), &expired.into_js_value());
+
+        let render = Reflect::get(api, &"render".into()).and_then(|f| f.dyn_into::<Function>());
+        if let Err(e) = render.and_then(|render| render.call2(api, container, &options)) {
+            log::error!("failed to render captcha: {:?}", e);
+        }
+    }
+}
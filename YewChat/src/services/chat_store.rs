@@ -0,0 +1,416 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+
+use gloo::render::{request_animation_frame, AnimationFrame};
+use gloo::timers::callback::Timeout;
+use yew::prelude::*;
+
+use crate::components::chat::{MessageData, Poll, Role, UserProfile};
+use crate::services::alerts::{AlertLevel, RoomAlerts};
+use crate::services::i18n::t_with;
+use crate::services::name_list::NameList;
+use crate::services::title;
+use crate::DEFAULT_ROOM;
+
+/// What the server has told us about the chat, shared through context so the
+/// sidebar, header and message list all read the same data. Dispatching a
+/// [`StoreAction`] re-renders everything that reads it.
+pub type ChatStore = UseReducerHandle<ChatStoreState>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Connection {
+    /// No chat screen is open, so there is no socket.
+    #[default]
+    Offline,
+    /// The socket is open but the server hasn't accepted our registration yet.
+    Connecting,
+    Connected,
+    /// A moderator removed us; the server closes the socket after telling us.
+    Removed,
+}
+
+/// Messages and users are shared behind `Rc`s, so cloning the state for each
+/// action and handing them to the components that render them stays cheap
+/// however long the history grows.
+#[derive(Clone, Default, PartialEq)]
+pub struct Room {
+    pub messages: Vec<Rc<MessageData>>,
+    pub pinned: Option<Rc<MessageData>>,
+    /// Messages that arrived unseen, while the reader was in another tab or
+    /// scrolled up through older ones.
+    pub unread: usize,
+    /// How many of those mention the reader.
+    pub unread_mentions: usize,
+}
+
+impl Room {
+    fn mark_read(&mut self) {
+        self.unread = 0;
+        self.unread_mentions = 0;
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct ChatStoreState {
+    /// Rooms by name; the server only has [`DEFAULT_ROOM`] for now.
+    pub rooms: BTreeMap<String, Room>,
+    /// The room messages from the server belong to.
+    pub active_room: String,
+    /// Everyone online.
+    pub users: Vec<Rc<UserProfile>>,
+    /// When each user who has left was last online.
+    pub last_seen: HashMap<String, f64>,
+    /// Whether the user list has arrived since we connected.
+    users_received: bool,
+    /// Whether older messages were asked for and haven't come back yet.
+    history_requested: bool,
+    /// Whether the reader has the newest messages in view. While they are
+    /// scrolled back through older ones, what arrives stays unread.
+    pub at_latest: bool,
+    pub connection: Connection,
+    /// Moved on each tick while a disappearing message counts down, to redraw it.
+    pub now: f64,
+}
+
+impl Default for ChatStoreState {
+    fn default() -> Self {
+        Self {
+            rooms: BTreeMap::from([(DEFAULT_ROOM.to_string(), Room::default())]),
+            active_room: DEFAULT_ROOM.to_string(),
+            users: vec![],
+            last_seen: HashMap::new(),
+            users_received: false,
+            history_requested: false,
+            at_latest: true,
+            connection: Connection::default(),
+            now: js_sys::Date::now(),
+        }
+    }
+}
+
+impl ChatStoreState {
+    pub fn room(&self) -> &Room {
+        &self.rooms[&self.active_room]
+    }
+
+    fn room_mut(&mut self) -> &mut Room {
+        self.rooms.entry(self.active_room.clone()).or_default()
+    }
+
+    /// The room `step` places away from the active one in name order,
+    /// wrapping around; `None` while there is only the one.
+    pub fn neighbouring_room(&self, step: isize) -> Option<&str> {
+        if self.rooms.len() < 2 {
+            return None;
+        }
+        let names: Vec<&str> = self.rooms.keys().map(String::as_str).collect();
+        let current = names.iter().position(|name| *name == self.active_room)? as isize;
+        Some(names[(current + step).rem_euclid(names.len() as isize) as usize])
+    }
+
+    /// Whether we're still waiting for the socket and the first user list,
+    /// without which the room looks empty.
+    pub fn is_loading(&self) -> bool {
+        !self.users_received && self.connection != Connection::Removed
+    }
+
+    /// Whether older messages are on their way, to be shown above the rest.
+    pub fn is_loading_history(&self) -> bool {
+        self.history_requested && self.connection == Connection::Connected
+    }
+
+    /// Whether a message arriving now goes unseen: the reader is in another
+    /// tab or window, or reading further up.
+    fn unseen(&self) -> bool {
+        title::away() || !self.at_latest
+    }
+
+    /// Unread messages across all rooms, as far as each room's alert level
+    /// lets them count: only mentions in some, nothing in muted ones.
+    pub fn unread(&self, alerts: &RoomAlerts) -> usize {
+        self.rooms
+            .iter()
+            .map(|(name, room)| match alerts.level(name) {
+                AlertLevel::All => room.unread,
+                AlertLevel::Mentions => room.unread_mentions,
+                AlertLevel::Muted => 0,
+            })
+            .sum()
+    }
+
+    /// Unread messages that mention the reader, in rooms that aren't muted.
+    pub fn unread_mentions(&self, alerts: &RoomAlerts) -> usize {
+        self.rooms
+            .iter()
+            .filter(|(name, _)| alerts.level(name) != AlertLevel::Muted)
+            .map(|(_, room)| room.unread_mentions)
+            .sum()
+    }
+
+    pub fn role_of(&self, name: &str) -> Role {
+        self.users
+            .iter()
+            .find(|u| u.name == name)
+            .map(|u| u.role)
+            .unwrap_or_default()
+    }
+
+    fn receive(&mut self, message: MessageData, mentioned: bool) {
+        if message.expires_at.is_some() {
+            self.now = js_sys::Date::now();
+        }
+        let unseen = self.unseen();
+        let room = self.room_mut();
+        if unseen {
+            room.unread += 1;
+            if mentioned {
+                room.unread_mentions += 1;
+            }
+        }
+        room.messages.push(Rc::new(message));
+    }
+
+    /// Adds "joined"/"left" notices for the difference between the current and
+    /// the incoming user list, leaving out anyone in `quiet`.
+    fn announce_presence_changes(&mut self, online: &[UserProfile], quiet: &NameList) {
+        let before: HashSet<&str> = self.users.iter().map(|u| u.name.as_str()).collect();
+        let after: HashSet<&str> = online.iter().map(|u| u.name.as_str()).collect();
+        let joined = online
+            .iter()
+            .filter(|u| !before.contains(u.name.as_str()))
+            .filter(|u| !quiet.contains(&u.name))
+            .map(|u| t_with("presence-joined", &[("name", u.display_name())]));
+        let left = self
+            .users
+            .iter()
+            .filter(|u| !after.contains(u.name.as_str()))
+            .filter(|u| !quiet.contains(&u.name))
+            .map(|u| t_with("presence-left", &[("name", u.display_name())]));
+        let notices: Vec<Rc<MessageData>> = joined.chain(left).map(MessageData::notice).map(Rc::new).collect();
+        self.room_mut().messages.extend(notices);
+    }
+}
+
+pub enum StoreAction {
+    /// The chat screen opened a socket.
+    Connect,
+    /// The server accepted our registration.
+    Connected,
+    /// A moderator kicked or banned us.
+    Removed,
+    /// The chat screen closed its socket.
+    Disconnect,
+    /// Someone signed out; nothing they saw should outlive them.
+    Reset,
+    UsersReceived {
+        online: Vec<UserProfile>,
+        last_seen: HashMap<String, f64>,
+        /// Users whose arrivals and departures aren't announced.
+        quiet: NameList,
+    },
+    /// Messages gathered by [`IncomingMessages`], in the order they came.
+    MessagesReceived(Vec<Received>),
+    PollUpdated(Poll),
+    /// `None` when the pin was removed.
+    Pinned(Option<MessageData>),
+    Deleted(u64),
+    Notice(String),
+    /// Older messages were asked for, e.g. to reach a permalink.
+    HistoryRequested,
+    /// Older messages fetched for a permalink, merged in front of what we have.
+    HistoryReceived(Vec<MessageData>),
+    /// Drops disappearing messages whose time is up.
+    Tick(f64),
+    /// The user is looking at the tab again.
+    MarkRead,
+    /// The reader scrolled to the newest messages, or away from them.
+    ScrolledToLatest(bool),
+    /// Makes one of the known rooms the active one.
+    SwitchRoom(String),
+}
+
+impl Reducible for ChatStoreState {
+    type Action = StoreAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut state = (*self).clone();
+        match action {
+            StoreAction::Connect => {
+                state.connection = Connection::Connecting;
+                // The first list after connecting is everyone already here.
+                state.users_received = false;
+                // Nor does an answer to what the last socket asked for.
+                state.history_requested = false;
+            }
+            StoreAction::Connected => state.connection = Connection::Connected,
+            StoreAction::Removed => state.connection = Connection::Removed,
+            StoreAction::Disconnect => {
+                if state.connection != Connection::Removed {
+                    state.connection = Connection::Offline;
+                }
+            }
+            StoreAction::Reset => state = Self::default(),
+            StoreAction::UsersReceived { online, last_seen, quiet } => {
+                state.last_seen = last_seen;
+                if state.users_received {
+                    state.announce_presence_changes(&online, &quiet);
+                }
+                state.users_received = true;
+                state.users = online.into_iter().map(Rc::new).collect();
+            }
+            StoreAction::MessagesReceived(received) => {
+                for Received { message, mentioned } in received {
+                    state.receive(message, mentioned);
+                }
+            }
+            StoreAction::PollUpdated(poll) => {
+                let room = state.room_mut();
+                let existing = room
+                    .messages
+                    .iter_mut()
+                    .find(|m| m.poll.as_ref().map(|p| p.id) == Some(poll.id));
+                match existing {
+                    Some(m) => Rc::make_mut(m).poll = Some(poll),
+                    None => room.messages.push(Rc::new(MessageData {
+                        from: poll.creator.clone(),
+                        message: poll.question.clone(),
+                        poll: Some(poll),
+                        ..Default::default()
+                    })),
+                }
+            }
+            StoreAction::Pinned(pinned) => state.room_mut().pinned = pinned.map(Rc::new),
+            StoreAction::Deleted(id) => {
+                let room = state.room_mut();
+                room.messages.retain(|m| m.id != Some(id));
+                if room.pinned.as_ref().is_some_and(|p| p.id == Some(id)) {
+                    room.pinned = None;
+                }
+            }
+            StoreAction::Notice(text) => state.room_mut().messages.push(Rc::new(MessageData::notice(text))),
+            StoreAction::HistoryRequested => state.history_requested = true,
+            StoreAction::HistoryReceived(older) => {
+                state.history_requested = false;
+                let room = state.room_mut();
+                let known: HashSet<u64> = room.messages.iter().filter_map(|m| m.id).collect();
+                let mut merged: Vec<Rc<MessageData>> = older
+                    .into_iter()
+                    .filter(|m| m.id.is_some_and(|id| !known.contains(&id)))
+                    .map(Rc::new)
+                    .collect();
+                merged.append(&mut room.messages);
+                room.messages = merged;
+            }
+            StoreAction::Tick(now) => {
+                let mut counting_down = false;
+                for room in state.rooms.values_mut() {
+                    room.messages
+                        .retain(|m| m.expires_at.is_none_or(|expires_at| expires_at > now));
+                    counting_down |= room.messages.iter().any(|m| m.expires_at.is_some());
+                }
+                // Only re-render while there are countdowns to update.
+                if counting_down {
+                    state.now = now;
+                }
+            }
+            // Only once they can see what came in.
+            StoreAction::MarkRead => {
+                if !state.unseen() {
+                    state.room_mut().mark_read();
+                }
+            }
+            StoreAction::ScrolledToLatest(at_latest) => {
+                state.at_latest = at_latest;
+                if !state.unseen() {
+                    state.room_mut().mark_read();
+                }
+            }
+            StoreAction::SwitchRoom(name) => {
+                if state.rooms.contains_key(&name) {
+                    state.active_room = name;
+                    state.room_mut().mark_read();
+                }
+            }
+        }
+        state.into()
+    }
+}
+
+/// A message as it arrived, waiting in [`IncomingMessages`].
+pub struct Received {
+    pub message: MessageData,
+    /// Whether it mentions the reader.
+    pub mentioned: bool,
+}
+
+/// How long messages wait when no animation frame comes, as in a hidden tab.
+const FALLBACK_FLUSH_MS: u32 = 100;
+
+/// Holds back messages that arrive in a burst, such as a replay after
+/// reconnecting, and hands them to the store together on the next animation
+/// frame: one render for the lot rather than one per frame received.
+#[derive(Clone)]
+pub struct IncomingMessages(Rc<RefCell<Incoming>>);
+
+#[derive(Default)]
+struct Incoming {
+    waiting: Vec<Received>,
+    /// The flush scheduled last, on both the next frame and a timer, since
+    /// the tab may be hidden before the frame comes. Dropping them cancels
+    /// them, so they stay put after they have run, as neither can be dropped
+    /// from its own callback, until the next burst replaces them.
+    frame: Option<AnimationFrame>,
+    timer: Option<Timeout>,
+}
+
+/// The calling component's [`IncomingMessages`], kept across renders.
+pub fn use_incoming_messages() -> IncomingMessages {
+    IncomingMessages(use_mut_ref(Incoming::default))
+}
+
+impl IncomingMessages {
+    pub fn push(&self, store: &ChatStore, message: MessageData, mentioned: bool) {
+        let mut incoming = self.0.borrow_mut();
+        let first = incoming.waiting.is_empty();
+        incoming.waiting.push(Received { message, mentioned });
+        if !first {
+            return;
+        }
+        let (batch, store) = (Rc::downgrade(&self.0), store.clone());
+        // Whichever of the frame and the timer comes second finds nothing waiting.
+        let flush = move || {
+            if let Some(batch) = batch.upgrade() {
+                let received = std::mem::take(&mut batch.borrow_mut().waiting);
+                if !received.is_empty() {
+                    store.dispatch(StoreAction::MessagesReceived(received));
+                }
+            }
+        };
+        let on_frame = flush.clone();
+        incoming.frame = Some(request_animation_frame(move |_| on_frame()));
+        incoming.timer = Some(Timeout::new(FALLBACK_FLUSH_MS, flush));
+    }
+
+    /// Hands over what is waiting straight away, ahead of anything else the
+    /// server sent after it.
+    pub fn flush(&self, store: &ChatStore) {
+        let received = {
+            let mut incoming = self.0.borrow_mut();
+            incoming.frame.take();
+            incoming.timer.take();
+            std::mem::take(&mut incoming.waiting)
+        };
+        if !received.is_empty() {
+            store.dispatch(StoreAction::MessagesReceived(received));
+        }
+    }
+
+    /// Drops what hasn't reached the store yet, e.g. when the user signs out.
+    pub fn clear(&self) {
+        let mut incoming = self.0.borrow_mut();
+        incoming.waiting.clear();
+        incoming.frame.take();
+        incoming.timer.take();
+    }
+}
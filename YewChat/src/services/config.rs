@@ -0,0 +1,10 @@
+/// Reads deployment settings from `<meta name="yewchat-..." content="...">`
+/// tags in `index.html`, so a build can be configured without recompiling.
+pub fn meta(name: &str) -> Option<String> {
+    gloo::utils::document()
+        .query_selector(&format!("meta[name='{}']", name))
+        .ok()
+        .flatten()
+        .and_then(|meta| meta.get_attribute("content"))
+        .filter(|content| !content.trim().is_empty())
+}
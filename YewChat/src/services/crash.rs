@@ -0,0 +1,101 @@
+use std::cell::Cell;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlDocument, HtmlElement, HtmlTextAreaElement};
+
+use crate::services::{logging, websocket};
+
+thread_local! {
+    static CRASHED: Cell<bool> = const { Cell::new(false) };
+}
+
+const CRASH_SCREEN: &str = r#"
+<div class="bg-gradient-to-r from-indigo-600 to-purple-600 min-h-screen w-full flex items-center">
+    <div class="container mx-auto px-4">
+        <div class="max-w-lg mx-auto bg-white rounded-xl shadow-lg p-8">
+            <h1 class="text-xl font-bold text-gray-800">YewChat stopped working</h1>
+            <p class="mt-2 text-gray-600">Something broke that the app can't recover from. Reload to start again, and if it keeps happening, include the details below in your bug report.</p>
+            <p id="crash-message" class="mt-4 text-sm text-red-600 font-mono break-words"></p>
+            <textarea id="crash-details" readonly class="mt-4 w-full h-40 p-2 text-xs text-gray-600 font-mono bg-gray-100 rounded resize-none"></textarea>
+            <div class="mt-4 flex justify-end">
+                <button id="crash-copy" class="px-4 py-2 rounded-lg border border-gray-300 text-gray-700 hover:bg-gray-50">Copy details</button>
+                <button id="crash-reload" class="ms-2 px-4 py-2 rounded-lg bg-purple-600 hover:bg-purple-700 text-white">Reload</button>
+            </div>
+        </div>
+    </div>
+</div>
+"#;
+
+/// Replaces the default panic hook, which only reaches the console, with one
+/// that swaps the page for a crash screen the user can report from.
+pub fn install() {
+    std::panic::set_hook(Box::new(|info| {
+        let message = info.to_string();
+        log::error!("{}", message);
+        // Timers keep firing into the broken app; one screen is enough.
+        if !CRASHED.with(|crashed| crashed.replace(true)) {
+            show_crash_screen(&message, &diagnostics(&message));
+        }
+    }));
+}
+
+/// Everything a bug report needs: version, page, browser, what went wrong and
+/// the last frames on the chat socket and lines of the log.
+pub fn diagnostics(message: &str) -> String {
+    let window = gloo::utils::window();
+    let frames = websocket::recent_frames();
+    let lines = logging::recent_lines();
+    format!(
+        "YewChat {}\nPage: {}\nBrowser: {}\nTime: {}\n\n{}\n\nRecent chat frames ({}):\n{}\n\nRecent log lines ({}):\n{}",
+        env!("CARGO_PKG_VERSION"),
+        window.location().href().unwrap_or_default(),
+        window.navigator().user_agent().unwrap_or_default(),
+        String::from(js_sys::Date::new_0().to_iso_string()),
+        message,
+        frames.len(),
+        frames.join("\n"),
+        lines.len(),
+        lines.join("\n"),
+    )
+}
+
+fn show_crash_screen(message: &str, details: &str) {
+    let document = gloo::utils::document();
+    let body = gloo::utils::body();
+    body.set_inner_html(CRASH_SCREEN);
+    let element = |id: &str| document.get_element_by_id(id);
+
+    if let Some(message_element) = element("crash-message") {
+        message_element.set_text_content(Some(message));
+    }
+    let details_element = element("crash-details").and_then(|e| e.dyn_into::<HtmlTextAreaElement>().ok());
+    if let Some(details_element) = &details_element {
+        details_element.set_value(details);
+    }
+
+    if let Some(copy) = element("crash-copy").and_then(|e| e.dyn_into::<HtmlElement>().ok()) {
+        let document = document.clone();
+        let on_copy = Closure::wrap(Box::new(move || {
+            if let Some(details_element) = &details_element {
+                details_element.select();
+            }
+            let copied = document
+                .dyn_ref::<HtmlDocument>()
+                .is_some_and(|document| document.exec_command("copy").unwrap_or(false));
+            if !copied {
+                log::error!("copying the crash details failed; they are selected for copying by hand");
+            }
+        }) as Box<dyn FnMut()>);
+        copy.set_onclick(Some(on_copy.as_ref().unchecked_ref()));
+        // The page is done for; the handler lives as long as it does.
+        on_copy.forget();
+    }
+    if let Some(reload) = element("crash-reload").and_then(|e| e.dyn_into::<HtmlElement>().ok()) {
+        let on_reload = Closure::wrap(Box::new(|| {
+            let _ = gloo::utils::window().location().reload();
+        }) as Box<dyn FnMut()>);
+        reload.set_onclick(Some(on_reload.as_ref().unchecked_ref()));
+        on_reload.forget();
+    }
+}
@@ -0,0 +1,136 @@
+//! The unread count as a badge on the tab's icon, for when the title is cut
+//! off among many tabs. It is drawn on an offscreen canvas over the page's
+//! own icon, or a plain one in the accent colour if the page has none, and
+//! turns red when one of the messages mentions the reader.
+
+use std::cell::RefCell;
+use std::f64::consts::PI;
+
+use gloo::events::EventListener;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement, HtmlLinkElement};
+
+/// Width and height of the drawn icon, in pixels.
+const SIZE: f64 = 32.0;
+
+const MENTION_COLOR: &str = "#dc2626";
+const UNREAD_COLOR: &str = "#1f2937";
+
+thread_local! {
+    /// The page's own icon, if it has one, put back once everything is read.
+    static ORIGINAL: RefCell<Option<Option<String>>> = const { RefCell::new(None) };
+    /// Waiting for the page's icon to load so the badge can go on it.
+    static LOADING: RefCell<Vec<EventListener>> = const { RefCell::new(Vec::new()) };
+}
+
+fn icon_link() -> Option<HtmlLinkElement> {
+    gloo::utils::document()
+        .query_selector("link[rel~='icon']")
+        .ok()
+        .flatten()
+        .and_then(|element| element.dyn_into().ok())
+}
+
+/// The accent from the appearance settings, kept in `--accent` as "r g b".
+fn accent() -> String {
+    let rgb = gloo::utils::document()
+        .document_element()
+        .and_then(|root| gloo::utils::window().get_computed_style(&root).ok().flatten())
+        .and_then(|style| style.get_property_value("--accent").ok())
+        .map(|rgb| rgb.trim().to_string())
+        .filter(|rgb| !rgb.is_empty())
+        .unwrap_or_else(|| "59 130 246".to_string());
+    format!("rgb({})", rgb)
+}
+
+/// Shows `unread` on the icon, red if any of them mention the reader; zero
+/// puts the page's own icon back.
+pub fn show_badge(unread: usize, mentioned: bool) {
+    let original = ORIGINAL.with(|original| {
+        original
+            .borrow_mut()
+            .get_or_insert_with(|| icon_link().map(|link| link.href()))
+            .clone()
+    });
+    // A badge still waiting on the icon is out of date now.
+    LOADING.with(|loading| loading.borrow_mut().clear());
+
+    if unread == 0 {
+        match (icon_link(), original) {
+            (Some(link), Some(href)) => link.set_href(&href),
+            (Some(link), None) => link.remove(),
+            (None, _) => {}
+        }
+        return;
+    }
+    let label = if unread > 9 { "9+".to_string() } else { unread.to_string() };
+    let href = match original {
+        Some(href) => href,
+        None => return draw(None, &label, mentioned),
+    };
+    let image = match HtmlImageElement::new() {
+        Ok(image) => image,
+        Err(e) => return log::error!("failed to load the page icon: {:?}", e),
+    };
+    let loaded = {
+        let (base, label) = (image.clone(), label.clone());
+        EventListener::once(&image, "load", move |_| draw(Some(&base), &label, mentioned))
+    };
+    // Better a badge on the plain icon than none at all.
+    let failed = EventListener::once(&image, "error", move |_| draw(None, &label, mentioned));
+    LOADING.with(|loading| *loading.borrow_mut() = vec![loaded, failed]);
+    image.set_src(&href);
+}
+
+fn draw(base: Option<&HtmlImageElement>, label: &str, mentioned: bool) {
+    if let Err(e) = try_draw(base, label, mentioned) {
+        log::error!("failed to draw the favicon badge: {:?}", e);
+    }
+}
+
+fn try_draw(base: Option<&HtmlImageElement>, label: &str, mentioned: bool) -> Result<(), JsValue> {
+    let document = gloo::utils::document();
+    let canvas: HtmlCanvasElement = document.create_element("canvas")?.dyn_into()?;
+    canvas.set_width(SIZE as u32);
+    canvas.set_height(SIZE as u32);
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("no 2d context"))?
+        .dyn_into()?;
+
+    match base {
+        Some(image) => context.draw_image_with_html_image_element_and_dw_and_dh(image, 0.0, 0.0, SIZE, SIZE)?,
+        None => {
+            context.set_fill_style_str(&accent());
+            context.begin_path();
+            context.arc(SIZE / 2.0, SIZE / 2.0, SIZE / 2.0, 0.0, 2.0 * PI)?;
+            context.fill();
+        }
+    }
+
+    let radius = SIZE * 0.3;
+    let (x, y) = (SIZE - radius, SIZE - radius);
+    context.set_fill_style_str(if mentioned { MENTION_COLOR } else { UNREAD_COLOR });
+    context.begin_path();
+    context.arc(x, y, radius, 0.0, 2.0 * PI)?;
+    context.fill();
+    context.set_fill_style_str("#ffffff");
+    context.set_font("bold 13px sans-serif");
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    context.fill_text(label, x, y + 1.0)?;
+
+    // Fails if the page's icon came from another origin and tainted the canvas.
+    let url = canvas.to_data_url()?;
+    let link = match icon_link() {
+        Some(link) => link,
+        None => {
+            let link: HtmlLinkElement = document.create_element("link")?.dyn_into()?;
+            link.set_rel("icon");
+            document.head().ok_or_else(|| JsValue::from_str("no head"))?.append_child(&link)?;
+            link
+        }
+    };
+    link.set_href(&url);
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+
+use gloo::storage::{LocalStorage, Storage};
+
+use crate::services::config;
+use crate::services::i18n::t;
+
+const STORAGE_KEY: &str = "yewchat.features";
+
+/// Experimental capabilities that stay off unless a deployment or the user
+/// turns them on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Feature {
+    Reactions,
+    Threads,
+    /// End-to-end encrypted direct messages.
+    E2e,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 3] = [Self::Reactions, Self::Threads, Self::E2e];
+
+    /// The name used in the `yewchat-features` meta tag and in localStorage.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Reactions => "reactions",
+            Self::Threads => "threads",
+            Self::E2e => "e2e",
+        }
+    }
+
+    pub fn label(self) -> String {
+        t(match self {
+            Self::Reactions => "feature-reactions",
+            Self::Threads => "feature-threads",
+            Self::E2e => "feature-e2e",
+        })
+    }
+}
+
+/// Which experimental features are on. A deployment enables them with
+/// `<meta name="yewchat-features" content="reactions,threads">`; anything the
+/// user switched on the settings page is kept in localStorage and wins.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeatureFlags {
+    deployment: Vec<Feature>,
+    overrides: BTreeMap<String, bool>,
+}
+
+impl FeatureFlags {
+    pub fn load() -> Self {
+        let deployment = config::meta("yewchat-features")
+            .map(|list| {
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|key| !key.is_empty())
+                    .filter_map(|key| {
+                        let feature = Feature::ALL.into_iter().find(|f| f.key() == key);
+                        if feature.is_none() {
+                            log::warn!("ignoring unknown feature {:?} in yewchat-features", key);
+                        }
+                        feature
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            deployment,
+            overrides: LocalStorage::get(STORAGE_KEY).unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, &self.overrides) {
+            log::error!("failed to persist feature flags: {:?}", e);
+        }
+    }
+
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.overrides
+            .get(feature.key())
+            .copied()
+            .unwrap_or_else(|| self.enabled_by_deployment(feature))
+    }
+
+    pub fn enabled_by_deployment(&self, feature: Feature) -> bool {
+        self.deployment.contains(&feature)
+    }
+
+    /// Turns `feature` on or off for this browser. Matching the deployment
+    /// drops the override, so a later change to the meta tag still applies.
+    pub fn set(&mut self, feature: Feature, enabled: bool) {
+        if enabled == self.enabled_by_deployment(feature) {
+            self.overrides.remove(feature.key());
+        } else {
+            self.overrides.insert(feature.key().to_string(), enabled);
+        }
+    }
+}
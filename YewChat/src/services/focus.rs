@@ -0,0 +1,81 @@
+//! Where keyboard focus goes when nothing else has a say. Screens register
+//! their main text field as a [`FocusTarget`] with [`use_focus_target`], and
+//! anything can then send focus there by name, e.g. back to the composer
+//! once a dialog closes, without holding on to the field's `NodeRef`.
+
+use std::cell::RefCell;
+
+use web_sys::HtmlElement;
+use yew::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FocusTarget {
+    /// The chat's message field.
+    Composer,
+    /// The name field on the login screen.
+    Username,
+}
+
+thread_local! {
+    /// Mounted targets, the most recently mounted last.
+    static TARGETS: RefCell<Vec<(FocusTarget, NodeRef)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn focus_node(node: &NodeRef) -> bool {
+    match node.cast::<HtmlElement>() {
+        Some(element) => element.focus().is_ok(),
+        None => false,
+    }
+}
+
+/// Nothing in particular has focus, e.g. the page just loaded or the
+/// element that had it was removed.
+fn unclaimed() -> bool {
+    let document = gloo::utils::document();
+    let active = document.active_element();
+    active.is_none() || document.body().is_some_and(|body| body.is_same_node(active.as_deref()))
+}
+
+/// Moves focus to `target`; false if it isn't on screen.
+pub fn focus(target: FocusTarget) -> bool {
+    let node = TARGETS.with(|targets| {
+        targets
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(t, _)| *t == target)
+            .map(|(_, node)| node.clone())
+    });
+    node.is_some_and(|node| focus_node(&node))
+}
+
+/// Gives focus back to the screen's main field when whatever had it went
+/// away, e.g. the button that opened a dialog which has since closed.
+pub fn restore() -> bool {
+    let node = TARGETS.with(|targets| targets.borrow().last().map(|(_, node)| node.clone()));
+    node.is_some_and(|node| focus_node(&node))
+}
+
+/// Registers `node` as `target` while the calling component is mounted,
+/// focusing it straight away when `autofocus` is set and focus isn't
+/// already somewhere else, such as a dialog opened on the same render.
+pub fn use_focus_target(target: FocusTarget, node: &NodeRef, autofocus: bool) {
+    let node = node.clone();
+    use_effect_with_deps(
+        move |_| {
+            TARGETS.with(|targets| targets.borrow_mut().push((target, node.clone())));
+            if autofocus && unclaimed() {
+                focus_node(&node);
+            }
+            move || {
+                TARGETS.with(|targets| {
+                    let mut targets = targets.borrow_mut();
+                    if let Some(at) = targets.iter().rposition(|(t, n)| *t == target && *n == node) {
+                        targets.remove(at);
+                    }
+                })
+            }
+        },
+        target,
+    );
+}
@@ -0,0 +1,211 @@
+//! UI strings, looked up by key in the active locale's catalog.
+//!
+//! Catalogs live in `locales/*.ftl` and use the simple end of Fluent syntax:
+//! `key = value` lines, indented lines continuing the value above on a new
+//! line, `#` comments, and `{ $name }` placeholders. Keys
+//! missing from a catalog fall back to English, so a partial translation
+//! still renders.
+//!
+//! Numbers and plural forms come from the browser's `Intl`, so they follow
+//! the active locale without the catalogs spelling them out.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use gloo::storage::{LocalStorage, Storage};
+use js_sys::{Array, Function, Intl, Object, Reflect};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+
+const STORAGE_KEY: &str = "yewchat.locale";
+/// Languages written right to left. A catalog in any of them mirrors the layout.
+const RTL_LANGUAGES: [&str; 6] = ["ar", "dv", "fa", "he", "ps", "ur"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Locale {
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "es")]
+    Spanish,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Self::English, Self::Spanish];
+
+    /// BCP 47 tag, as used by `<html lang>` and `navigator.language`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+        }
+    }
+
+    /// The language's name in itself, so people can find theirs in the list.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::Spanish => "Español",
+        }
+    }
+
+    /// The `dir` for a page in this language.
+    pub fn direction(self) -> &'static str {
+        if RTL_LANGUAGES.contains(&self.code()) {
+            "rtl"
+        } else {
+            "ltr"
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Self::English => include_str!("../../locales/en.ftl"),
+            Self::Spanish => include_str!("../../locales/es.ftl"),
+        }
+    }
+
+    /// The saved choice, or the browser's language if we have it.
+    fn preferred() -> Self {
+        if let Ok(locale) = LocalStorage::get(STORAGE_KEY) {
+            return locale;
+        }
+        let language = gloo::utils::window().navigator().language().unwrap_or_default();
+        let primary = language.split('-').next().unwrap_or_default();
+        Self::ALL
+            .into_iter()
+            .find(|l| l.code().eq_ignore_ascii_case(primary))
+            .unwrap_or(Self::English)
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<Locale> = const { Cell::new(Locale::English) };
+    static CATALOGS: RefCell<HashMap<Locale, HashMap<&'static str, String>>> = RefCell::new(HashMap::new());
+}
+
+/// Picks the saved or browser locale. Call before the first render.
+pub fn init() {
+    use_locale(Locale::preferred());
+}
+
+pub fn locale() -> Locale {
+    CURRENT.with(Cell::get)
+}
+
+/// Switches and remembers the locale. Views pick it up as they re-render.
+pub fn set_locale(locale: Locale) {
+    if let Err(e) = LocalStorage::set(STORAGE_KEY, locale) {
+        log::error!("failed to persist locale: {:?}", e);
+    }
+    use_locale(locale);
+}
+
+/// Also sets `lang` and `dir` on `<html>`; the views use logical classes
+/// (`ms-*`, `text-start`, ...) and flex order, so they mirror with it.
+fn use_locale(locale: Locale) {
+    CURRENT.with(|current| current.set(locale));
+    if let Some(root) = gloo::utils::document().document_element() {
+        for (name, value) in [("lang", locale.code()), ("dir", locale.direction())] {
+            if let Err(e) = root.set_attribute(name, value) {
+                log::error!("failed to set the page {}: {:?}", name, e);
+            }
+        }
+    }
+}
+
+/// The string for `key` in the active locale.
+pub fn t(key: &str) -> String {
+    t_with(key, &[])
+}
+
+/// The string for `key` with each `{ $name }` replaced by its value in `args`.
+pub fn t_with(key: &str, args: &[(&str, &str)]) -> String {
+    let mut text = match lookup(locale(), key).or_else(|| lookup(Locale::English, key)) {
+        Some(text) => text,
+        None => {
+            log::warn!("no string for {}", key);
+            return key.to_string();
+        }
+    };
+    for (name, value) in args {
+        text = text.replace(&format!("{{ ${} }}", name), value);
+    }
+    text
+}
+
+/// The string for `{key}-{category}`, where the category is the CLDR plural
+/// form of `count` (`one`, `other`, ...), with `{ $count }` set to the
+/// formatted number. Forms a catalog leaves out use `{key}-other`.
+pub fn t_count(key: &str, count: usize) -> String {
+    let rules = Intl::PluralRules::new(&locales(), &Object::new());
+    let form = format!("{}-{}", key, String::from(rules.select(count as f64)));
+    let key = if lookup(locale(), &form).or_else(|| lookup(Locale::English, &form)).is_some() {
+        form
+    } else {
+        format!("{}-other", key)
+    };
+    t_with(&key, &[("count", &number(count as f64))])
+}
+
+/// `n` with the active locale's digits, grouping and decimal mark.
+pub fn number(n: f64) -> String {
+    format_number(n, &Object::new())
+}
+
+/// `n` through `Intl.NumberFormat` with the given options, e.g. a `unit` style.
+pub fn format_number(n: f64, options: &Object) -> String {
+    let format = Intl::NumberFormat::new(&locales(), options).format();
+    format
+        .call1(&JsValue::UNDEFINED, &n.into())
+        .ok()
+        .and_then(|text| text.as_string())
+        .unwrap_or_else(|| n.to_string())
+}
+
+/// The active locale as the `locales` argument of an `Intl` constructor.
+pub fn locales() -> Array {
+    Array::of1(&locale().code().into())
+}
+
+/// A member of `Intl` that js-sys doesn't bind, or an error on browsers
+/// without it.
+pub fn intl(name: &str) -> Result<Function, JsValue> {
+    let intl = Reflect::get(&js_sys::global(), &"Intl".into())?;
+    Reflect::get(&intl, &name.into())?.dyn_into()
+}
+
+fn lookup(locale: Locale, key: &str) -> Option<String> {
+    CATALOGS.with(|catalogs| {
+        catalogs
+            .borrow_mut()
+            .entry(locale)
+            .or_insert_with(|| parse(locale.source()))
+            .get(key)
+            .cloned()
+    })
+}
+
+fn parse(source: &'static str) -> HashMap<&'static str, String> {
+    let mut catalog = HashMap::new();
+    let mut current: Option<(&'static str, String)> = None;
+    for line in source.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            if let Some((_, value)) = &mut current {
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        if let Some((key, value)) = current.take() {
+            catalog.insert(key, value);
+        }
+        current = line
+            .split_once('=')
+            .map(|(key, value)| (key.trim(), value.trim().to_string()));
+    }
+    catalog.extend(current);
+    catalog
+}
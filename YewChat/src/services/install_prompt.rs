@@ -0,0 +1,88 @@
+//! Our own "Install YewChat" button in place of the browser's mini-infobar.
+//! Browsers that can install the app fire `beforeinstallprompt`, often before
+//! anything has rendered, so it is caught at start-up and held back until the
+//! reader asks for it.
+
+use std::cell::RefCell;
+
+use gloo::events::{EventListener, EventListenerOptions};
+use js_sys::{Function, Promise, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use yew::prelude::*;
+
+thread_local! {
+    /// The `beforeinstallprompt` event, which can only be shown once.
+    static DEFERRED: RefCell<Option<Event>> = const { RefCell::new(None) };
+}
+
+fn available() -> bool {
+    DEFERRED.with(|deferred| deferred.borrow().is_some())
+}
+
+/// Stops the browser offering the install itself and keeps the event for
+/// [`use_install_prompt`]. Call before the first render.
+pub fn capture() {
+    let window = gloo::utils::window();
+    EventListener::new_with_options(
+        &window,
+        "beforeinstallprompt",
+        EventListenerOptions::enable_prevent_default(),
+        |event| {
+            event.prevent_default();
+            DEFERRED.with(|deferred| *deferred.borrow_mut() = Some(event.clone()));
+        },
+    )
+    .forget();
+    EventListener::new(&window, "appinstalled", |_| {
+        DEFERRED.with(|deferred| deferred.borrow_mut().take());
+    })
+    .forget();
+}
+
+/// Shows the browser's install dialog and waits for the reader's answer.
+async fn prompt() -> Result<bool, JsValue> {
+    let event = match DEFERRED.with(|deferred| deferred.borrow_mut().take()) {
+        Some(event) => event,
+        None => return Ok(false),
+    };
+    let prompt: Function = Reflect::get(&event, &"prompt".into())?.dyn_into()?;
+    prompt.call0(&event)?;
+    let choice = JsFuture::from(Promise::from(Reflect::get(&event, &"userChoice".into())?)).await?;
+    Ok(Reflect::get(&choice, &"outcome".into())?.as_string().as_deref() == Some("accepted"))
+}
+
+/// A callback that shows the install dialog, or `None` while the browser has
+/// nothing to offer: it isn't supported, YewChat is installed already, or
+/// the reader just turned it down. Re-renders the caller when that changes.
+pub fn use_install_prompt() -> Option<Callback<()>> {
+    let installable = use_state(available);
+    {
+        let installable = installable.clone();
+        use_effect_with_deps(
+            move |_| {
+                let window = gloo::utils::window();
+                let (offered, installed) = (installable.clone(), installable);
+                // Registered after `capture`'s, so the event is already kept.
+                let listeners = [
+                    EventListener::new(&window, "beforeinstallprompt", move |_| offered.set(available())),
+                    EventListener::new(&window, "appinstalled", move |_| installed.set(false)),
+                ];
+                move || drop(listeners)
+            },
+            (),
+        );
+    }
+    (*installable).then(|| {
+        Callback::from(move |_| {
+            let installable = installable.clone();
+            spawn_local(async move {
+                match prompt().await {
+                    Ok(accepted) => log::debug!("install prompt answered, accepted: {}", accepted),
+                    Err(e) => log::error!("failed to show the install prompt: {:?}", e),
+                }
+                installable.set(available());
+            });
+        })
+    })
+}
@@ -0,0 +1,57 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+const STORAGE_KEY: &str = "yewchat.layout";
+
+/// Narrowest and widest the sidebar can be dragged, in CSS pixels.
+pub const SIDEBAR_MIN_WIDTH: u32 = 200;
+pub const SIDEBAR_MAX_WIDTH: u32 = 480;
+
+/// How the user arranged the chat screen, kept across reloads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Layout {
+    /// Whether the sidebar is open on small screens; it always shows from `md` up.
+    pub sidebar_visible: bool,
+    pub sidebar_width: u32,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            sidebar_visible: true,
+            sidebar_width: 288,
+        }
+    }
+}
+
+impl Layout {
+    pub fn load() -> Self {
+        let layout: Self = LocalStorage::get(STORAGE_KEY).unwrap_or_default();
+        Self {
+            sidebar_width: clamp_sidebar_width(layout.sidebar_width as f64),
+            ..layout
+        }
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, self) {
+            log::error!("failed to persist layout: {:?}", e);
+        }
+    }
+}
+
+/// Below Tailwind's `md` breakpoint, where the sidebar covers the chat
+/// rather than sitting beside it.
+pub fn narrow_screen() -> bool {
+    gloo::utils::window()
+        .match_media("(max-width: 767px)")
+        .ok()
+        .flatten()
+        .is_some_and(|query| query.matches())
+}
+
+/// `width` rounded and kept within the sidebar's limits.
+pub fn clamp_sidebar_width(width: f64) -> u32 {
+    (width.round().max(0.0) as u32).clamp(SIDEBAR_MIN_WIDTH, SIDEBAR_MAX_WIDTH)
+}
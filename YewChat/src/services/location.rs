@@ -0,0 +1,34 @@
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{Position, PositionError};
+use yew::Callback;
+
+use crate::services::i18n::{t, t_with};
+
+/// Asks the browser for the current position once, reporting `(latitude, longitude)`
+/// or a human-readable error. The browser prompts for permission on first use.
+pub fn current_position(on_success: Callback<(f64, f64)>, on_error: Callback<String>) {
+    let geolocation = match web_sys::window().and_then(|w| w.navigator().geolocation().ok()) {
+        Some(geolocation) => geolocation,
+        None => {
+            on_error.emit(t("location-unsupported"));
+            return;
+        }
+    };
+
+    let success = Closure::once_into_js(move |position: Position| {
+        let coords = position.coords();
+        on_success.emit((coords.latitude(), coords.longitude()));
+    });
+    let report_error = on_error.clone();
+    let failure = Closure::once_into_js(move |error: PositionError| {
+        report_error.emit(t_with("location-failed-reason", &[("error", &error.message())]));
+    });
+
+    if let Err(e) = geolocation
+        .get_current_position_with_error_callback(success.unchecked_ref(), Some(failure.unchecked_ref()))
+    {
+        log::error!("geolocation: {:?}", e);
+        on_error.emit(t("location-failed"));
+    }
+}
@@ -0,0 +1,120 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+use gloo::storage::{LocalStorage, Storage};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use wasm_bindgen::JsValue;
+use web_sys::console;
+
+const LEVEL_KEY: &str = "yewchat.log-level";
+
+/// How many log lines are kept for the debug panel and bug reports.
+const RECENT_LINES_KEPT: usize = 200;
+
+thread_local! {
+    static RECENT_LINES: RefCell<VecDeque<String>> = const { RefCell::new(VecDeque::new()) };
+}
+
+pub const LEVELS: [LevelFilter; 6] = [
+    LevelFilter::Off,
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+    LevelFilter::Trace,
+];
+
+/// Writes to the browser console like `wasm_logger` did, and keeps the last
+/// lines around so they can be looked at without the devtools open.
+struct Logger;
+
+static LOGGER: Logger = Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{} {:<5} {}:{} {}",
+            String::from(js_sys::Date::new_0().to_iso_string()),
+            record.level(),
+            record.file().unwrap_or_else(|| record.target()),
+            record.line().unwrap_or_default(),
+            record.args(),
+        );
+        let message = JsValue::from_str(&line);
+        match record.level() {
+            Level::Trace => console::debug_1(&message),
+            Level::Debug => console::log_1(&message),
+            Level::Info => console::info_1(&message),
+            Level::Warn => console::warn_1(&message),
+            Level::Error => console::error_1(&message),
+        }
+        record_line(line);
+    }
+
+    fn flush(&self) {}
+}
+
+fn record_line(line: String) {
+    RECENT_LINES.with(|lines| {
+        // A panic inside a logging call can log again from the panic hook.
+        if let Ok(mut lines) = lines.try_borrow_mut() {
+            if lines.len() == RECENT_LINES_KEPT {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    });
+}
+
+/// Installs the logger at the level from the `?log=` query parameter if there
+/// is one, else the one picked in the debug panel, else `debug`.
+pub fn init() {
+    match log::set_logger(&LOGGER) {
+        Ok(_) => log::set_max_level(query_level().or_else(saved_level).unwrap_or(LevelFilter::Debug)),
+        Err(e) => console::error_1(&JsValue::from_str(&e.to_string())),
+    }
+}
+
+/// `?log=trace` and friends raise or lower the level for this page load only.
+fn query_level() -> Option<LevelFilter> {
+    let search = gloo::utils::window().location().search().ok()?;
+    let level = web_sys::UrlSearchParams::new_with_str(&search).ok()?.get("log")?;
+    LevelFilter::from_str(&level).ok()
+}
+
+fn saved_level() -> Option<LevelFilter> {
+    LocalStorage::get::<String>(LEVEL_KEY)
+        .ok()
+        .and_then(|level| LevelFilter::from_str(&level).ok())
+}
+
+pub fn level() -> LevelFilter {
+    log::max_level()
+}
+
+/// Changes the level right away and keeps it for later visits.
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+    if let Err(e) = LocalStorage::set(LEVEL_KEY, level.as_str()) {
+        log::error!("failed to persist the log level: {:?}", e);
+    }
+}
+
+/// The last few log lines, oldest first.
+pub fn recent_lines() -> Vec<String> {
+    // Also called from the panic hook, which may have interrupted `record_line`.
+    RECENT_LINES.with(|lines| {
+        lines
+            .try_borrow()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    })
+}
@@ -1,2 +1,37 @@
 pub mod websocket;
-pub mod event_bus;
\ No newline at end of file
+pub mod event_bus;
+pub mod account;
+pub mod alerts;
+pub mod avatar;
+pub mod captcha;
+pub mod chat_store;
+pub mod config;
+pub mod crash;
+pub mod favicon;
+pub mod feature_flags;
+pub mod focus;
+pub mod i18n;
+pub mod install_prompt;
+pub mod layout;
+pub mod location;
+pub mod logging;
+pub mod name_list;
+pub mod oauth;
+pub mod offline;
+pub mod onboarding;
+pub mod outbox;
+pub mod palette;
+pub mod profile;
+pub mod profanity;
+pub mod push;
+pub mod session;
+pub mod settings;
+pub mod shortcuts;
+pub mod sound;
+pub mod speech;
+pub mod telemetry;
+pub mod theme;
+pub mod time;
+pub mod title;
+pub mod translate;
+pub mod username;
\ No newline at end of file
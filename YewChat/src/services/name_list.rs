@@ -0,0 +1,59 @@
+use std::collections::BTreeSet;
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+pub const CONTACTS: &str = "yewchat.contacts";
+pub const BLOCKED: &str = "yewchat.blocked";
+pub const MUTED: &str = "yewchat.muted";
+/// Only read to carry mutes over to room alert levels.
+pub const MUTED_ROOMS: &str = "yewchat.muted-rooms";
+
+/// A set of names (friends, blocked users, muted users, ...) kept in localStorage
+/// under `key` so it survives reloads.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NameList {
+    #[serde(skip)]
+    key: &'static str,
+    names: BTreeSet<String>,
+}
+
+impl NameList {
+    pub fn load(key: &'static str) -> Self {
+        let mut list: Self = LocalStorage::get(key).unwrap_or_default();
+        list.key = key;
+        list
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = LocalStorage::set(self.key, self) {
+            log::error!("failed to persist {}: {:?}", self.key, e);
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    pub fn add(&mut self, name: &str) {
+        self.names.insert(name.to_string());
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.names.remove(name);
+    }
+
+    pub fn toggle(&mut self, name: &str) {
+        if !self.names.remove(name) {
+            self.add(name);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.names.iter()
+    }
+}
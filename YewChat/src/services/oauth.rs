@@ -0,0 +1,165 @@
+use gloo::storage::{SessionStorage, Storage};
+use reqwasm::http::Request;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use web_sys::UrlSearchParams;
+
+use crate::services::config;
+use crate::services::i18n::{t, t_with};
+
+const STORAGE_KEY: &str = "yewchat.oauth";
+const CALLBACK_PATH: &str = "/oauth/callback";
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    GitHub,
+    Google,
+}
+
+impl Provider {
+    pub const ALL: [Provider; 2] = [Self::GitHub, Self::Google];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::GitHub => "GitHub",
+            Self::Google => "Google",
+        }
+    }
+
+    fn slug(self) -> &'static str {
+        match self {
+            Self::GitHub => "github",
+            Self::Google => "google",
+        }
+    }
+
+    /// The OAuth app's client id, from `<meta name="yewchat-oauth-github-client-id">`
+    /// (or `-google-`). Providers without one aren't offered.
+    pub fn client_id(self) -> Option<String> {
+        config::meta(&format!("yewchat-oauth-{}-client-id", self.slug()))
+    }
+
+    fn authorize_url(self, client_id: &str, state: &str) -> String {
+        let (base, scope) = match self {
+            Self::GitHub => ("https://github.com/login/oauth/authorize", "read:user"),
+            Self::Google => ("https://accounts.google.com/o/oauth2/v2/auth", "openid profile"),
+        };
+        format!(
+            "{}?response_type=code&client_id={}&redirect_uri={}&scope={}&state={}",
+            base,
+            js_sys::encode_uri_component(client_id),
+            js_sys::encode_uri_component(&redirect_uri()),
+            js_sys::encode_uri_component(scope),
+            state
+        )
+    }
+}
+
+/// What the token endpoint returns after exchanging the authorization code.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthProfile {
+    pub name: String,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    pub access_token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PendingLogin {
+    provider: Provider,
+    state: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenRequest<'a> {
+    provider: Provider,
+    code: &'a str,
+    redirect_uri: String,
+}
+
+fn redirect_uri() -> String {
+    let origin = gloo::utils::window().location().origin().unwrap_or_default();
+    format!("{}{}", origin, CALLBACK_PATH)
+}
+
+/// 128 bits from the browser's CSPRNG, hex-encoded, so the state can't be guessed.
+fn random_state() -> Result<String, JsValue> {
+    let mut bytes = [0u8; 16];
+    gloo::utils::window().crypto()?.get_random_values_with_u8_array(&mut bytes)?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Sends the browser to `provider`'s consent page. Does nothing if the
+/// provider has no client id configured.
+pub fn start(provider: Provider) {
+    let client_id = match provider.client_id() {
+        Some(client_id) => client_id,
+        None => return,
+    };
+    // Guards the callback against requests we didn't start.
+    let state = match random_state() {
+        Ok(state) => state,
+        Err(e) => {
+            log::error!("failed to generate oauth state: {:?}", e);
+            return;
+        }
+    };
+    let pending = PendingLogin {
+        provider,
+        state: state.clone(),
+    };
+    if let Err(e) = SessionStorage::set(STORAGE_KEY, &pending) {
+        log::error!("failed to store oauth state: {:?}", e);
+        return;
+    }
+    if let Err(e) = gloo::utils::window()
+        .location()
+        .set_href(&provider.authorize_url(&client_id, &state))
+    {
+        log::error!("failed to redirect to {}: {:?}", provider.label(), e);
+    }
+}
+
+/// Finishes a login on the callback route: checks the `state` we stored, then
+/// trades the code for a token at the endpoint configured with
+/// `<meta name="yewchat-oauth-token-endpoint">`. The endpoint receives
+/// `{ provider, code, redirectUri }` and answers with an [`OAuthProfile`].
+pub async fn complete() -> Result<OAuthProfile, String> {
+    let search = gloo::utils::window().location().search().unwrap_or_default();
+    let params = UrlSearchParams::new_with_str(&search).map_err(|_| t("oauth-malformed-callback"))?;
+    if let Some(error) = params.get("error") {
+        return Err(t_with("oauth-refused", &[("error", &error)]));
+    }
+    let code = params.get("code").ok_or_else(|| t("oauth-no-code"))?;
+
+    let pending: PendingLogin =
+        SessionStorage::get(STORAGE_KEY).map_err(|_| t("oauth-not-started"))?;
+    SessionStorage::delete(STORAGE_KEY);
+    if params.get("state").as_deref() != Some(pending.state.as_str()) {
+        return Err(t("oauth-state-mismatch"));
+    }
+
+    let endpoint = config::meta("yewchat-oauth-token-endpoint")
+        .ok_or_else(|| t("oauth-no-endpoint"))?;
+    let body = TokenRequest {
+        provider: pending.provider,
+        code: &code,
+        redirect_uri: redirect_uri(),
+    };
+    let response = Request::post(&endpoint)
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(&body).unwrap())
+        .send()
+        .await
+        .map_err(|e| t_with("oauth-unreachable", &[("error", &e.to_string())]))?;
+    if !response.ok() {
+        return Err(t_with("oauth-rejected", &[("status", &response.status().to_string())]));
+    }
+    response
+        .json::<OAuthProfile>()
+        .await
+        .map_err(|e| t_with("oauth-unexpected", &[("error", &e.to_string())]))
+}
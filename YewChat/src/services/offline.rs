@@ -0,0 +1,136 @@
+//! Keeps YewChat usable without a network. The service worker in
+//! `static/sw.js` caches the app shell so the page still loads, and the
+//! newest messages of each room are kept here so the chat has something to
+//! show until the connection is back.
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use gloo::events::EventListener;
+use gloo::storage::{LocalStorage, Storage};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{ServiceWorkerContainer, ServiceWorkerRegistration};
+use yew::prelude::*;
+
+use crate::components::chat::MessageData;
+
+const HISTORY_KEY: &str = "yewchat.offline-history";
+
+/// Messages kept per room; enough to scroll back through, small enough for
+/// localStorage.
+const KEPT: usize = 100;
+
+/// The service worker, served from the site root so it covers every page.
+const WORKER: &str = "/sw.js";
+
+fn container() -> Option<ServiceWorkerContainer> {
+    let navigator = gloo::utils::window().navigator();
+    js_sys::Reflect::has(&navigator, &"serviceWorker".into())
+        .unwrap_or(false)
+        .then(|| navigator.service_worker())
+}
+
+/// Installs the service worker, or updates it, and waits until it is active.
+pub async fn worker() -> Result<ServiceWorkerRegistration, JsValue> {
+    let container = container().ok_or_else(|| JsValue::from_str("no service workers in this browser"))?;
+    JsFuture::from(container.register(WORKER)).await?;
+    JsFuture::from(container.ready()?).await?.dyn_into()
+}
+
+/// Installs the service worker in the background, so the next visit loads
+/// without a network.
+pub fn install() {
+    if container().is_none() {
+        return;
+    }
+    spawn_local(async {
+        if let Err(e) = worker().await {
+            log::error!("failed to install the service worker: {:?}", e);
+        }
+    });
+}
+
+/// Whether the browser thinks it has a network.
+pub fn online() -> bool {
+    gloo::utils::window().navigator().on_line()
+}
+
+/// [`online`], re-rendering the caller when it changes.
+pub fn use_online() -> bool {
+    let online = use_state(online);
+    {
+        let online = online.clone();
+        use_effect_with_deps(
+            move |_| {
+                let window = gloo::utils::window();
+                let (up, down) = (online.clone(), online);
+                let listeners = [
+                    EventListener::new(&window, "online", move |_| up.set(true)),
+                    EventListener::new(&window, "offline", move |_| down.set(false)),
+                ];
+                move || drop(listeners)
+            },
+            (),
+        );
+    }
+    *online
+}
+
+fn load() -> BTreeMap<String, Vec<MessageData>> {
+    LocalStorage::get(HISTORY_KEY).unwrap_or_default()
+}
+
+fn save(history: &BTreeMap<String, Vec<MessageData>>) {
+    if let Err(e) = LocalStorage::set(HISTORY_KEY, history) {
+        log::error!("failed to persist offline history: {:?}", e);
+    }
+}
+
+/// The messages kept for `room`, oldest first.
+pub fn cached_history(room: &str) -> Vec<MessageData> {
+    load().remove(room).unwrap_or_default()
+}
+
+/// Adds what `room` shows now to what is kept for it. Notices, and messages
+/// meant to disappear, never reach the disk.
+pub fn keep_history(room: &str, messages: &[Rc<MessageData>]) {
+    let mut history = load();
+    let kept = history.entry(room.to_string()).or_default();
+    let before = kept.clone();
+    for message in messages {
+        if message.system || message.expires_at.is_some() {
+            continue;
+        }
+        let id = match message.id {
+            Some(id) => id,
+            None => continue,
+        };
+        if !kept.iter().any(|m| m.id == Some(id)) {
+            kept.push(MessageData::clone(message));
+        }
+    }
+    kept.sort_by_key(|m| m.id);
+    let excess = kept.len().saturating_sub(KEPT);
+    kept.drain(..excess);
+    if *kept != before {
+        save(&history);
+    }
+}
+
+/// Drops a message deleted on the server from every room's copy.
+pub fn forget_message(id: u64) {
+    let mut history = load();
+    let before = history.clone();
+    for messages in history.values_mut() {
+        messages.retain(|m| m.id != Some(id));
+    }
+    if history != before {
+        save(&history);
+    }
+}
+
+/// Forgets every kept message, e.g. when the user signs out.
+pub fn forget_history() {
+    LocalStorage::delete(HISTORY_KEY);
+}
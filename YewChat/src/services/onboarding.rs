@@ -0,0 +1,46 @@
+use std::collections::HashSet;
+
+use gloo::storage::{LocalStorage, Storage};
+use web_sys::{Notification, NotificationPermission};
+
+use crate::services::config;
+use crate::services::i18n::t;
+
+const STORAGE_KEY: &str = "yewchat.welcomed";
+const NOTIFICATION_PROMPT_KEY: &str = "yewchat.notification-prompt-dismissed";
+
+/// The welcome text, overridable per deployment with
+/// `<meta name="yewchat-welcome" content="...">` in `index.html`.
+pub fn welcome_text() -> String {
+    config::meta("yewchat-welcome")
+        .map(|text| text.replace("\\n", "\n"))
+        .unwrap_or_else(|| t("onboarding-welcome"))
+}
+
+/// Returns `true` the first time it is called for `room` in this browser.
+pub fn first_visit(room: &str) -> bool {
+    let mut welcomed: HashSet<String> = LocalStorage::get(STORAGE_KEY).unwrap_or_default();
+    let first = welcomed.insert(room.to_string());
+    if first {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, &welcomed) {
+            log::error!("failed to persist onboarding state: {:?}", e);
+        }
+    }
+    first
+}
+
+/// Whether to offer desktop notifications: they aren't on, the browser has
+/// never asked, and the user hasn't said "not now". Browsers hold it against
+/// sites that ask out of the blue, so we explain first and only then ask.
+pub fn notification_prompt_due(enabled: bool) -> bool {
+    !enabled
+        && Notification::permission() == NotificationPermission::Default
+        && !LocalStorage::get::<bool>(NOTIFICATION_PROMPT_KEY).unwrap_or(false)
+}
+
+/// Remembers "not now"; notifications can still be turned on in settings.
+pub fn dismiss_notification_prompt() {
+    if let Err(e) = LocalStorage::set(NOTIFICATION_PROMPT_KEY, true) {
+        log::error!("failed to persist onboarding state: {:?}", e);
+    }
+}
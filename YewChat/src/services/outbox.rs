@@ -0,0 +1,143 @@
+//! Messages written while offline, waiting to be sent. They are kept with the
+//! Cache API rather than localStorage so the service worker can read them
+//! too: with Background Sync it sends them as soon as the network is back,
+//! even if YewChat was closed. Otherwise the chat sends them when it next
+//! connects. Either way they go out in one `Outbox` frame, and the server
+//! skips any it has already posted.
+
+use js_sys::{Function, Reflect};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Cache, Response};
+
+use crate::services::offline;
+use crate::services::websocket::SERVER_URL;
+
+const CACHE: &str = "yewchat-outbox";
+const ENTRY: &str = "/outbox";
+
+/// What the service worker listens for in its `sync` event.
+const SYNC_TAG: &str = "outbox";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Queued {
+    /// Made up here, so the server can tell a retry from a new message.
+    pub id: String,
+    /// Set for a direct message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    pub message: String,
+}
+
+impl Queued {
+    pub fn new(to: Option<String>, message: String) -> Self {
+        Self {
+            id: format!("{}-{}", js_sys::Date::now() as u64, (js_sys::Math::random() * 1e9) as u64),
+            to,
+            message,
+        }
+    }
+}
+
+/// Everything the service worker needs to send the messages by itself.
+#[derive(Default, Serialize, Deserialize)]
+struct Outbox {
+    server: String,
+    /// The last session the server granted, which vouches for the author.
+    token: Option<String>,
+    messages: Vec<Queued>,
+}
+
+/// The `data` of an `Outbox` frame.
+#[derive(Serialize)]
+struct OutboxFrame<'a> {
+    token: &'a str,
+    messages: &'a [Queued],
+}
+
+async fn cache() -> Result<Cache, JsValue> {
+    JsFuture::from(gloo::utils::window().caches()?.open(CACHE)).await?.dyn_into()
+}
+
+async fn load() -> Result<Outbox, JsValue> {
+    let response = match JsFuture::from(cache().await?.match_with_str(ENTRY)).await?.dyn_into::<Response>() {
+        Ok(response) => response,
+        Err(_) => return Ok(Outbox::default()),
+    };
+    let text = JsFuture::from(response.text()?).await?.as_string().unwrap_or_default();
+    Ok(serde_json::from_str(&text).unwrap_or_default())
+}
+
+async fn store(mut outbox: Outbox) -> Result<(), JsValue> {
+    outbox.server = SERVER_URL.to_string();
+    let response = Response::new_with_opt_str(Some(&serde_json::to_string(&outbox).unwrap()))?;
+    JsFuture::from(cache().await?.put_with_str(ENTRY, &response)).await?;
+    Ok(())
+}
+
+/// Runs `change` on the stored outbox, logging instead of failing: the
+/// composer has nothing better to do with the error.
+async fn update(change: impl FnOnce(&mut Outbox)) {
+    let result = async {
+        let mut outbox = load().await?;
+        change(&mut outbox);
+        store(outbox).await
+    };
+    if let Err(e) = result.await {
+        log::error!("failed to update the outbox: {:?}", e);
+    }
+}
+
+/// What is still waiting to be sent, oldest first.
+pub async fn queued() -> Vec<Queued> {
+    match load().await {
+        Ok(outbox) => outbox.messages,
+        Err(e) => {
+            log::error!("failed to read the outbox: {:?}", e);
+            vec![]
+        }
+    }
+}
+
+/// Keeps `message` until it can be sent and asks the service worker to send
+/// it once the network is back.
+pub async fn push(message: Queued) {
+    update(|outbox| outbox.messages.push(message)).await;
+    if let Err(e) = request_sync().await {
+        // The chat sends it when it next connects instead.
+        log::debug!("no background sync: {:?}", e);
+    }
+}
+
+/// Remembers the session the server just granted, for the service worker to
+/// send as.
+pub async fn set_token(token: String) {
+    update(|outbox| outbox.token = Some(token)).await;
+}
+
+/// Drops the messages the server confirmed.
+pub async fn delivered(ids: Vec<String>) {
+    update(|outbox| outbox.messages.retain(|m| !ids.contains(&m.id))).await;
+}
+
+/// Forgets the outbox and the session in it, e.g. when the user signs out.
+pub fn clear() {
+    if let Err(e) = gloo::utils::window().caches().map(|caches| caches.delete(CACHE)) {
+        log::error!("failed to clear the outbox: {:?}", e);
+    }
+}
+
+/// The `data` of an `Outbox` frame sending `messages` with `token`.
+pub fn frame(token: &str, messages: &[Queued]) -> String {
+    serde_json::to_string(&OutboxFrame { token, messages }).unwrap()
+}
+
+/// `registration.sync.register(SYNC_TAG)`, which only some browsers have.
+async fn request_sync() -> Result<(), JsValue> {
+    let registration = offline::worker().await?;
+    let sync = Reflect::get(&registration, &"sync".into())?;
+    let register: Function = Reflect::get(&sync, &"register".into())?.dyn_into()?;
+    JsFuture::from(js_sys::Promise::from(register.call1(&sync, &SYNC_TAG.into())?)).await?;
+    Ok(())
+}
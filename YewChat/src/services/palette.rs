@@ -0,0 +1,14 @@
+/// Colors picked to stay readable both as text on white and behind white text.
+const USER_COLORS: [&str; 8] = [
+    "#dc2626", "#ea580c", "#b45309", "#16a34a", "#0d9488", "#2563eb", "#7c3aed", "#db2777",
+];
+
+/// A stable color for `username`, so a speaker looks the same everywhere and
+/// across sessions.
+pub fn user_color(username: &str) -> &'static str {
+    // FNV-1a: tiny, and stable across builds unlike `DefaultHasher`.
+    let hash = username.bytes().fold(0x811c_9dc5u32, |h, b| {
+        (h ^ b as u32).wrapping_mul(0x0100_0193)
+    });
+    USER_COLORS[hash as usize % USER_COLORS.len()]
+}
@@ -0,0 +1,146 @@
+use std::collections::BTreeSet;
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+const STORAGE_KEY: &str = "yewchat.profanity";
+
+const DEFAULT_WORDS: &[&str] = &[
+    "arse", "ass", "asshole", "bastard", "bitch", "bollocks", "crap", "damn", "dick", "fuck",
+    "fucking", "piss", "shit", "wanker",
+];
+
+/// Masks swear words in rendered messages. The built-in list can be extended
+/// with the user's own words; matching is whole-word and case-insensitive.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProfanityFilter {
+    pub enabled: bool,
+    pub extra_words: BTreeSet<String>,
+}
+
+impl Default for ProfanityFilter {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            extra_words: BTreeSet::new(),
+        }
+    }
+}
+
+impl ProfanityFilter {
+    pub fn load() -> Self {
+        LocalStorage::get(STORAGE_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, self) {
+            log::error!("failed to persist profanity filter: {:?}", e);
+        }
+    }
+
+    pub fn add_word(&mut self, word: &str) {
+        let word = word.trim().to_lowercase();
+        if !word.is_empty() {
+            self.extra_words.insert(word);
+        }
+    }
+
+    pub fn remove_word(&mut self, word: &str) {
+        self.extra_words.remove(word);
+    }
+
+    fn is_profane(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        DEFAULT_WORDS.contains(&word.as_str()) || self.extra_words.contains(&word)
+    }
+
+    pub fn mask(&self, text: &str) -> String {
+        if self.enabled {
+            mask_words(text, |word| self.is_profane(word))
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Words of `text` that appear (case-insensitively) in `list`, in order.
+pub fn find_words(text: &str, list: &[String]) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && is_listed(word, list))
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn is_listed(word: &str, list: &[String]) -> bool {
+    let word = word.to_lowercase();
+    list.contains(&word)
+}
+
+/// Replaces every letter of each word in `text` for which `matches` returns
+/// true with `*`, leaving punctuation and spacing untouched.
+pub fn mask_words(text: &str, matches: impl Fn(&str) -> bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut word = String::new();
+    let flush = |word: &mut String, out: &mut String| {
+        if !word.is_empty() && matches(word) {
+            out.extend(word.chars().map(|_| '*'));
+        } else {
+            out.push_str(word);
+        }
+        word.clear();
+    };
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut word, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_listed_words_letter_for_letter() {
+        let filter = ProfanityFilter::default();
+        assert_eq!(filter.mask("well, shit happens"), "well, **** happens");
+        assert_eq!(filter.mask("damn-it!"), "****-it!");
+    }
+
+    #[test]
+    fn matches_regardless_of_case() {
+        let filter = ProfanityFilter::default();
+        assert_eq!(filter.mask("SHIT and Damn"), "**** and ****");
+        assert_eq!(find_words("No WAY", &["way".to_string()]), vec!["WAY"]);
+    }
+
+    #[test]
+    fn leaves_words_that_only_contain_a_listed_one() {
+        let filter = ProfanityFilter::default();
+        assert_eq!(filter.mask("a classic assessment"), "a classic assessment");
+        assert!(find_words("scrappy", &["crap".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn masks_added_words_until_removed() {
+        let mut filter = ProfanityFilter::default();
+        filter.add_word("  Heck ");
+        assert_eq!(filter.mask("oh heck"), "oh ****");
+        filter.remove_word("heck");
+        assert_eq!(filter.mask("oh heck"), "oh heck");
+    }
+
+    #[test]
+    fn leaves_everything_when_disabled() {
+        let filter = ProfanityFilter {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(filter.mask("shit"), "shit");
+    }
+}
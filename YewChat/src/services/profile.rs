@@ -0,0 +1,60 @@
+use serde::Deserialize;
+
+use crate::components::chat::{MsgTypes, Role};
+use crate::services::avatar;
+use crate::services::i18n::t_with;
+use crate::services::websocket::request;
+
+/// What the server tells anyone about a user, for the profile page.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicProfile {
+    pub name: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub(crate) role: Role,
+    #[serde(default)]
+    pub joined_at: Option<f64>,
+    /// Set instead of `joined_at` when the user has left.
+    #[serde(default)]
+    pub last_seen: Option<f64>,
+    /// Rooms the user is currently in.
+    #[serde(default)]
+    pub rooms: Vec<String>,
+}
+
+impl PublicProfile {
+    pub fn avatar(&self) -> String {
+        self.avatar
+            .clone()
+            .filter(|a| !a.is_empty())
+            .unwrap_or_else(|| avatar::default_avatar(&self.name))
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.display_name
+            .as_deref()
+            .filter(|n| !n.is_empty())
+            .unwrap_or(&self.name)
+    }
+
+    pub fn online(&self) -> bool {
+        !self.rooms.is_empty()
+    }
+}
+
+pub async fn fetch(name: &str) -> Result<PublicProfile, String> {
+    let reply = request(MsgTypes::Whois, name.to_string()).await?;
+    match reply.message_type {
+        MsgTypes::UserInfo => serde_json::from_str(&reply.data.unwrap_or_default())
+            .map_err(|e| t_with("server-unexpected-reply", &[("error", &e.to_string())])),
+        _ => Err(t_with("profile-unknown-user", &[("name", name)])),
+    }
+}
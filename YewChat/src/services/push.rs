@@ -0,0 +1,109 @@
+//! Web Push, for mentions and direct messages that arrive while no tab is
+//! open. The browser subscribes with the server's VAPID key, the chat hands
+//! the subscription over each time it connects, and `static/sw.js` shows what
+//! the server pushes.
+
+use js_sys::{Reflect, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{PushManager, PushSubscription, PushSubscriptionOptionsInit, ServiceWorkerContainer, ServiceWorkerRegistration};
+
+use crate::services::{config, offline};
+
+/// Where this browser stands with push, as shown in settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PushState {
+    /// No `yewchat-vapid-key` was configured, so there is nothing to offer.
+    Unconfigured,
+    /// The browser has no service workers or no push.
+    Unsupported,
+    Off,
+    On,
+}
+
+/// The server's public VAPID key, `<meta name="yewchat-vapid-key">`.
+fn vapid_key() -> Option<String> {
+    config::meta("yewchat-vapid-key")
+}
+
+fn supported() -> bool {
+    let window = gloo::utils::window();
+    Reflect::has(&window.navigator(), &"serviceWorker".into()).unwrap_or(false)
+        && Reflect::has(&window, &"PushManager".into()).unwrap_or(false)
+}
+
+fn container() -> ServiceWorkerContainer {
+    gloo::utils::window().navigator().service_worker()
+}
+
+/// This browser's subscription, if it has one. Doesn't install the worker.
+async fn current() -> Result<Option<PushSubscription>, JsValue> {
+    let registration = match JsFuture::from(container().get_registration()).await?.dyn_into::<ServiceWorkerRegistration>() {
+        Ok(registration) => registration,
+        Err(_) => return Ok(None),
+    };
+    let subscription = JsFuture::from(registration.push_manager()?.get_subscription()?).await?;
+    Ok(subscription.dyn_into().ok())
+}
+
+pub async fn state() -> PushState {
+    if vapid_key().is_none() {
+        return PushState::Unconfigured;
+    }
+    if !supported() {
+        return PushState::Unsupported;
+    }
+    match current().await {
+        Ok(Some(_)) => PushState::On,
+        Ok(None) => PushState::Off,
+        Err(e) => {
+            log::error!("failed to read the push subscription: {:?}", e);
+            PushState::Off
+        }
+    }
+}
+
+/// The subscription as the JSON the server expects, or `None` while push is off.
+pub async fn subscription() -> Option<String> {
+    if vapid_key().is_none() || !supported() {
+        return None;
+    }
+    match current().await {
+        Ok(subscription) => subscription
+            .and_then(|subscription| js_sys::JSON::stringify(&subscription).ok())
+            .map(String::from),
+        Err(e) => {
+            log::error!("failed to read the push subscription: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Installs the worker and subscribes, asking for notification permission
+/// if that hasn't been decided yet.
+pub async fn subscribe() -> Result<(), JsValue> {
+    let key = vapid_key().ok_or_else(|| JsValue::from_str("no VAPID key configured"))?;
+    let manager: PushManager = offline::worker().await?.push_manager()?;
+    let options = PushSubscriptionOptionsInit::new();
+    options.set_user_visible_only(true);
+    options.set_application_server_key(&decode_key(&key)?);
+    JsFuture::from(manager.subscribe_with_options(&options)?).await?;
+    Ok(())
+}
+
+/// Drops this browser's subscription; the server forgets it the next time a
+/// push to it is refused.
+pub async fn unsubscribe() -> Result<(), JsValue> {
+    if let Some(subscription) = current().await? {
+        JsFuture::from(subscription.unsubscribe()?).await?;
+    }
+    Ok(())
+}
+
+/// The raw bytes of a base64url key, which is how `subscribe` wants them.
+fn decode_key(key: &str) -> Result<JsValue, JsValue> {
+    let base64 = key.trim().replace('-', "+").replace('_', "/");
+    let padded = format!("{}{}", base64, "=".repeat((4 - base64.len() % 4) % 4));
+    let bytes: Vec<u8> = gloo::utils::window().atob(&padded)?.chars().map(|c| c as u8).collect();
+    Ok(Uint8Array::from(bytes.as_slice()).into())
+}
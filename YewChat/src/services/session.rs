@@ -0,0 +1,64 @@
+use gloo::storage::{LocalStorage, SessionStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::UserState;
+
+const STORAGE_KEY: &str = "yewchat.session";
+const DRAFT_KEY: &str = "yewchat.draft";
+
+/// Who is signed in, kept in localStorage so a reload goes straight back to
+/// the chat instead of the login screen.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub username: String,
+    pub avatar: Option<String>,
+    pub token: Option<String>,
+    #[serde(default)]
+    pub account_token: Option<String>,
+}
+
+impl Session {
+    pub fn load() -> Option<Self> {
+        LocalStorage::get(STORAGE_KEY).ok()
+    }
+
+    /// Remembers the user currently in the context.
+    pub fn remember(user: &UserState) {
+        let session = Self {
+            username: user.username.clone(),
+            avatar: user.avatar.clone(),
+            token: user.token.clone(),
+            account_token: user.account_token.clone(),
+        };
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, &session) {
+            log::error!("failed to persist session: {:?}", e);
+        }
+    }
+
+    pub fn forget() {
+        LocalStorage::delete(STORAGE_KEY);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Draft {
+    room: String,
+    text: String,
+}
+
+/// Keeps an unsent message for `room` in this tab while the user signs in again.
+pub fn save_draft(room: &str, text: &str) {
+    let draft = Draft {
+        room: room.to_string(),
+        text: text.to_string(),
+    };
+    if let Err(e) = SessionStorage::set(DRAFT_KEY, &draft) {
+        log::error!("failed to keep draft: {:?}", e);
+    }
+}
+
+pub fn take_draft(room: &str) -> Option<String> {
+    let draft: Draft = SessionStorage::get(DRAFT_KEY).ok()?;
+    SessionStorage::delete(DRAFT_KEY);
+    Some(draft.text).filter(|_| draft.room == room)
+}
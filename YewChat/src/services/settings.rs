@@ -0,0 +1,192 @@
+use gloo::events::EventListener;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+use yew::Callback;
+
+use crate::services::i18n::t;
+use crate::services::time::Clock;
+
+const STORAGE_KEY: &str = "yewchat.settings";
+
+/// How tightly the message list is packed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    /// Large avatars and room between messages.
+    #[default]
+    Cozy,
+    /// Small avatars and tight spacing, to fit more messages on screen.
+    Compact,
+}
+
+impl Density {
+    pub const ALL: [Density; 2] = [Self::Cozy, Self::Compact];
+
+    pub fn label(self) -> String {
+        t(match self {
+            Self::Cozy => "density-cozy",
+            Self::Compact => "density-compact",
+        })
+    }
+}
+
+/// The order of the sidebar's online users.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserSort {
+    /// By display name.
+    #[default]
+    Name,
+    /// Longest in the room first.
+    Joined,
+    /// Contacts, then moderators by rank, then everyone else by name.
+    Contacts,
+}
+
+impl UserSort {
+    pub const ALL: [UserSort; 3] = [Self::Name, Self::Joined, Self::Contacts];
+
+    pub fn label(self) -> String {
+        t(match self {
+            Self::Name => "sort-name",
+            Self::Joined => "sort-joined",
+            Self::Contacts => "sort-contacts",
+        })
+    }
+}
+
+/// Personal preferences, kept in localStorage so they survive reloads.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsStore {
+    /// Desktop notifications for messages that arrive while the tab is hidden.
+    pub notifications: bool,
+    /// Show GIFs and map previews inline rather than as links.
+    pub autoload_media: bool,
+    pub density: Density,
+    pub user_sort: UserSort,
+    /// Profile shared with the room each time the chat connects.
+    pub display_name: String,
+    pub status: String,
+    pub bio: String,
+    /// Anonymous usage events, sent only if the deployment collects them.
+    pub telemetry: bool,
+    pub clock: Clock,
+    /// IANA zone to show times in; empty for the device's own.
+    pub time_zone: String,
+    /// Language messages are translated into; empty for the interface's.
+    pub translate_to: String,
+    /// Screen readers only hear about messages that mention the user.
+    pub announce_mentions_only: bool,
+    /// Ask before leaving the chat with text in the composer or an unsaved avatar.
+    pub warn_unsent: bool,
+    /// A chime for every message, for ones mentioning the user, and for
+    /// direct messages; muted people and rooms stay quiet.
+    pub sound_messages: bool,
+    pub sound_mentions: bool,
+    pub sound_direct: bool,
+    /// Holds back notifications and sounds from every room until turned off.
+    pub do_not_disturb: bool,
+    /// Holds them back every day between `quiet_from` and `quiet_until`,
+    /// local `HH:MM` times that may span midnight.
+    pub quiet_hours: bool,
+    pub quiet_from: String,
+    pub quiet_until: String,
+}
+
+impl Default for SettingsStore {
+    fn default() -> Self {
+        Self {
+            notifications: false,
+            autoload_media: true,
+            density: Density::Cozy,
+            user_sort: UserSort::Name,
+            display_name: String::new(),
+            status: String::new(),
+            bio: String::new(),
+            telemetry: false,
+            clock: Clock::Auto,
+            time_zone: String::new(),
+            translate_to: String::new(),
+            announce_mentions_only: false,
+            warn_unsent: true,
+            sound_messages: false,
+            sound_mentions: true,
+            sound_direct: true,
+            do_not_disturb: false,
+            quiet_hours: false,
+            quiet_from: "22:00".to_string(),
+            quiet_until: "07:00".to_string(),
+        }
+    }
+}
+
+impl SettingsStore {
+    pub fn load() -> Self {
+        LocalStorage::get(STORAGE_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, self) {
+            log::error!("failed to persist settings: {:?}", e);
+        }
+    }
+}
+
+/// Asks the browser for permission to show notifications, if it hasn't been decided yet.
+pub fn request_notification_permission() {
+    if Notification::permission() == NotificationPermission::Default {
+        if let Err(e) = Notification::request_permission() {
+            log::error!("failed to request notification permission: {:?}", e);
+        }
+    }
+}
+
+/// Asks the browser for permission and waits for the answer: whether
+/// notifications may be shown.
+pub async fn ask_notification_permission() -> bool {
+    let promise = match Notification::request_permission() {
+        Ok(promise) => promise,
+        Err(e) => {
+            log::error!("failed to request notification permission: {:?}", e);
+            return false;
+        }
+    };
+    match JsFuture::from(promise).await {
+        Ok(answer) => answer.as_string().as_deref() == Some("granted"),
+        Err(e) => {
+            log::error!("failed to request notification permission: {:?}", e);
+            false
+        }
+    }
+}
+
+/// Whether the browser refuses notifications from this site; only the
+/// browser's own site settings can change that.
+pub fn notifications_blocked() -> bool {
+    Notification::permission() == NotificationPermission::Denied
+}
+
+/// Shows a desktop notification when the tab is in the background and the
+/// user allowed it. Clicking it brings the tab forward and calls `on_click`,
+/// e.g. to scroll to the message.
+pub fn notify(title: &str, body: &str, on_click: Callback<()>) {
+    if !gloo::utils::document().hidden() || Notification::permission() != NotificationPermission::Granted {
+        return;
+    }
+    let options = NotificationOptions::new();
+    options.set_body(body);
+    let notification = match Notification::new_with_options(title, &options) {
+        Ok(notification) => notification,
+        Err(e) => return log::error!("failed to show notification: {:?}", e),
+    };
+    // Lives as long as the notification; there is no telling when that ends.
+    EventListener::once(&notification.clone(), "click", move |_| {
+        let _ = gloo::utils::window().focus();
+        notification.close();
+        on_click.emit(());
+    })
+    .forget();
+}
@@ -0,0 +1,292 @@
+//! Keyboard shortcuts for the whole page. Components declare theirs with
+//! [`use_shortcut`]; one `keydown` listener on the document hands each press
+//! to whoever registered that combination, and the help dialog lists them.
+//!
+//! Escape is special: it closes the newest surface that declared itself
+//! with [`use_dismissable`], a dialog, a menu, a popover, and only reaches
+//! the shortcut registered for it once none is left open.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+use gloo::events::{EventListener, EventListenerOptions};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent};
+use yew::prelude::*;
+
+use crate::services::i18n::t;
+
+/// A key with the modifiers that must be held for it. Ctrl also matches ⌘ so
+/// the same shortcuts work on a Mac.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chord {
+    /// A `KeyboardEvent.key` value, compared ignoring case.
+    key: &'static str,
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+}
+
+impl Chord {
+    pub const fn key(key: &'static str) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    pub const fn ctrl(key: &'static str) -> Self {
+        Self {
+            ctrl: true,
+            ..Self::key(key)
+        }
+    }
+
+    pub const fn alt(key: &'static str) -> Self {
+        Self {
+            alt: true,
+            ..Self::key(key)
+        }
+    }
+
+    fn matches(&self, e: &KeyboardEvent) -> bool {
+        e.key().eq_ignore_ascii_case(self.key)
+            && (e.ctrl_key() || e.meta_key()) == self.ctrl
+            && e.alt_key() == self.alt
+            && e.shift_key() == self.shift
+    }
+
+    /// Plain keys mustn't get in the way of typing; Escape is the exception
+    /// since text fields have no use for it.
+    fn works_while_typing(&self) -> bool {
+        self.ctrl || self.alt || self.key == "Escape"
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            f.write_str("Ctrl+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+        match self.key {
+            "ArrowUp" => f.write_str("↑"),
+            "ArrowDown" => f.write_str("↓"),
+            "ArrowLeft" => f.write_str("←"),
+            "ArrowRight" => f.write_str("→"),
+            "Escape" => f.write_str("Esc"),
+            key if key.chars().count() == 1 => f.write_str(&key.to_uppercase()),
+            key => f.write_str(key),
+        }
+    }
+}
+
+/// Where a shortcut applies, to group them in the help dialog.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShortcutArea {
+    General,
+    Rooms,
+    People,
+}
+
+impl ShortcutArea {
+    /// In the order the help dialog lists them.
+    pub const ALL: [ShortcutArea; 3] = [ShortcutArea::General, ShortcutArea::Rooms, ShortcutArea::People];
+
+    pub fn label(&self) -> String {
+        t(match self {
+            ShortcutArea::General => "shortcut-area-general",
+            ShortcutArea::Rooms => "shortcut-area-rooms",
+            ShortcutArea::People => "shortcut-area-people",
+        })
+    }
+}
+
+struct Registration {
+    id: u64,
+    chord: Chord,
+    area: ShortcutArea,
+    /// Catalog key describing what the shortcut does, for the help dialog.
+    description: &'static str,
+    callback: Callback<KeyboardEvent>,
+}
+
+thread_local! {
+    static REGISTRATIONS: RefCell<Vec<Registration>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+    static LISTENER: RefCell<Option<EventListener>> = const { RefCell::new(None) };
+    /// Open surfaces Escape closes, the newest last.
+    static SURFACES: RefCell<Vec<(u64, Callback<()>)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn next_id() -> u64 {
+    NEXT_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    })
+}
+
+fn listen() {
+    LISTENER.with(|listener| {
+        listener.borrow_mut().get_or_insert_with(|| {
+            EventListener::new_with_options(
+                &gloo::utils::document(),
+                "keydown",
+                EventListenerOptions::enable_prevent_default(),
+                |e| dispatch(e.unchecked_ref()),
+            )
+        });
+    });
+}
+
+/// Keeps a shortcut registered until dropped.
+pub struct ShortcutHandle(u64);
+
+impl Drop for ShortcutHandle {
+    fn drop(&mut self) {
+        let id = self.0;
+        REGISTRATIONS.with(|registrations| registrations.borrow_mut().retain(|r| r.id != id));
+    }
+}
+
+/// Sends presses of `chord` to `callback` for as long as the handle is kept.
+/// A combination belongs to whoever registered it first, so a second claim
+/// is refused rather than having one press do two things.
+pub fn register(
+    chord: Chord,
+    area: ShortcutArea,
+    description: &'static str,
+    callback: Callback<KeyboardEvent>,
+) -> Option<ShortcutHandle> {
+    let conflict = REGISTRATIONS.with(|registrations| {
+        registrations
+            .borrow()
+            .iter()
+            .find(|r| r.chord == chord)
+            .map(|r| r.description)
+    });
+    if let Some(taken) = conflict {
+        log::warn!("shortcut {} for {} is already taken by {}", chord, description, taken);
+        return None;
+    }
+
+    listen();
+    let id = next_id();
+    REGISTRATIONS.with(|registrations| {
+        registrations.borrow_mut().push(Registration {
+            id,
+            chord,
+            area,
+            description,
+            callback,
+        })
+    });
+    Some(ShortcutHandle(id))
+}
+
+fn dispatch(e: &KeyboardEvent) {
+    if e.default_prevented() || (e.repeat() && !e.key().starts_with("Arrow")) {
+        return;
+    }
+    // Left registered: its owner closing it is what takes it off the stack.
+    let newest = SURFACES.with(|surfaces| surfaces.borrow().last().map(|(_, on_dismiss)| on_dismiss.clone()));
+    if let Some(on_dismiss) = newest.filter(|_| Chord::key("Escape").matches(e)) {
+        e.prevent_default();
+        on_dismiss.emit(());
+        return;
+    }
+    let typing = e
+        .target()
+        .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+        .is_some_and(|element| {
+            element.is_content_editable() || matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+        });
+    // Cloned out first: the handler may well register or drop shortcuts.
+    let callback = REGISTRATIONS.with(|registrations| {
+        registrations
+            .borrow()
+            .iter()
+            .find(|r| r.chord.matches(e) && (!typing || r.chord.works_while_typing()))
+            .map(|r| r.callback.clone())
+    });
+    if let Some(callback) = callback {
+        e.prevent_default();
+        callback.emit(e.clone());
+    }
+}
+
+/// Every shortcut registered right now with what it does, by area. Areas
+/// without any are left out; within one they keep the order they were
+/// registered in.
+pub fn registered() -> Vec<(ShortcutArea, Vec<(String, String)>)> {
+    REGISTRATIONS.with(|registrations| {
+        let registrations = registrations.borrow();
+        ShortcutArea::ALL
+            .into_iter()
+            .map(|area| {
+                let shortcuts = registrations
+                    .iter()
+                    .filter(|r| r.area == area)
+                    .map(|r| (r.chord.to_string(), t(r.description)))
+                    .collect::<Vec<_>>();
+                (area, shortcuts)
+            })
+            .filter(|(_, shortcuts)| !shortcuts.is_empty())
+            .collect()
+    })
+}
+
+/// Registers `chord` while the calling component is mounted. `callback` may
+/// change from render to render; the latest one is always used.
+pub fn use_shortcut(chord: Chord, area: ShortcutArea, description: &'static str, callback: Callback<KeyboardEvent>) {
+    let latest = use_mut_ref(|| callback.clone());
+    *latest.borrow_mut() = callback;
+    use_effect_with_deps(
+        move |_| {
+            let handle = register(chord, area, description, Callback::from(move |e| {
+                // Not emitted under the borrow: the handler may re-render us.
+                let callback = latest.borrow().clone();
+                callback.emit(e)
+            }));
+            move || drop(handle)
+        },
+        (chord, area, description),
+    );
+}
+
+/// Puts the calling component's surface on top of the ones Escape closes
+/// for as long as `open` holds, so a press calls `on_dismiss` while it is
+/// the newest one open. Surfaces that must be answered pass a no-op, which
+/// keeps Escape from reaching anything behind them.
+pub fn use_dismissable(open: bool, on_dismiss: Callback<()>) {
+    let latest = use_mut_ref(|| on_dismiss.clone());
+    *latest.borrow_mut() = on_dismiss;
+    use_effect_with_deps(
+        move |open| {
+            let id = open.then(|| {
+                listen();
+                let id = next_id();
+                let on_dismiss = Callback::from(move |_| {
+                    let on_dismiss = latest.borrow().clone();
+                    on_dismiss.emit(())
+                });
+                SURFACES.with(|surfaces| surfaces.borrow_mut().push((id, on_dismiss)));
+                id
+            });
+            move || {
+                if let Some(id) = id {
+                    SURFACES.with(|surfaces| surfaces.borrow_mut().retain(|(other, _)| *other != id));
+                }
+            }
+        },
+        open,
+    );
+}
@@ -0,0 +1,81 @@
+//! Short chimes for what arrives in the chat. The clips under `static/sounds`
+//! are loaded once, when the chat opens, so the first one plays on time.
+
+use std::cell::RefCell;
+
+use web_sys::HtmlAudioElement;
+
+use crate::services::settings::SettingsStore;
+
+/// Loud enough to notice, quiet enough to keep the chat in the background.
+const VOLUME: f64 = 0.4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sound {
+    Message,
+    Mention,
+    Direct,
+}
+
+impl Sound {
+    const ALL: [Sound; 3] = [Sound::Message, Sound::Mention, Sound::Direct];
+
+    fn clip(self) -> &'static str {
+        match self {
+            Sound::Message => "/sounds/message.wav",
+            Sound::Mention => "/sounds/mention.wav",
+            Sound::Direct => "/sounds/direct.wav",
+        }
+    }
+
+    /// Whether the user wants to hear this one.
+    pub fn enabled(self, settings: &SettingsStore) -> bool {
+        match self {
+            Sound::Message => settings.sound_messages,
+            Sound::Mention => settings.sound_mentions,
+            Sound::Direct => settings.sound_direct,
+        }
+    }
+}
+
+thread_local! {
+    static CLIPS: RefCell<Vec<(Sound, HtmlAudioElement)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Starts fetching every clip; later calls do nothing.
+pub fn preload() {
+    CLIPS.with(|clips| {
+        let mut clips = clips.borrow_mut();
+        if !clips.is_empty() {
+            return;
+        }
+        for sound in Sound::ALL {
+            match HtmlAudioElement::new_with_src(sound.clip()) {
+                Ok(audio) => {
+                    audio.set_preload("auto");
+                    audio.set_volume(VOLUME);
+                    clips.push((sound, audio));
+                }
+                Err(e) => log::error!("failed to load sound {:?}: {:?}", sound, e),
+            }
+        }
+    });
+}
+
+/// Plays `sound` from the start, cutting off the same clip if it is still
+/// going. Browsers refuse until the user has interacted with the page; that
+/// is left alone rather than reported.
+pub fn play(sound: Sound) {
+    preload();
+    let audio = CLIPS.with(|clips| {
+        clips
+            .borrow()
+            .iter()
+            .find(|(s, _)| *s == sound)
+            .map(|(_, audio)| audio.clone())
+    });
+    if let Some(audio) = audio {
+        audio.set_current_time(0.0);
+        let _ = audio.play();
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{SpeechSynthesis, SpeechSynthesisUtterance, SpeechSynthesisVoice};
+
+const STORAGE_KEY: &str = "yewchat.speech";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpeechSettings {
+    pub rooms: HashSet<String>,
+    pub rate: f32,
+    pub voice: Option<String>,
+}
+
+impl Default for SpeechSettings {
+    fn default() -> Self {
+        Self {
+            rooms: HashSet::new(),
+            rate: 1.0,
+            voice: None,
+        }
+    }
+}
+
+impl SpeechSettings {
+    pub fn load() -> Self {
+        LocalStorage::get(STORAGE_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, self) {
+            log::error!("failed to persist speech settings: {:?}", e);
+        }
+    }
+
+    pub fn is_enabled(&self, room: &str) -> bool {
+        self.rooms.contains(room)
+    }
+
+    pub fn toggle(&mut self, room: &str) {
+        if !self.rooms.remove(room) {
+            self.rooms.insert(room.to_string());
+        }
+    }
+}
+
+fn synth() -> Option<SpeechSynthesis> {
+    web_sys::window()?.speech_synthesis().ok()
+}
+
+fn available_voices(synth: &SpeechSynthesis) -> impl Iterator<Item = SpeechSynthesisVoice> {
+    synth
+        .get_voices()
+        .to_vec()
+        .into_iter()
+        .filter_map(|v| v.dyn_into::<SpeechSynthesisVoice>().ok())
+}
+
+/// Names of the voices the browser currently offers. Browsers load voices
+/// lazily, so this may be empty until shortly after the page has loaded.
+pub fn voices() -> Vec<String> {
+    synth()
+        .map(|s| available_voices(&s).map(|v| v.name()).collect())
+        .unwrap_or_default()
+}
+
+pub fn speak(text: &str, settings: &SpeechSettings) {
+    let synth = match synth() {
+        Some(synth) => synth,
+        None => return,
+    };
+    let utterance = match SpeechSynthesisUtterance::new_with_text(text) {
+        Ok(utterance) => utterance,
+        Err(e) => {
+            log::error!("speech: {:?}", e);
+            return;
+        }
+    };
+    utterance.set_rate(settings.rate);
+    if let Some(name) = &settings.voice {
+        let voice = available_voices(&synth).find(|v| &v.name() == name);
+        utterance.set_voice(voice.as_ref());
+    }
+    synth.speak(&utterance);
+}
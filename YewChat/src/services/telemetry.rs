@@ -0,0 +1,113 @@
+use std::cell::{Cell, RefCell};
+
+use gloo::events::EventListener;
+use gloo::timers::callback::Interval;
+use serde::Serialize;
+
+use crate::services::config;
+
+/// Events are sent once this many have queued up, or every [`FLUSH_EVERY_MS`].
+const BATCH_SIZE: usize = 20;
+const FLUSH_EVERY_MS: u32 = 60_000;
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static QUEUE: RefCell<Vec<Event>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Something worth counting. Events never carry names, message text or
+/// anything else that identifies a person.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum EventKind {
+    /// The server accepted our registration.
+    Connected,
+    /// The chat socket failed. Tests talk to a mock socket that never does.
+    #[cfg_attr(test, allow(dead_code))]
+    ConnectionFailed,
+    /// The server turned the registration down, e.g. for a taken name.
+    RegistrationRejected,
+    FeatureUsed { feature: &'static str },
+}
+
+#[derive(Serialize)]
+struct Event {
+    #[serde(flatten)]
+    kind: EventKind,
+    /// Milliseconds since the epoch.
+    at: f64,
+}
+
+#[derive(Serialize)]
+struct Batch<'a> {
+    version: &'static str,
+    events: &'a [Event],
+}
+
+/// Where batches go, from `<meta name="yewchat-telemetry-endpoint">`. Without
+/// it there is nothing to opt into and the setting is hidden.
+pub fn endpoint() -> Option<String> {
+    config::meta("yewchat-telemetry-endpoint")
+}
+
+/// Starts sending batches if the user opted in, and sends what is left when
+/// the page goes away.
+pub fn init(enabled: bool) {
+    set_enabled(enabled);
+    if endpoint().is_none() {
+        return;
+    }
+    // Both live as long as the page.
+    Interval::new(FLUSH_EVERY_MS, flush).forget();
+    EventListener::new(&gloo::utils::window(), "pagehide", |_| flush()).forget();
+}
+
+/// Follows the settings toggle; opting out drops anything not yet sent.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.with(|e| e.set(enabled));
+    if !enabled {
+        QUEUE.with(|queue| queue.borrow_mut().clear());
+    }
+}
+
+pub fn record(kind: EventKind) {
+    if !ENABLED.with(Cell::get) || endpoint().is_none() {
+        return;
+    }
+    let full = QUEUE.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        queue.push(Event {
+            kind,
+            at: js_sys::Date::now(),
+        });
+        queue.len() >= BATCH_SIZE
+    });
+    if full {
+        flush();
+    }
+}
+
+pub fn feature_used(feature: &'static str) {
+    record(EventKind::FeatureUsed { feature });
+}
+
+fn flush() {
+    let endpoint = match endpoint() {
+        Some(endpoint) => endpoint,
+        None => return,
+    };
+    let events = QUEUE.with(|queue| std::mem::take(&mut *queue.borrow_mut()));
+    if events.is_empty() {
+        return;
+    }
+    let batch = Batch {
+        version: env!("CARGO_PKG_VERSION"),
+        events: &events,
+    };
+    // A beacon still goes out while the page unloads, and nobody waits on it.
+    let body = serde_json::to_string(&batch).unwrap();
+    match gloo::utils::window().navigator().send_beacon_with_opt_str(&endpoint, Some(&body)) {
+        Ok(true) => log::debug!("sent {} telemetry events", events.len()),
+        _ => log::warn!("failed to send {} telemetry events", events.len()),
+    }
+}
@@ -0,0 +1,314 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, MediaQueryList};
+
+use crate::services::i18n::t;
+
+const STORAGE_KEY: &str = "yewchat.theme";
+const APPEARANCE_KEY: &str = "yewchat.appearance";
+const FONT_SIZE_KEY: &str = "yewchat.font-size";
+/// Where the theme lived before it got its own key.
+const LEGACY_SETTINGS_KEY: &str = "yewchat.settings";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+    /// Light, with text, borders and controls that meet WCAG AA contrast.
+    HighContrast,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Self::System, Self::Light, Self::Dark, Self::HighContrast];
+
+    pub fn label(self) -> String {
+        t(match self {
+            Self::System => "theme-system",
+            Self::Light => "theme-light",
+            Self::Dark => "theme-dark",
+            Self::HighContrast => "theme-high-contrast",
+        })
+    }
+
+    /// The saved choice, falling back to one made on the old settings page.
+    pub fn load() -> Self {
+        if let Ok(theme) = LocalStorage::get(STORAGE_KEY) {
+            return theme;
+        }
+        LocalStorage::get::<serde_json::Value>(LEGACY_SETTINGS_KEY)
+            .ok()
+            .and_then(|settings| serde_json::from_value(settings.get("theme")?.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, self) {
+            log::error!("failed to persist theme: {:?}", e);
+        }
+    }
+
+    pub fn is_dark(self) -> bool {
+        match self {
+            Self::Light | Self::HighContrast => false,
+            Self::Dark => true,
+            Self::System => system_query().is_some_and(|query| query.matches()),
+        }
+    }
+}
+
+/// `prefers-color-scheme: dark`, for following the system while it changes.
+pub fn system_query() -> Option<MediaQueryList> {
+    gloo::utils::window()
+        .match_media("(prefers-color-scheme: dark)")
+        .ok()
+        .flatten()
+}
+
+/// Toggles the `dark` and `contrast` classes on `<html>`, which `index.html`
+/// styles for pages that don't pick their own colours. `contrast` also darkens
+/// the accent and thickens focus outlines everywhere.
+pub fn apply(dark: bool, high_contrast: bool) {
+    if let Some(root) = gloo::utils::document().document_element() {
+        let classes = root.class_list();
+        for (class, on) in [("dark", dark), ("contrast", high_contrast)] {
+            let result = if on { classes.add_1(class) } else { classes.remove_1(class) };
+            if let Err(e) = result {
+                log::error!("failed to apply theme: {:?}", e);
+            }
+        }
+    }
+}
+
+/// How large messages and the composer are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FontSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl FontSize {
+    pub const ALL: [FontSize; 3] = [Self::Small, Self::Medium, Self::Large];
+
+    pub fn label(self) -> String {
+        t(match self {
+            Self::Small => "font-small",
+            Self::Medium => "font-medium",
+            Self::Large => "font-large",
+        })
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Self::Small => "font-size-small",
+            Self::Medium => "font-size-medium",
+            Self::Large => "font-size-large",
+        }
+    }
+
+    pub fn load() -> Self {
+        LocalStorage::get(FONT_SIZE_KEY).unwrap_or_default()
+    }
+
+    pub fn save(self) {
+        if let Err(e) = LocalStorage::set(FONT_SIZE_KEY, self) {
+            log::error!("failed to persist font size: {:?}", e);
+        }
+    }
+
+    /// Puts this size's class on `<html>`, which `index.html` turns into the
+    /// `text-message` size.
+    pub fn apply(self) {
+        if let Some(root) = gloo::utils::document().document_element() {
+            let classes = root.class_list();
+            for size in Self::ALL {
+                let result = if size == self {
+                    classes.add_1(size.class())
+                } else {
+                    classes.remove_1(size.class())
+                };
+                if let Err(e) = result {
+                    log::error!("failed to apply font size: {:?}", e);
+                }
+            }
+        }
+    }
+}
+
+/// The colour of buttons and of your own messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Accent {
+    Blue,
+    Purple,
+    Green,
+    Teal,
+    Rose,
+    Amber,
+}
+
+impl Accent {
+    pub const ALL: [Accent; 6] = [Self::Blue, Self::Purple, Self::Green, Self::Teal, Self::Rose, Self::Amber];
+
+    pub fn label(self) -> String {
+        t(match self {
+            Self::Blue => "accent-blue",
+            Self::Purple => "accent-purple",
+            Self::Green => "accent-green",
+            Self::Teal => "accent-teal",
+            Self::Rose => "accent-rose",
+            Self::Amber => "accent-amber",
+        })
+    }
+
+    /// RGB channels of the colour and of its darker hover shade, in the form
+    /// `index.html` expects for `--accent` and `--accent-dark`.
+    pub fn channels(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Blue => ("59 130 246", "37 99 235"),
+            Self::Purple => ("168 85 247", "147 51 234"),
+            Self::Green => ("34 197 94", "22 163 74"),
+            Self::Teal => ("20 184 166", "13 148 136"),
+            Self::Rose => ("244 63 94", "225 29 72"),
+            Self::Amber => ("245 158 11", "217 119 6"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BubbleStyle {
+    Rounded,
+    Soft,
+    Square,
+}
+
+impl BubbleStyle {
+    pub const ALL: [BubbleStyle; 3] = [Self::Rounded, Self::Soft, Self::Square];
+
+    pub fn label(self) -> String {
+        t(match self {
+            Self::Rounded => "bubbles-rounded",
+            Self::Soft => "bubbles-soft",
+            Self::Square => "bubbles-square",
+        })
+    }
+
+    fn radius(self) -> &'static str {
+        match self {
+            Self::Rounded => "0.5rem",
+            Self::Soft => "1.25rem",
+            Self::Square => "0.125rem",
+        }
+    }
+}
+
+/// Whether the page slides, fades and scrolls smoothly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Motion {
+    /// Follows the system's `prefers-reduced-motion`.
+    System,
+    Reduced,
+    Full,
+}
+
+impl Motion {
+    pub const ALL: [Motion; 3] = [Self::System, Self::Reduced, Self::Full];
+
+    pub fn label(self) -> String {
+        t(match self {
+            Self::System => "motion-system",
+            Self::Reduced => "motion-reduced",
+            Self::Full => "motion-full",
+        })
+    }
+
+    pub fn is_reduced(self) -> bool {
+        match self {
+            Self::Reduced => true,
+            Self::Full => false,
+            Self::System => gloo::utils::window()
+                .match_media("(prefers-reduced-motion: reduce)")
+                .ok()
+                .flatten()
+                .is_some_and(|query| query.matches()),
+        }
+    }
+}
+
+/// Whether to skip animations and smooth scrolling right now, for effects
+/// started from code; CSS transitions are switched off by [`Appearance::apply`].
+pub fn reduced_motion() -> bool {
+    Appearance::load().motion.is_reduced()
+}
+
+/// Accent colour, bubble shape and motion. They live in CSS custom properties
+/// and classes on `<html>`, so changing them restyles the page without a
+/// re-render.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Appearance {
+    pub accent: Accent,
+    pub bubbles: BubbleStyle,
+    pub motion: Motion,
+}
+
+impl Default for Appearance {
+    fn default() -> Self {
+        Self {
+            accent: Accent::Blue,
+            bubbles: BubbleStyle::Rounded,
+            motion: Motion::System,
+        }
+    }
+}
+
+impl Appearance {
+    pub fn load() -> Self {
+        LocalStorage::get(APPEARANCE_KEY).unwrap_or_default()
+    }
+
+    pub fn save(self) {
+        if let Err(e) = LocalStorage::set(APPEARANCE_KEY, self) {
+            log::error!("failed to persist appearance: {:?}", e);
+        }
+    }
+
+    /// Sets the custom properties `index.html` builds the `accent` colour and
+    /// `bubble` radius from, and the `motion-reduce`/`motion-full` class that
+    /// overrides the system's motion preference there.
+    pub fn apply(self) {
+        let root = match gloo::utils::document().document_element() {
+            Some(root) => root.unchecked_into::<HtmlElement>(),
+            None => return,
+        };
+        let (accent, accent_dark) = self.accent.channels();
+        let style = root.style();
+        for (property, value) in [
+            ("--accent", accent),
+            ("--accent-dark", accent_dark),
+            ("--bubble-radius", self.bubbles.radius()),
+        ] {
+            if let Err(e) = style.set_property(property, value) {
+                log::error!("failed to set {}: {:?}", property, e);
+            }
+        }
+        let classes = root.class_list();
+        for (class, on) in [
+            ("motion-reduce", self.motion == Motion::Reduced),
+            ("motion-full", self.motion == Motion::Full),
+        ] {
+            let result = if on { classes.add_1(class) } else { classes.remove_1(class) };
+            if let Err(e) = result {
+                log::error!("failed to set motion: {:?}", e);
+            }
+        }
+    }
+}
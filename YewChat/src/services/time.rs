@@ -0,0 +1,198 @@
+use std::cell::RefCell;
+
+use js_sys::{Array, Date, Function, Object, Reflect};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::services::i18n::{self, t, t_with};
+
+const HOUR_MS: f64 = 3_600_000.0;
+const DAY_MS: f64 = 24.0 * HOUR_MS;
+
+/// 12 or 24 hours on the clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Clock {
+    /// Whichever the interface language usually uses.
+    #[serde(rename = "auto")]
+    #[default]
+    Auto,
+    #[serde(rename = "12h")]
+    TwelveHour,
+    #[serde(rename = "24h")]
+    TwentyFourHour,
+}
+
+impl Clock {
+    pub const ALL: [Clock; 3] = [Self::Auto, Self::TwelveHour, Self::TwentyFourHour];
+
+    pub fn label(self) -> String {
+        t(match self {
+            Self::Auto => "clock-auto",
+            Self::TwelveHour => "clock-12h",
+            Self::TwentyFourHour => "clock-24h",
+        })
+    }
+}
+
+thread_local! {
+    /// The clock, and an IANA zone to show times in instead of the device's.
+    static PREFERENCES: RefCell<(Clock, Option<String>)> = const { RefCell::new((Clock::Auto, None)) };
+}
+
+/// Sets how every timestamp is shown from now on. An empty `time_zone` means
+/// the device's own.
+pub fn set_preferences(clock: Clock, time_zone: &str) {
+    let time_zone = Some(time_zone.trim().to_string()).filter(|zone| !zone.is_empty());
+    PREFERENCES.with(|preferences| *preferences.borrow_mut() = (clock, time_zone));
+}
+
+/// The zone the device is set to, e.g. `Europe/Madrid`.
+pub fn device_time_zone() -> Option<String> {
+    let format = js_sys::Intl::DateTimeFormat::new(&Array::new(), &Object::new());
+    Reflect::get(&format.resolved_options(), &"timeZone".into()).ok()?.as_string()
+}
+
+/// Every zone the browser can show times in, or none on browsers that can't
+/// list them.
+pub fn time_zones() -> Vec<String> {
+    let list = || -> Result<Array, JsValue> {
+        let supported = i18n::intl("supportedValuesOf")?;
+        supported.call1(&JsValue::UNDEFINED, &"timeZone".into())?.dyn_into()
+    };
+    list().map(|zones| zones.iter().filter_map(|zone| zone.as_string()).collect()).unwrap_or_default()
+}
+
+/// Calls one of `Date`'s `toLocale*String` methods with `fields` plus the
+/// user's clock and zone. A zone the browser doesn't know falls back to the
+/// device's rather than showing nothing.
+fn format(ms: f64, method: &str, fields: &[(&str, &str)]) -> String {
+    let date = Date::new(&JsValue::from_f64(ms));
+    let options = Object::new();
+    for (name, value) in fields {
+        let _ = Reflect::set(&options, &(*name).into(), &(*value).into());
+    }
+    PREFERENCES.with(|preferences| {
+        let (clock, time_zone) = &*preferences.borrow();
+        let hour12 = match clock {
+            Clock::Auto => None,
+            Clock::TwelveHour => Some(true),
+            Clock::TwentyFourHour => Some(false),
+        };
+        if let Some(hour12) = hour12 {
+            let _ = Reflect::set(&options, &"hour12".into(), &hour12.into());
+        }
+        if let Some(zone) = time_zone {
+            let _ = Reflect::set(&options, &"timeZone".into(), &zone.as_str().into());
+        }
+    });
+
+    let call = |options: &Object| -> Result<JsValue, JsValue> {
+        let method: Function = Reflect::get(&date, &method.into())?.dyn_into()?;
+        method.call2(&date, &i18n::locale().code().into(), options)
+    };
+    let formatted = call(&options).or_else(|e| {
+        log::warn!("couldn't format a time, using the device's zone: {:?}", e);
+        let _ = Reflect::delete_property(&options, &"timeZone".into());
+        call(&options)
+    });
+    formatted.ok().and_then(|text| text.as_string()).unwrap_or_default()
+}
+
+/// The time of day, e.g. `14:05` or `2:05 PM`.
+pub fn clock_time(ms: f64) -> String {
+    format(ms, "toLocaleTimeString", &[("hour", "numeric"), ("minute", "2-digit")])
+}
+
+/// The date and time in full, for tooltips.
+pub fn date_time(ms: f64) -> String {
+    format(ms, "toLocaleString", &[("dateStyle", "medium"), ("timeStyle", "short")])
+}
+
+fn day_key(ms: f64) -> String {
+    format(ms, "toLocaleDateString", &[("year", "numeric"), ("month", "numeric"), ("day", "numeric")])
+}
+
+/// Whether two moments fall on the same calendar day in the user's zone.
+pub fn same_day(a: f64, b: f64) -> bool {
+    day_key(a) == day_key(b)
+}
+
+enum Day {
+    Today,
+    Yesterday,
+    Other(String),
+}
+
+fn which_day(ms: f64, now: f64) -> Day {
+    if same_day(ms, now) {
+        Day::Today
+    } else if same_day(ms, now - DAY_MS) {
+        Day::Yesterday
+    } else if now - ms < 7.0 * DAY_MS {
+        Day::Other(format(ms, "toLocaleDateString", &[("weekday", "long")]))
+    } else {
+        Day::Other(format(ms, "toLocaleDateString", &[("year", "numeric"), ("month", "short"), ("day", "numeric")]))
+    }
+}
+
+/// The heading of a day's messages: today, yesterday, a weekday within the
+/// week, or the date.
+pub fn day(ms: f64, now: f64) -> String {
+    match which_day(ms, now) {
+        Day::Today => t("time-today"),
+        Day::Yesterday => t("time-yesterday"),
+        Day::Other(date) => date,
+    }
+}
+
+/// When someone was last around: how long ago within the hour, then the day
+/// and time.
+pub fn last_seen(at: f64, now: f64) -> String {
+    if now - at < HOUR_MS {
+        return relative(now - at);
+    }
+    let time = clock_time(at);
+    match which_day(at, now) {
+        Day::Today => t_with("time-today-at", &[("time", &time)]),
+        Day::Yesterday => t_with("time-yesterday-at", &[("time", &time)]),
+        Day::Other(date) => t_with("time-date-at", &[("date", &date), ("time", &time)]),
+    }
+}
+
+/// `value` `unit`s from now, negative for the past, e.g. "5 min. ago".
+fn relative_unit(value: f64, unit: &str) -> Result<String, JsValue> {
+    let options = Object::new();
+    Reflect::set(&options, &"style".into(), &"short".into())?;
+    let formatter = Reflect::construct(&i18n::intl("RelativeTimeFormat")?, &Array::of2(&i18n::locales(), &options))?;
+    let format: Function = Reflect::get(&formatter, &"format".into())?.dyn_into()?;
+    Ok(format.call2(&formatter, &value.into(), &unit.into())?.as_string().unwrap_or_default())
+}
+
+/// Formats how long ago something happened, given the elapsed milliseconds.
+/// Browsers without `Intl.RelativeTimeFormat` get the catalog's wording.
+pub fn relative(elapsed_ms: f64) -> String {
+    let minutes = (elapsed_ms / 60_000.0).max(0.0) as u64;
+    let (value, unit, fallback) = match minutes {
+        0 => return t("time-just-now"),
+        m if m < 60 => (m, "minute", "time-minutes-ago"),
+        m if m < 60 * 24 => (m / 60, "hour", "time-hours-ago"),
+        m => (m / (60 * 24), "day", "time-days-ago"),
+    };
+    relative_unit(-(value as f64), unit).unwrap_or_else(|_| t_with(fallback, &[("count", &i18n::number(value as f64))]))
+}
+
+/// Time left on a countdown in its largest whole unit, e.g. "5m" or "5 min".
+pub fn remaining(ms: f64) -> String {
+    let secs = (ms / 1000.0).ceil().max(0.0);
+    let (value, unit) = match secs {
+        s if s < 60.0 => (s, "second"),
+        s if s < 3600.0 => ((s / 60.0).floor(), "minute"),
+        s if s < 86400.0 => ((s / 3600.0).floor(), "hour"),
+        s => ((s / 86400.0).floor(), "day"),
+    };
+    let options = Object::new();
+    for (name, value) in [("style", "unit"), ("unit", unit), ("unitDisplay", "narrow")] {
+        let _ = Reflect::set(&options, &name.into(), &value.into());
+    }
+    i18n::format_number(value, &options)
+}
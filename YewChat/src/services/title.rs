@@ -0,0 +1,71 @@
+//! The tab's title, which counts unread messages as "(3) YewChat" while the
+//! reader is away from the tab and goes back to normal once they return.
+
+use std::cell::RefCell;
+
+use gloo::events::EventListener;
+use yew::prelude::*;
+
+use crate::services::alerts::RoomAlerts;
+use crate::services::chat_store::{ChatStore, StoreAction};
+use crate::services::favicon;
+
+thread_local! {
+    /// The title before any count was put in front of it.
+    static BASE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Whether the reader is looking elsewhere: another tab, another window, or
+/// the window minimised. Messages arriving now count as unread.
+pub fn away() -> bool {
+    let document = gloo::utils::document();
+    document.hidden() || !document.has_focus().unwrap_or(true)
+}
+
+/// Puts `unread` in front of the title, or takes it away at zero.
+pub fn show_unread(unread: usize) {
+    let document = gloo::utils::document();
+    let base = BASE.with(|base| base.borrow_mut().get_or_insert_with(|| document.title()).clone());
+    if unread > 0 {
+        document.set_title(&format!("({}) {}", unread, base));
+    } else {
+        document.set_title(&base);
+    }
+}
+
+/// Keeps the title and the favicon badge in step with the store's unread
+/// count, as far as `alerts` lets each room count, while the caller is
+/// mounted, and marks everything read when the reader comes back.
+pub fn use_unread_title(store: &ChatStore, alerts: &RoomAlerts) {
+    {
+        let store = store.dispatcher();
+        use_effect_with_deps(
+            move |_| {
+                let window = gloo::utils::window();
+                let document = gloo::utils::document();
+                let mark_read = move |_: &Event| {
+                    if !away() {
+                        store.dispatch(StoreAction::MarkRead);
+                    }
+                };
+                let listeners = [
+                    EventListener::new(&document, "visibilitychange", mark_read.clone()),
+                    EventListener::new(&window, "focus", mark_read),
+                ];
+                move || drop(listeners)
+            },
+            (),
+        );
+    }
+    use_effect_with_deps(
+        |&(unread, mentions): &(usize, usize)| {
+            show_unread(unread);
+            favicon::show_badge(unread, mentions > 0);
+            || {
+                show_unread(0);
+                favicon::show_badge(0, false);
+            }
+        },
+        (store.unread(alerts), store.unread_mentions(alerts)),
+    );
+}
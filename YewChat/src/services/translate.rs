@@ -0,0 +1,85 @@
+use js_sys::{Array, Function, Object, Reflect};
+use reqwasm::http::Request;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::services::config;
+use crate::services::i18n::{self, t, t_with};
+
+/// Offered as targets in settings. The endpoint decides which it can really do.
+pub const LANGUAGES: [&str; 18] = [
+    "ar", "de", "en", "es", "fr", "hi", "id", "it", "ja", "ko", "nl", "pl", "pt", "ru", "tr", "uk", "vi", "zh",
+];
+
+/// A message's text in the reader's language.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Translation {
+    pub text: String,
+    /// The language the endpoint detected the original as, if it said.
+    pub source: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    text: &'a str,
+    target: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranslateResponse {
+    text: String,
+    #[serde(default)]
+    detected_language: Option<String>,
+}
+
+/// Where translations come from, `<meta name="yewchat-translate-endpoint">`.
+/// Without it messages don't offer to translate.
+pub fn endpoint() -> Option<String> {
+    config::meta("yewchat-translate-endpoint")
+}
+
+/// The language to translate into: the one picked in settings, or the
+/// interface language when none is.
+pub fn target(preferred: &str) -> String {
+    if preferred.is_empty() {
+        i18n::locale().code().to_string()
+    } else {
+        preferred.to_string()
+    }
+}
+
+/// `code`'s name in the interface language, e.g. "German" for `de`.
+pub fn language_name(code: &str) -> String {
+    let name = || -> Result<JsValue, JsValue> {
+        let options = Object::new();
+        Reflect::set(&options, &"type".into(), &"language".into())?;
+        let names = Reflect::construct(&i18n::intl("DisplayNames")?, &Array::of2(&i18n::locales(), &options))?;
+        let of: Function = Reflect::get(&names, &"of".into())?.dyn_into()?;
+        of.call1(&names, &code.into())
+    };
+    name().ok().and_then(|name| name.as_string()).unwrap_or_else(|| code.to_string())
+}
+
+/// Sends `text` to the endpoint as `{ text, target }`; it answers with
+/// `{ text, detectedLanguage? }`.
+pub async fn translate(text: &str, target: &str) -> Result<Translation, String> {
+    let endpoint = endpoint().ok_or_else(|| t("translate-not-configured"))?;
+    let response = Request::post(&endpoint)
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(&TranslateRequest { text, target }).unwrap())
+        .send()
+        .await
+        .map_err(|e| t_with("translate-unreachable", &[("error", &e.to_string())]))?;
+    if !response.ok() {
+        return Err(t_with("translate-failed", &[("status", &response.status().to_string())]));
+    }
+    let reply: TranslateResponse = response
+        .json()
+        .await
+        .map_err(|e| t_with("translate-unexpected", &[("error", &e.to_string())]))?;
+    Ok(Translation {
+        text: reply.text,
+        source: reply.detected_language,
+    })
+}
@@ -0,0 +1,86 @@
+pub const MIN_LENGTH: usize = 2;
+pub const MAX_LENGTH: usize = 20;
+
+use crate::services::i18n::{t, t_with};
+
+/// Names that would be confused with the room itself or its staff.
+const RESERVED: &[&str] = &[
+    "admin", "administrator", "everyone", "moderator", "owner", "root", "server", "system",
+    "yewchat",
+];
+
+/// Checks a username as typed, explaining the first rule it breaks.
+pub fn validate(name: &str) -> Result<(), String> {
+    if name.trim() != name {
+        return Err(t("username-spaces"));
+    }
+    let length = name.chars().count();
+    if length < MIN_LENGTH {
+        return Err(t_with("username-too-short", &[("count", &MIN_LENGTH.to_string())]));
+    }
+    if length > MAX_LENGTH {
+        return Err(t_with("username-too-long", &[("count", &MAX_LENGTH.to_string())]));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_alphanumeric() || matches!(c, ' ' | '_' | '-' | '.')))
+    {
+        return Err(t_with("username-bad-character", &[("character", &c.to_string())]));
+    }
+    if RESERVED.contains(&normalize(name).to_lowercase().as_str()) {
+        return Err(t("username-reserved"));
+    }
+    Ok(())
+}
+
+/// The form a valid name is registered under: runs of spaces collapsed to one.
+pub fn normalize(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejects(name: &str, key: &str, args: &[(&str, &str)]) {
+        assert_eq!(validate(name), Err(t_with(key, args)), "validating {:?}", name);
+    }
+
+    #[test]
+    fn accepts_names_within_the_rules() {
+        for name in ["al", "alice", "Ana María", "bob_the-builder.2", "x".repeat(MAX_LENGTH).as_str()] {
+            assert_eq!(validate(name), Ok(()), "validating {:?}", name);
+        }
+    }
+
+    #[test]
+    fn checks_the_length_in_characters() {
+        rejects("", "username-too-short", &[("count", "2")]);
+        rejects("a", "username-too-short", &[("count", "2")]);
+        rejects(&"x".repeat(MAX_LENGTH + 1), "username-too-long", &[("count", "20")]);
+        // Counted in characters, not bytes.
+        assert_eq!(validate(&"é".repeat(MAX_LENGTH)), Ok(()));
+    }
+
+    #[test]
+    fn allows_only_letters_numbers_and_a_little_punctuation() {
+        rejects("alice!", "username-bad-character", &[("character", "!")]);
+        rejects("a@b", "username-bad-character", &[("character", "@")]);
+        rejects("tab\there", "username-bad-character", &[("character", "\t")]);
+        rejects(" alice", "username-spaces", &[]);
+        rejects("alice ", "username-spaces", &[]);
+    }
+
+    #[test]
+    fn refuses_reserved_names_in_any_case() {
+        for name in ["admin", "Admin", "SYSTEM", "YewChat"] {
+            rejects(name, "username-reserved", &[]);
+        }
+        assert_eq!(validate("admin2"), Ok(()));
+    }
+
+    #[test]
+    fn normalize_collapses_runs_of_spaces() {
+        assert_eq!(normalize("Ana   María"), "Ana María");
+    }
+}
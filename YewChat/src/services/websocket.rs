@@ -1,46 +1,151 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
 use futures::{channel::mpsc::Sender, SinkExt, StreamExt};
 use reqwasm::websocket::{futures::WebSocket, Message};
+use yew::Callback;
 use yew_agent::Dispatched;
+use crate::components::chat::{MsgTypes, WebSocketMessage};
 use crate::services::event_bus::{EventBus, Request};
+use crate::services::i18n::{t, t_with};
+#[cfg(not(test))]
+use crate::services::telemetry::{self, EventKind};
 
 use wasm_bindgen_futures::spawn_local;
 
+pub const SERVER_URL: &str = "ws://127.0.0.1:8080";
+
+/// How many chat frames are kept for crash reports.
+const RECENT_FRAMES_KEPT: usize = 20;
+
+thread_local! {
+    static RECENT_FRAMES: RefCell<VecDeque<String>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// Stands in for what [`redact`] leaves out.
+const REDACTED: &str = "[redacted]";
+
+/// `frame` as it is safe to log or paste into a bug report: the payloads of frames
+/// carrying passwords, codes, tokens or push keys are blanked out.
+fn redact(frame: &str) -> String {
+    let mut message: WebSocketMessage = match serde_json::from_str(frame) {
+        Ok(message) => message,
+        Err(_) => return "(unreadable frame)".to_string(),
+    };
+    match message.message_type {
+        // The name in `data` can stay; the tokens come after it.
+        MsgTypes::Register | MsgTypes::Reauth => {}
+        MsgTypes::Admin
+        | MsgTypes::Session
+        | MsgTypes::Outbox
+        | MsgTypes::Push
+        | MsgTypes::CreateAccount
+        | MsgTypes::Account
+        | MsgTypes::Login
+        | MsgTypes::Totp
+        | MsgTypes::VerifyTotp
+        | MsgTypes::TotpSetup
+        | MsgTypes::TotpSecret
+        | MsgTypes::TotpEnable => message.data = message.data.map(|_| REDACTED.to_string()),
+        _ => return frame.to_string(),
+    }
+    message.data_array = message
+        .data_array
+        .map(|items| items.iter().map(|_| REDACTED.to_string()).collect());
+    serde_json::to_string(&message).unwrap_or_default()
+}
+
+/// Remembers a chat frame, `direction` being `">"` for sent and `"<"` for received.
+fn record_frame(direction: &str, frame: &str) {
+    let frame = redact(frame);
+    RECENT_FRAMES.with(|frames| {
+        let mut frames = frames.borrow_mut();
+        if frames.len() == RECENT_FRAMES_KEPT {
+            frames.pop_front();
+        }
+        frames.push_back(format!("{} {}", direction, frame));
+    });
+}
+
+/// The last few frames sent and received on the chat socket, oldest first.
+pub fn recent_frames() -> Vec<String> {
+    // Also called from the panic hook, which may have interrupted `record_frame`.
+    RECENT_FRAMES.with(|frames| {
+        frames
+            .try_borrow()
+            .map(|frames| frames.iter().cloned().collect())
+            .unwrap_or_default()
+    })
+}
+
 pub struct WebsocketService {
     pub tx: Sender<String>,
 }
 
+/// Logs a failed chat socket and tells the screen that opened it, with
+/// `error` worded for the reader.
+#[cfg(not(test))]
+fn socket_failed(on_failure: &Callback<String>, error: String) {
+    log::error!("ws: {}", error);
+    telemetry::record(EventKind::ConnectionFailed);
+    on_failure.emit(t_with("server-unreachable", &[("error", &error)]));
+}
+
 impl WebsocketService {
-    pub fn new() -> Self {
-        let ws = WebSocket::open("ws://127.0.0.1:8080").unwrap();
+    #[cfg(test)]
+    pub fn new(_on_failure: Callback<String>) -> Self {
+        mock::connect()
+    }
+
+    /// Opens the chat socket. `on_failure` hears when it can't be opened or a
+    /// frame can't be sent; frames sent after that go nowhere.
+    #[cfg(not(test))]
+    pub fn new(on_failure: Callback<String>) -> Self {
+        let (in_tx, mut in_rx) = futures::channel::mpsc::channel::<String>(1000);
+        let ws = match WebSocket::open(SERVER_URL) {
+            Ok(ws) => ws,
+            Err(e) => {
+                socket_failed(&on_failure, e.to_string());
+                return Self { tx: in_tx };
+            }
+        };
 
         let (mut write, mut read) = ws.split();
 
-        let (in_tx, mut in_rx) = futures::channel::mpsc::channel::<String>(1000);
         let mut event_bus = EventBus::dispatcher();
 
         spawn_local(async move {
             while let Some(s) = in_rx.next().await {
-                log::debug!("got event from channel! {}", s);
-                write.send(Message::Text(s)).await.unwrap();
+                log::debug!("got event from channel! {}", redact(&s));
+                record_frame(">", &s);
+                if let Err(e) = write.send(Message::Text(s)).await {
+                    return socket_failed(&on_failure, e.to_string());
+                }
             }
+            // The service was dropped, e.g. by navigating away from the chat;
+            // hang up so the server frees our name for the next visit.
+            let _ = write.close().await;
         });
 
         spawn_local(async move {
             while let Some(msg) = read.next().await {
                 match msg {
                     Ok(Message::Text(data)) => {
-                        log::debug!("from websocket: {}", data);
+                        log::debug!("from websocket: {}", redact(&data));
+                        record_frame("<", &data);
                         event_bus.send(Request::EventBusMsg(data));
                     }
                     Ok(Message::Bytes(b)) => {
                         let decoded = std::str::from_utf8(&b);
                         if let Ok(val) = decoded {
-                            log::debug!("from websocket: {}", val);
+                            log::debug!("from websocket: {}", redact(val));
+                            record_frame("<", val);
                             event_bus.send(Request::EventBusMsg(val.into()));
                         }
                     }
                     Err(e) => {
                         log::error!("ws: {:?}", e);
+                        telemetry::record(EventKind::ConnectionFailed);
                     }
                 }
             }
@@ -49,4 +154,103 @@ impl WebsocketService {
 
         Self { tx: in_tx }
     }
-}
\ No newline at end of file
+}
+
+/// Sends one message on a short-lived connection and waits for the server's
+/// reply, for pages that need the server without joining the chat.
+pub async fn request(message_type: MsgTypes, data: String) -> Result<WebSocketMessage, String> {
+    let mut ws = WebSocket::open(SERVER_URL).map_err(|e| t_with("server-unreachable", &[("error", &e.to_string())]))?;
+    let message = WebSocketMessage {
+        message_type,
+        data: Some(data),
+        data_array: None,
+    };
+    ws.send(Message::Text(serde_json::to_string(&message).unwrap()))
+        .await
+        .map_err(|e| t_with("server-unreachable", &[("error", &e.to_string())]))?;
+    match ws.next().await {
+        Some(Ok(Message::Text(reply))) => {
+            serde_json::from_str(&reply).map_err(|e| t_with("server-unexpected-reply", &[("error", &e.to_string())]))
+        }
+        _ => Err(t("server-closed")),
+    }
+}
+
+/// Stands in for the chat server under test: frames the app sends are kept
+/// instead of going out, and tests hand the app frames as if the server had
+/// sent them.
+#[cfg(test)]
+pub mod mock {
+    use super::{
+        record_frame, spawn_local, Dispatched, EventBus, RefCell, Request, StreamExt, WebSocketMessage,
+        WebsocketService,
+    };
+
+    thread_local! {
+        static SENT: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub(super) fn connect() -> WebsocketService {
+        let (tx, mut rx) = futures::channel::mpsc::channel::<String>(1000);
+        spawn_local(async move {
+            while let Some(frame) = rx.next().await {
+                record_frame(">", &frame);
+                SENT.with(|sent| sent.borrow_mut().push(frame));
+            }
+        });
+        WebsocketService { tx }
+    }
+
+    /// Everything sent since the last call, oldest first.
+    pub fn take_sent() -> Vec<WebSocketMessage> {
+        SENT.with(|sent| sent.borrow_mut().drain(..).collect::<Vec<_>>())
+            .iter()
+            .map(|frame| serde_json::from_str(frame).expect("the app to send well-formed frames"))
+            .collect()
+    }
+
+    /// Delivers `message` the way the real socket delivers a server frame.
+    pub fn receive(message: &WebSocketMessage) {
+        let frame = serde_json::to_string(message).unwrap();
+        record_frame("<", &frame);
+        EventBus::dispatcher().send(Request::EventBusMsg(frame));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_ordinary_frames_as_they_are() {
+        let frame = r#"{"messageType":"message","dataArray":null,"data":"hello"}"#;
+        assert_eq!(redact(frame), frame);
+    }
+
+    #[test]
+    fn blanks_the_tokens_of_a_registration_but_keeps_the_name() {
+        let frame = r#"{"messageType":"register","dataArray":["data:image/png","captcha","account"],"data":"alice"}"#;
+        assert_eq!(
+            redact(frame),
+            r#"{"messageType":"register","dataArray":["[redacted]","[redacted]","[redacted]"],"data":"alice"}"#
+        );
+    }
+
+    #[test]
+    fn blanks_credentials_and_session_tokens() {
+        for frame in [
+            r#"{"messageType":"login","data":"{\"email\":\"a@b.c\",\"password\":\"hunter22\"}"}"#,
+            r#"{"messageType":"session","data":"{\"token\":\"abc\",\"expiresAt\":1}"}"#,
+            r#"{"messageType":"outbox","data":"{\"token\":\"abc\",\"messages\":[]}"}"#,
+        ] {
+            let redacted = redact(frame);
+            assert!(redacted.contains(REDACTED), "{}", redacted);
+            assert!(!redacted.contains("abc") && !redacted.contains("hunter22"), "{}", redacted);
+        }
+    }
+
+    #[test]
+    fn drops_frames_it_cannot_read() {
+        assert_eq!(redact("token=abc"), "(unreadable frame)");
+    }
+}
@@ -0,0 +1,92 @@
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{MountedChat, USERNAME};
+use crate::components::chat::MsgTypes;
+use crate::services::websocket::mock;
+
+const SIDEBAR: &str = "[aria-label='Online users']";
+
+fn message(from: &str, text: &str) -> Option<String> {
+    Some(serde_json::json!({ "from": from, "message": text }).to_string())
+}
+
+#[wasm_bindgen_test]
+async fn registers_when_created() {
+    let _chat = MountedChat::mount().await;
+    let sent = mock::take_sent();
+    let register = sent.first().expect("a frame to be sent on mount");
+    assert!(matches!(register.message_type, MsgTypes::Register));
+    assert_eq!(register.data.as_deref(), Some(USERNAME));
+    // No avatar was picked and no CAPTCHA solved, so there is nothing extra to send.
+    assert!(register.data_array.is_none());
+}
+
+#[wasm_bindgen_test]
+async fn lists_online_users_from_user_details() {
+    let chat = MountedChat::mount().await;
+    let users = serde_json::json!([
+        { "name": USERNAME },
+        { "name": "bob", "displayName": "Bobby", "status": "Lunch" },
+        { "name": "carol", "lastSeen": 1_600_000_000_000.0 },
+    ]);
+    chat.receive(MsgTypes::Users, Some(users.to_string()), None).await;
+
+    let sidebar = chat.find(SIDEBAR).unwrap().text_content().unwrap_or_default();
+    assert!(sidebar.contains("Bobby"), "sidebar was {:?}", sidebar);
+    assert!(sidebar.contains("Lunch"));
+    // Users with a last-seen time have left.
+    assert!(!sidebar.contains("carol"));
+}
+
+#[wasm_bindgen_test]
+async fn lists_online_users_from_bare_names() {
+    let chat = MountedChat::mount().await;
+    let names = vec![USERNAME.to_string(), "dave".to_string()];
+    chat.receive(MsgTypes::Users, None, Some(names)).await;
+
+    let sidebar = chat.find(SIDEBAR).unwrap().text_content().unwrap_or_default();
+    assert!(sidebar.contains("dave"), "sidebar was {:?}", sidebar);
+    assert!(!sidebar.contains("No users online"));
+}
+
+#[wasm_bindgen_test]
+async fn shows_text_messages_as_text() {
+    let chat = MountedChat::mount().await;
+    chat.receive(MsgTypes::Message, message("bob", "hello there"), None).await;
+
+    assert!(chat.text().contains("hello there"));
+    assert!(chat.find("img[src$='.gif']").is_none());
+}
+
+#[wasm_bindgen_test]
+async fn shows_gif_messages_as_images() {
+    let chat = MountedChat::mount().await;
+    let gif = "https://media.example.com/party.gif";
+    chat.receive(MsgTypes::Message, message("bob", gif), None).await;
+
+    let image = chat.find("img[src$='.gif']").expect("the GIF to be shown inline");
+    assert_eq!(image.get_attribute("src").as_deref(), Some(gif));
+}
+
+#[wasm_bindgen_test]
+async fn shows_script_addresses_ending_in_gif_as_text() {
+    let chat = MountedChat::mount().await;
+    let trap = "javascript:alert(1)//.gif";
+    chat.receive(MsgTypes::Message, message("bob", trap), None).await;
+
+    assert!(chat.text().contains(trap));
+    assert!(chat.find("img[src$='.gif'], a[href^='javascript:']").is_none());
+}
+
+#[wasm_bindgen_test]
+async fn toggles_the_sidebar() {
+    let chat = MountedChat::mount().await;
+    let hidden = || chat.find(SIDEBAR).unwrap().class_list().contains("hidden");
+    assert!(!hidden());
+
+    chat.click("[aria-label='Toggle user list']").await;
+    assert!(hidden());
+
+    chat.click("[aria-label='Toggle user list']").await;
+    assert!(!hidden());
+}
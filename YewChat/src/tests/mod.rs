@@ -0,0 +1,107 @@
+//! Browser tests for the chat screen, run with `wasm-pack test --headless --firefox`
+//! (or `--chrome`). The chat talks to [`mock`] instead of a server, so they need
+//! nothing else running.
+//!
+//! [`mock`]: crate::services::websocket::mock
+
+mod chat;
+
+use gloo::storage::{LocalStorage, Storage};
+use gloo_timers::future::TimeoutFuture;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+use yew::functional::*;
+use yew::prelude::*;
+use yew::AppHandle;
+use yew_router::prelude::*;
+
+use crate::components::chat::{Chat, MsgTypes, WebSocketMessage};
+use crate::services::chat_store::{ChatStore, ChatStoreState};
+use crate::services::websocket::mock;
+use crate::{User, UserState};
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+pub const USERNAME: &str = "alice";
+
+/// The providers `Main` puts around the routes, with [`USERNAME`] signed in.
+#[function_component(ChatHarness)]
+fn chat_harness() -> Html {
+    let user = use_reducer(|| UserState {
+        username: USERNAME.to_string(),
+        ..Default::default()
+    });
+    let store = use_reducer_eq(ChatStoreState::default);
+    html! {
+        <ContextProvider<User> context={user}>
+        <ContextProvider<ChatStore> context={store}>
+        <BrowserRouter>
+            <Chat/>
+        </BrowserRouter>
+        </ContextProvider<ChatStore>>
+        </ContextProvider<User>>
+    }
+}
+
+/// A chat screen mounted in its own element; unmounting it hangs up.
+pub struct MountedChat {
+    root: Element,
+    app: Option<AppHandle<ChatHarness>>,
+}
+
+impl MountedChat {
+    /// Mounts a fresh chat with no saved preferences, and lets it connect.
+    pub async fn mount() -> Self {
+        LocalStorage::clear();
+        mock::take_sent();
+        let document = gloo::utils::document();
+        let root = document.create_element("div").unwrap();
+        gloo::utils::body().append_child(&root).unwrap();
+        let app = yew::start_app_in_element::<ChatHarness>(root.clone());
+        let chat = Self { root, app: Some(app) };
+        settle().await;
+        chat
+    }
+
+    /// Delivers a server frame and waits for the chat to render it.
+    pub async fn receive(&self, message_type: MsgTypes, data: Option<String>, data_array: Option<Vec<String>>) {
+        mock::receive(&WebSocketMessage {
+            message_type,
+            data,
+            data_array,
+        });
+        settle().await;
+    }
+
+    pub fn find(&self, selector: &str) -> Option<Element> {
+        self.root.query_selector(selector).unwrap()
+    }
+
+    pub fn text(&self) -> String {
+        self.root.text_content().unwrap_or_default()
+    }
+
+    pub async fn click(&self, selector: &str) {
+        self.find(selector)
+            .unwrap_or_else(|| panic!("nothing matches {}", selector))
+            .unchecked_into::<HtmlElement>()
+            .click();
+        settle().await;
+    }
+}
+
+impl Drop for MountedChat {
+    fn drop(&mut self) {
+        if let Some(app) = self.app.take() {
+            app.destroy();
+        }
+        self.root.remove();
+    }
+}
+
+/// Gives the event bus, the mock socket and Yew's scheduler a turn.
+pub async fn settle() {
+    for _ in 0..3 {
+        TimeoutFuture::new(0).await;
+    }
+}
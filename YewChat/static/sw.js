@@ -0,0 +1,197 @@
+// Bump to throw away what older versions cached.
+const CACHE = 'yewchat-shell-v1';
+
+// Enough for the page to load without a network. Chunks with generated names
+// are cached as they are first fetched.
+const SHELL = [
+    '/',
+    '/index.html',
+    '/yewchat.js',
+    '/yewchat_bg.wasm',
+    '/manifest.webmanifest',
+    '/icons/icon-192.png',
+    '/icons/icon-512.png',
+    '/sounds/message.wav',
+    '/sounds/mention.wav',
+    '/sounds/direct.wav',
+];
+
+// Messages written offline, kept here by the page (src/services/outbox.rs)
+// along with the server to send them to and a session vouching for the author.
+const OUTBOX = 'yewchat-outbox';
+
+// Other origins whose responses are worth keeping: the page is unstyled without Tailwind.
+const CACHED_ORIGINS = ['https://cdn.tailwindcss.com'];
+
+// Images from other origins: DiceBear and Gravatar avatars, GIFs, map
+// thumbnails. Shown from here straight away and refreshed behind the scenes,
+// so history scrolled back through offline still has its pictures.
+const MEDIA = 'yewchat-media';
+
+// Images kept, most recently shown last. Cross-origin responses are opaque,
+// and browsers count each against the quota as if it were several megabytes.
+const MEDIA_KEPT = 150;
+
+self.addEventListener('install', (event) => {
+    event.waitUntil(
+        caches.open(CACHE).then((cache) =>
+            // One missing file shouldn't stop the worker from installing.
+            Promise.all(SHELL.map((path) => cache.add(path).catch(() => undefined)))
+        )
+    );
+    self.skipWaiting();
+});
+
+// Take over open tabs straight away, so they work offline and a notification
+// click can move them.
+self.addEventListener('activate', (event) => {
+    event.waitUntil(
+        caches
+            .keys()
+            .then((keys) => Promise.all(keys.filter((key) => ![CACHE, OUTBOX, MEDIA].includes(key)).map((key) => caches.delete(key))))
+            .then(() => self.clients.claim())
+    );
+});
+
+// The network first, so a deploy shows up on the next load; what it last
+// returned when there is none. Pages fall back to the shell, which routes itself.
+self.addEventListener('fetch', (event) => {
+    const request = event.request;
+    const url = new URL(request.url);
+    if (request.method !== 'GET') {
+        return;
+    }
+    if (request.destination === 'image' && url.origin !== self.location.origin) {
+        event.respondWith(staleWhileRevalidate(event));
+        return;
+    }
+    if (url.origin !== self.location.origin && !CACHED_ORIGINS.includes(url.origin)) {
+        return;
+    }
+    event.respondWith(
+        fetch(request)
+            .then((response) => {
+                if (response.ok || response.type === 'opaque') {
+                    const copy = response.clone();
+                    caches.open(CACHE).then((cache) => cache.put(request, copy));
+                }
+                return response;
+            })
+            .catch(() =>
+                caches
+                    .match(request)
+                    .then((cached) => cached || (request.mode === 'navigate' ? caches.match('/index.html') : undefined))
+                    .then((cached) => cached || Response.error())
+            )
+    );
+});
+
+// The cached image if there is one, while the network brings a fresh copy for
+// next time; the network's answer when nothing is cached yet.
+const staleWhileRevalidate = async (event) => {
+    const cache = await caches.open(MEDIA);
+    const cached = await cache.match(event.request);
+    const refreshed = fetch(event.request).then(async (response) => {
+        if (response.ok || response.type === 'opaque') {
+            // Storing it again moves it to the end, away from trimming.
+            await cache.put(event.request, response.clone());
+            await trimMedia(cache);
+        }
+        return response;
+    });
+    if (cached) {
+        // Offline the refresh fails, which leaves the cached copy as it is.
+        event.waitUntil(refreshed.catch(() => undefined));
+        return cached;
+    }
+    return refreshed.catch(() => Response.error());
+};
+
+// Forgets the images shown longest ago, beyond MEDIA_KEPT.
+const trimMedia = async (cache) => {
+    const keys = await cache.keys();
+    await Promise.all(keys.slice(0, Math.max(0, keys.length - MEDIA_KEPT)).map((key) => cache.delete(key)));
+};
+
+// Shows the mentions and direct messages the chat server pushes while no
+// YewChat tab is open. Payloads look like {id, from, message, direct, url}.
+self.addEventListener('push', (event) => {
+    let data = {};
+    try {
+        data = event.data ? event.data.json() : {};
+    } catch (e) {
+        data = { message: event.data.text() };
+    }
+    const title = data.direct ? `${data.from} (direct message)` : data.from || 'YewChat';
+    event.waitUntil(
+        self.registration.showNotification(title, {
+            body: data.message || '',
+            // One notification per message, even if the same one is pushed twice.
+            tag: data.id ? `message-${data.id}` : undefined,
+            data: { url: data.url || '/chat' },
+        })
+    );
+});
+
+// Brings an open chat tab forward, or opens one, at the message.
+self.addEventListener('notificationclick', (event) => {
+    event.notification.close();
+    const url = new URL(event.notification.data.url, self.location.origin).href;
+    event.waitUntil(
+        self.clients.matchAll({ type: 'window', includeUncontrolled: true }).then((windows) => {
+            const open = windows.find((client) => new URL(client.url).origin === self.location.origin);
+            if (open) {
+                return open
+                    .focus()
+                    .then(() => open.navigate(url))
+                    .catch(() => self.clients.openWindow(url));
+            }
+            return self.clients.openWindow(url);
+        })
+    );
+});
+
+// Background Sync: the network is back, so send what was written offline even
+// if no tab is open. Failing leaves the sync for the browser to retry.
+self.addEventListener('sync', (event) => {
+    if (event.tag === 'outbox') {
+        event.waitUntil(sendOutbox());
+    }
+});
+
+const readOutbox = (cache) => cache.match('/outbox').then((stored) => (stored ? stored.json() : null));
+
+const sendOutbox = async () => {
+    const cache = await caches.open(OUTBOX);
+    const outbox = await readOutbox(cache);
+    if (!outbox || !outbox.token || outbox.messages.length === 0) {
+        return;
+    }
+    const delivered = await new Promise((resolve, reject) => {
+        const socket = new WebSocket(outbox.server);
+        socket.onopen = () =>
+            socket.send(
+                JSON.stringify({
+                    messageType: 'outbox',
+                    data: JSON.stringify({ token: outbox.token, messages: outbox.messages }),
+                })
+            );
+        // Everything broadcast to the room arrives here too; only the answer matters.
+        socket.onmessage = (event) => {
+            const frame = JSON.parse(event.data);
+            if (frame.messageType === 'outbox') {
+                resolve(JSON.parse(frame.data));
+                socket.close();
+            } else if (frame.messageType === 'error') {
+                // The session ran out: the page sends them after the next sign-in.
+                resolve([]);
+                socket.close();
+            }
+        };
+        socket.onerror = () => reject(new Error('the chat server is unreachable'));
+    });
+    // More may have been written while this was out.
+    const latest = (await readOutbox(cache)) || outbox;
+    latest.messages = latest.messages.filter((message) => !delivered.includes(message.id));
+    await cache.put('/outbox', new Response(JSON.stringify(latest)));
+};
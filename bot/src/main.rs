@@ -0,0 +1,97 @@
+//! Simulates a crowd of chat users against a YewChat server, to see how the
+//! server holds up and how the web client renders a busy room.
+
+mod stats;
+mod user;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::Parser;
+use tokio::time::{self, Instant};
+
+use crate::stats::Stats;
+
+/// Simulates many chat users against a YewChat server, for load testing.
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Args {
+    /// Chat server to connect to.
+    #[arg(long, default_value = "ws://127.0.0.1:8080")]
+    pub url: String,
+    /// How many users to simulate.
+    #[arg(short, long, default_value_t = 10)]
+    pub users: usize,
+    /// Messages each user sends per minute; 0 only listens.
+    #[arg(short, long, default_value_t = 6.0)]
+    pub rate: f64,
+    /// Stop after this many seconds instead of waiting for Ctrl-C.
+    #[arg(short, long)]
+    pub duration: Option<u64>,
+    /// Users are named `<prefix>-<n>`.
+    #[arg(long, default_value = "bot")]
+    pub prefix: String,
+    /// What the users say; each message gets ` #<n>` appended.
+    #[arg(long, default_value = "Load test message")]
+    pub text: String,
+    /// Milliseconds between connecting one user and the next.
+    #[arg(long, default_value_t = 50)]
+    pub ramp_up: u64,
+    /// Seconds between progress reports.
+    #[arg(long, default_value_t = 5)]
+    pub report_every: u64,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Arc::new(Args::parse());
+    let stats = Arc::new(Stats::default());
+    println!(
+        "Connecting {} users to {} at {} messages per minute each",
+        args.users, args.url, args.rate
+    );
+
+    let started = Instant::now();
+    let users: Vec<_> = (0..args.users)
+        .map(|index| {
+            let (args, stats) = (args.clone(), stats.clone());
+            tokio::spawn(async move {
+                time::sleep(Duration::from_millis(args.ramp_up * index as u64)).await;
+                user::simulate(index, args, stats).await;
+            })
+        })
+        .collect();
+
+    let report_every = Duration::from_secs(args.report_every.max(1));
+    let mut reports = time::interval_at(started + report_every, report_every);
+    let mut last = stats.snapshot();
+    let deadline = async {
+        match args.duration {
+            Some(seconds) => time::sleep(Duration::from_secs(seconds)).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            _ = reports.tick() => {
+                let now = stats.snapshot();
+                println!("{}", now.report_since(&last, report_every, args.users));
+                last = now;
+            }
+            _ = &mut deadline => break,
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    // Dropping the sockets hangs up, so the server frees the names.
+    for user in &users {
+        user.abort();
+    }
+    let total = stats.snapshot();
+    println!(
+        "Done after {:.0}s: {}",
+        started.elapsed().as_secs_f64(),
+        total.report_since(&Default::default(), started.elapsed(), args.users)
+    );
+}
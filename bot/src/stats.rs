@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Counters shared by every simulated user, reported periodically.
+#[derive(Default)]
+pub struct Stats {
+    pub connected: AtomicUsize,
+    pub failed: AtomicUsize,
+    pub sent: AtomicU64,
+    pub received: AtomicU64,
+    /// `error` frames from the server, e.g. slow mode or a taken name.
+    pub errors: AtomicU64,
+    round_trips: AtomicU64,
+    round_trip_micros: AtomicU64,
+    slowest_round_trip_micros: AtomicU64,
+}
+
+/// What the counters read at one moment.
+#[derive(Clone, Copy, Default)]
+pub struct Snapshot {
+    pub connected: usize,
+    pub failed: usize,
+    pub sent: u64,
+    pub received: u64,
+    pub errors: u64,
+    pub round_trips: u64,
+    pub round_trip_micros: u64,
+    pub slowest_round_trip_micros: u64,
+}
+
+impl Stats {
+    /// Records how long one of our own messages took to come back from the server.
+    pub fn round_trip(&self, elapsed: Duration) {
+        let micros = elapsed.as_micros() as u64;
+        self.round_trips.fetch_add(1, Ordering::Relaxed);
+        self.round_trip_micros.fetch_add(micros, Ordering::Relaxed);
+        self.slowest_round_trip_micros.fetch_max(micros, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            connected: self.connected.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            sent: self.sent.load(Ordering::Relaxed),
+            received: self.received.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            round_trips: self.round_trips.load(Ordering::Relaxed),
+            round_trip_micros: self.round_trip_micros.load(Ordering::Relaxed),
+            slowest_round_trip_micros: self.slowest_round_trip_micros.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Snapshot {
+    /// One report line for what happened between `earlier` and now, `period` apart.
+    pub fn report_since(&self, earlier: &Snapshot, period: Duration, users: usize) -> String {
+        let seconds = period.as_secs_f64().max(f64::EPSILON);
+        let round_trips = self.round_trips - earlier.round_trips;
+        let average = (self.round_trip_micros - earlier.round_trip_micros)
+            .checked_div(round_trips)
+            .map_or_else(|| "-".to_string(), |micros| format!("{:.1}ms", micros as f64 / 1000.0));
+        format!(
+            "users {}/{} ({} failed) | sent {:.1}/s | received {:.1}/s | round trip avg {} max {:.1}ms | errors {}",
+            self.connected,
+            users,
+            self.failed,
+            (self.sent - earlier.sent) as f64 / seconds,
+            (self.received - earlier.received) as f64 / seconds,
+            average,
+            self.slowest_round_trip_micros as f64 / 1000.0,
+            self.errors,
+        )
+    }
+}
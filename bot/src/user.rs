@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{future, SinkExt, StreamExt};
+use tokio::time::{self, Instant, Interval};
+use tokio_tungstenite::tungstenite::Message;
+use yewchat_protocol::{ChatMessage, MsgTypes, WebSocketMessage};
+
+use crate::stats::Stats;
+use crate::Args;
+
+/// Messages that haven't come back after this long are written off.
+const ROUND_TRIP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// One simulated user: joins as `<prefix>-<index>`, talks at the configured
+/// rate and times how long its own messages take to come back.
+pub async fn simulate(index: usize, args: Arc<Args>, stats: Arc<Stats>) {
+    let name = format!("{}-{}", args.prefix, index);
+    let socket = match tokio_tungstenite::connect_async(args.url.as_str()).await {
+        Ok((socket, _)) => socket,
+        Err(e) => {
+            eprintln!("{}: failed to connect: {}", name, e);
+            stats.failed.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+    };
+    let (mut write, mut read) = socket.split();
+    if let Err(e) = write.send(Message::Text(WebSocketMessage::register(&name).to_json())).await {
+        eprintln!("{}: failed to register: {}", name, e);
+        stats.failed.fetch_add(1, Ordering::Relaxed);
+        return;
+    }
+    stats.connected.fetch_add(1, Ordering::Relaxed);
+
+    let mut ticker = first_message_delay(index, &args).map(|(start, period)| time::interval_at(start, period));
+    let mut sequence = 0u64;
+    let mut in_flight: HashMap<u64, Instant> = HashMap::new();
+    loop {
+        tokio::select! {
+            _ = tick(&mut ticker) => {
+                sequence += 1;
+                let now = Instant::now();
+                in_flight.retain(|_, sent_at| now.duration_since(*sent_at) < ROUND_TRIP_TIMEOUT);
+                in_flight.insert(sequence, now);
+                let text = format!("{} #{}", args.text, sequence);
+                if let Err(e) = write.send(Message::Text(WebSocketMessage::chat(text).to_json())).await {
+                    eprintln!("{}: failed to send: {}", name, e);
+                    break;
+                }
+                stats.sent.fetch_add(1, Ordering::Relaxed);
+            }
+            frame = read.next() => match frame {
+                Some(Ok(Message::Text(frame))) => handle_frame(&name, &frame, &mut in_flight, &stats),
+                Some(Ok(Message::Close(_))) | None => {
+                    eprintln!("{}: the server hung up", name);
+                    break;
+                }
+                // Pings are answered by tungstenite itself.
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    eprintln!("{}: {}", name, e);
+                    break;
+                }
+            },
+        }
+    }
+    stats.connected.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// When this user first speaks and how often after that, spread out so that
+/// all users don't talk in the same instant. `None` when the rate is zero.
+fn first_message_delay(index: usize, args: &Args) -> Option<(Instant, Duration)> {
+    if args.rate <= 0.0 {
+        return None;
+    }
+    let period = Duration::from_secs_f64(60.0 / args.rate);
+    let offset = period.mul_f64(index as f64 / args.users.max(1) as f64);
+    Some((Instant::now() + offset, period))
+}
+
+async fn tick(ticker: &mut Option<Interval>) {
+    match ticker {
+        Some(ticker) => {
+            ticker.tick().await;
+        }
+        None => future::pending().await,
+    }
+}
+
+fn handle_frame(name: &str, frame: &str, in_flight: &mut HashMap<u64, Instant>, stats: &Stats) {
+    let message = match WebSocketMessage::from_json(frame) {
+        Ok(message) => message,
+        // Frame types this version doesn't know about aren't ours to count.
+        Err(_) => return,
+    };
+    match message.message_type {
+        MsgTypes::Message => {
+            stats.received.fetch_add(1, Ordering::Relaxed);
+            let chat = match message.data.as_deref().map(serde_json::from_str::<ChatMessage>) {
+                Some(Ok(chat)) => chat,
+                _ => return,
+            };
+            if chat.from != name {
+                return;
+            }
+            let sent_at = chat
+                .message
+                .rsplit_once('#')
+                .and_then(|(_, sequence)| sequence.parse().ok())
+                .and_then(|sequence| in_flight.remove(&sequence));
+            if let Some(sent_at) = sent_at {
+                stats.round_trip(sent_at.elapsed());
+            }
+        }
+        MsgTypes::Error => {
+            stats.errors.fetch_add(1, Ordering::Relaxed);
+            eprintln!("{}: server error: {}", name, message.data.unwrap_or_default());
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,233 @@
+//! The frames spoken between YewChat clients and the chat server, shared by
+//! the web client and the native tools next to it.
+
+use serde::{Deserialize, Serialize};
+
+/// What a frame is about; its payload depends on the type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MsgTypes {
+    Users,
+    Register,
+    Message,
+    Poll,
+    Vote,
+    ClosePoll,
+    Ephemeral,
+    Location,
+    Direct,
+    Profile,
+    Status,
+    Avatar,
+    DisplayName,
+    System,
+    Pin,
+    Delete,
+    Role,
+    Kick,
+    Ban,
+    Disconnect,
+    Report,
+    SlowMode,
+    BlockedWords,
+    AuditEvent,
+    Admin,
+    Unban,
+    Stats,
+    Error,
+    Session,
+    Reauth,
+    History,
+    Whois,
+    UserInfo,
+    CreateAccount,
+    Account,
+    Login,
+    Totp,
+    VerifyTotp,
+    TotpSetup,
+    TotpSecret,
+    TotpEnable,
+    TotpEnabled,
+    Push,
+    Outbox,
+}
+
+/// One frame on the chat socket. Most types carry their payload in `data`,
+/// JSON-encoded when it isn't plain text.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebSocketMessage {
+    pub message_type: MsgTypes,
+    pub data_array: Option<Vec<String>>,
+    pub data: Option<String>,
+}
+
+impl WebSocketMessage {
+    pub fn new(message_type: MsgTypes, data: impl Into<String>) -> Self {
+        Self {
+            message_type,
+            data_array: None,
+            data: Some(data.into()),
+        }
+    }
+
+    /// Joins the room as `username`.
+    pub fn register(username: impl Into<String>) -> Self {
+        Self::new(MsgTypes::Register, username)
+    }
+
+    /// A chat message to everyone in the room.
+    pub fn chat(text: impl Into<String>) -> Self {
+        Self::new(MsgTypes::Message, text)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("frames to serialize")
+    }
+
+    pub fn from_json(frame: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(frame)
+    }
+}
+
+/// The part of a `Message` frame's `data` every client needs.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatMessage {
+    /// Assigned by the server.
+    #[serde(default)]
+    pub id: Option<u64>,
+    pub from: String,
+    pub message: String,
+    /// Set when the message was sent privately to a single user.
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
+/// Someone in the room, as listed in the `data` of a `Users` frame.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSummary {
+    pub name: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Present only for users who have left, in milliseconds since the epoch.
+    #[serde(default)]
+    pub last_seen: Option<f64>,
+}
+
+impl UserSummary {
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_deref().filter(|name| !name.is_empty()).unwrap_or(&self.name)
+    }
+
+    /// Everyone online according to a `Users` frame. Older servers only send
+    /// the bare usernames in `data_array`.
+    pub fn online(message: &WebSocketMessage) -> Vec<UserSummary> {
+        let users: Vec<UserSummary> = match &message.data {
+            Some(data) => serde_json::from_str(data).unwrap_or_default(),
+            None => message
+                .data_array
+                .iter()
+                .flatten()
+                .map(|name| UserSummary {
+                    name: name.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+        };
+        users.into_iter().filter(|u| u.last_seen.is_none()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    /// A `Users` frame as the server's `broadcastUsers` sends it.
+    fn users_frame() -> WebSocketMessage {
+        let data = json!([
+            { "name": "alice", "bio": "", "status": "Writing", "avatar": null, "displayName": "Alice", "role": "owner", "joinedAt": 1 },
+            { "name": "bob", "displayName": "", "role": "member", "joinedAt": 2 },
+            { "name": "carol", "lastSeen": 1700000000000.0 },
+        ]);
+        WebSocketMessage::from_json(
+            &json!({ "messageType": "users", "dataArray": ["alice", "bob"], "data": data.to_string() }).to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn online_lists_connected_users_from_the_server() {
+        let online = UserSummary::online(&users_frame());
+        assert_eq!(online.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), ["alice", "bob"]);
+        assert_eq!(online[0].display_name(), "Alice");
+        assert_eq!(online[0].status.as_deref(), Some("Writing"));
+        assert_eq!(online[1].display_name(), "bob");
+    }
+
+    #[test]
+    fn online_falls_back_to_bare_names_from_older_servers() {
+        let frame = WebSocketMessage::from_json(r#"{"messageType":"users","dataArray":["alice","bob"],"data":null}"#).unwrap();
+        let online = UserSummary::online(&frame);
+        assert_eq!(online.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), ["alice", "bob"]);
+        assert!(online.iter().all(|u| u.display_name.is_none()));
+    }
+
+    #[test]
+    fn online_is_empty_for_an_unreadable_list() {
+        let frame = WebSocketMessage::new(MsgTypes::Users, "not json");
+        assert!(UserSummary::online(&frame).is_empty());
+    }
+
+    #[test]
+    fn frames_serialize_the_way_the_server_reads_them() {
+        let frame: Value = serde_json::from_str(&WebSocketMessage::chat("hi").to_json()).unwrap();
+        assert_eq!(frame, json!({ "messageType": "message", "dataArray": null, "data": "hi" }));
+        let frame: Value = serde_json::from_str(&WebSocketMessage::register("alice").to_json()).unwrap();
+        assert_eq!(frame["messageType"], "register");
+        assert_eq!(serde_json::to_value(MsgTypes::TotpSecret).unwrap(), "totpsecret");
+    }
+
+    #[test]
+    fn frames_survive_a_round_trip() {
+        let sent = WebSocketMessage {
+            message_type: MsgTypes::Register,
+            data_array: Some(vec!["avatar".to_string(), String::new(), "token".to_string()]),
+            data: Some("alice".to_string()),
+        };
+        let received = WebSocketMessage::from_json(&sent.to_json()).unwrap();
+        assert_eq!(received.message_type, MsgTypes::Register);
+        assert_eq!(received.data_array, sent.data_array);
+        assert_eq!(received.data, sent.data);
+    }
+
+    #[test]
+    fn chat_messages_parse_from_the_server_payload() {
+        // As `sendChatMessage` stamps them; `expiresAt` is left out when unset.
+        let frame = WebSocketMessage::from_json(
+            &json!({
+                "messageType": "message",
+                "data": json!({ "id": 7, "from": "bob", "message": "hello", "time": 1700000000000u64, "to": "alice" }).to_string(),
+            })
+            .to_string(),
+        )
+        .unwrap();
+        assert_eq!(frame.message_type, MsgTypes::Message);
+        let message: ChatMessage = serde_json::from_str(frame.data.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            message,
+            ChatMessage {
+                id: Some(7),
+                from: "bob".to_string(),
+                message: "hello".to_string(),
+                to: Some("alice".to_string()),
+            }
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use yewchat_protocol::{ChatMessage, MsgTypes, UserSummary, WebSocketMessage};
+
+/// A line in the message pane.
+pub enum Entry {
+    Chat(ChatMessage),
+    /// Joins, leaves and anything else the room or this client has to say.
+    Notice(String),
+}
+
+#[derive(Serialize)]
+struct DirectMessage<'a> {
+    to: &'a str,
+    message: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Disconnect {
+    reason: String,
+    by: Option<String>,
+}
+
+/// What the user asked for with their last key press.
+pub enum Action {
+    None,
+    Send(WebSocketMessage),
+    Quit,
+}
+
+/// Everything on screen.
+pub struct App {
+    pub username: String,
+    pub server: String,
+    pub entries: Vec<Entry>,
+    pub users: Vec<UserSummary>,
+    pub input: String,
+    /// Whether the server has accepted us into the room.
+    pub connected: bool,
+}
+
+impl App {
+    pub fn new(username: String, server: String) -> Self {
+        Self {
+            username,
+            server,
+            entries: vec![Entry::Notice(
+                "Connecting… Type a message and press Enter. /msg <name> <text> whispers, /quit leaves.".to_string(),
+            )],
+            users: vec![],
+            input: String::new(),
+            connected: false,
+        }
+    }
+
+    pub fn notice(&mut self, text: impl Into<String>) {
+        self.entries.push(Entry::Notice(text.into()));
+    }
+
+    pub fn display_name_of<'a>(&'a self, name: &'a str) -> &'a str {
+        self.users
+            .iter()
+            .find(|u| u.name == name)
+            .map_or(name, UserSummary::display_name)
+    }
+
+    pub fn handle_frame(&mut self, frame: &str) {
+        let message = match WebSocketMessage::from_json(frame) {
+            Ok(message) => message,
+            // Frames from a newer server than this client knows about.
+            Err(_) => return,
+        };
+        let data = message.data.clone().unwrap_or_default();
+        match message.message_type {
+            MsgTypes::Users => {
+                let users = UserSummary::online(&message);
+                if users.iter().any(|u| u.name == self.username) && !self.connected {
+                    self.connected = true;
+                    self.notice(format!("Joined as {}.", self.username));
+                }
+                self.users = users;
+            }
+            MsgTypes::Message => match serde_json::from_str(&data) {
+                Ok(chat) => self.entries.push(Entry::Chat(chat)),
+                Err(e) => self.notice(format!("Couldn't read a message from the server: {}", e)),
+            },
+            MsgTypes::System => self.notice(data),
+            MsgTypes::Error => self.notice(match data.as_str() {
+                "name_taken" => format!("Someone is already using the name {}. Restart with another one.", self.username),
+                "captcha_failed" => "This server wants a CAPTCHA; sign in from the web client instead.".to_string(),
+                "auth_expired" => "Your session expired. Restart to join again.".to_string(),
+                other => format!("The server said: {}", other),
+            }),
+            MsgTypes::Disconnect => {
+                self.connected = false;
+                match serde_json::from_str::<Disconnect>(&data) {
+                    Ok(Disconnect { reason, by: Some(by) }) => {
+                        self.notice(format!("You were removed from the room ({} by {}).", reason, by))
+                    }
+                    _ => self.notice("You were removed from the room."),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::None;
+        }
+        match key.code {
+            KeyCode::Esc => Action::Quit,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                Action::None
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                Action::None
+            }
+            KeyCode::Enter => self.submit(),
+            _ => Action::None,
+        }
+    }
+
+    fn submit(&mut self) -> Action {
+        let input = std::mem::take(&mut self.input);
+        let text = input.trim();
+        if text.is_empty() {
+            return Action::None;
+        }
+        if text == "/quit" {
+            return Action::Quit;
+        }
+        if let Some(rest) = text.strip_prefix("/msg ") {
+            return match rest.trim().split_once(' ') {
+                Some((to, message)) if !message.trim().is_empty() => {
+                    let direct = DirectMessage {
+                        to,
+                        message: message.trim(),
+                    };
+                    Action::Send(WebSocketMessage::new(
+                        MsgTypes::Direct,
+                        serde_json::to_string(&direct).unwrap(),
+                    ))
+                }
+                _ => {
+                    self.notice("Usage: /msg <name> <text>");
+                    Action::None
+                }
+            };
+        }
+        Action::Send(WebSocketMessage::chat(text))
+    }
+}
@@ -0,0 +1,115 @@
+//! A terminal client for YewChat, for quick testing and for people who would
+//! rather not leave the terminal.
+
+mod app;
+mod ui;
+
+use std::io::{self, Stdout};
+
+use clap::Parser;
+use crossterm::event::{Event, EventStream};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use futures_util::{SinkExt, StreamExt};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use yewchat_protocol::WebSocketMessage;
+
+use crate::app::{Action, App};
+
+/// Chat in a YewChat room from the terminal.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// Name to join the room with.
+    username: String,
+    /// Chat server to connect to.
+    #[arg(long, default_value = "ws://127.0.0.1:8080")]
+    url: String,
+}
+
+type Tui = Terminal<CrosstermBackend<Stdout>>;
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    let args = Args::parse();
+    let (socket, _) = tokio_tungstenite::connect_async(args.url.as_str())
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::ConnectionRefused, format!("couldn't reach {}: {}", args.url, e)))?;
+
+    let mut terminal = enter()?;
+    // Put the terminal back before a panic message is printed, or it's unreadable.
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = leave();
+        default_hook(info);
+    }));
+
+    let result = run(&mut terminal, socket, App::new(args.username, args.url)).await;
+    leave()?;
+    result
+}
+
+fn enter() -> io::Result<Tui> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    Terminal::new(CrosstermBackend::new(io::stdout()))
+}
+
+fn leave() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)
+}
+
+async fn run(terminal: &mut Tui, socket: Socket, mut app: App) -> io::Result<()> {
+    let (mut write, mut read) = socket.split();
+    let mut keys = EventStream::new();
+    let mut open = true;
+    if let Err(e) = write.send(Message::Text(WebSocketMessage::register(&app.username).to_json())).await {
+        app.notice(format!("Couldn't join: {}", e));
+        open = false;
+    }
+
+    loop {
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+        tokio::select! {
+            event = keys.next() => match event {
+                Some(Ok(Event::Key(key))) => match app.handle_key(key) {
+                    Action::None => {}
+                    Action::Quit => break,
+                    Action::Send(_) if !open => app.notice("Not connected; restart to join again."),
+                    Action::Send(message) => {
+                        if let Err(e) = write.send(Message::Text(message.to_json())).await {
+                            app.notice(format!("Couldn't send: {}", e));
+                        }
+                    }
+                },
+                // Resizes only need the redraw at the top of the loop.
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+                None => break,
+            },
+            frame = read.next(), if open => match frame {
+                Some(Ok(Message::Text(frame))) => app.handle_frame(&frame),
+                Some(Ok(Message::Close(_))) | None => {
+                    open = false;
+                    app.connected = false;
+                    app.notice("The server closed the connection. Press Esc to quit.");
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    open = false;
+                    app.connected = false;
+                    app.notice(format!("Lost the connection: {}. Press Esc to quit.", e));
+                }
+            },
+        }
+    }
+    // Hang up so the server frees the name straight away.
+    let _ = write.close().await;
+    Ok(())
+}
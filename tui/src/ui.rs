@@ -0,0 +1,79 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
+
+use crate::app::{App, Entry};
+
+const SIDEBAR_WIDTH: u16 = 24;
+
+/// Messages on the left, who's online on the right, the composer underneath.
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [main, input] = Layout::vertical([Constraint::Min(3), Constraint::Length(3)]).areas(frame.size());
+    let [messages, users] =
+        Layout::horizontal([Constraint::Min(20), Constraint::Length(SIDEBAR_WIDTH)]).areas(main);
+
+    let presence = if app.connected { "online" } else { "offline" };
+    let title = format!(" YewChat · {} · {} ({}) ", app.server, app.username, presence);
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(messages);
+    let lines = last_lines_that_fit(app, inner.width, inner.height);
+    frame.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: false }), messages);
+
+    let items: Vec<ListItem> = app
+        .users
+        .iter()
+        .map(|u| {
+            let mut line = vec![Span::styled("● ", Style::default().fg(Color::Green)), Span::raw(u.display_name())];
+            if let Some(status) = u.status.as_deref().filter(|s| !s.is_empty()) {
+                line.push(Span::styled(format!(" {}", status), Style::default().fg(Color::DarkGray)));
+            }
+            ListItem::new(Line::from(line))
+        })
+        .collect();
+    let users_title = format!(" Online ({}) ", app.users.len());
+    frame.render_widget(List::new(items).block(Block::default().borders(Borders::ALL).title(users_title)), users);
+
+    let composer = Paragraph::new(app.input.as_str()).block(Block::default().borders(Borders::ALL).title(" Message "));
+    frame.render_widget(composer, input);
+    let cursor_x = input.x + 1 + (app.input.chars().count() as u16).min(input.width.saturating_sub(3));
+    frame.set_cursor(cursor_x, input.y + 1);
+}
+
+fn render_entry<'a>(app: &'a App, entry: &'a Entry) -> Line<'a> {
+    match entry {
+        Entry::Notice(text) => Line::styled(text.as_str(), Style::default().fg(Color::DarkGray).italic()),
+        Entry::Chat(chat) => {
+            let mut spans = vec![Span::styled(
+                app.display_name_of(&chat.from),
+                Style::default().fg(if chat.from == app.username { Color::Magenta } else { Color::Cyan }).bold(),
+            )];
+            if let Some(to) = &chat.to {
+                spans.push(Span::styled(format!(" → {}", app.display_name_of(to)), Style::default().fg(Color::Yellow)));
+            }
+            spans.push(Span::raw(": "));
+            if chat.message.ends_with(".gif") {
+                spans.push(Span::styled("[GIF] ", Style::default().fg(Color::DarkGray)));
+            }
+            spans.push(Span::raw(chat.message.as_str()));
+            Line::from(spans)
+        }
+    }
+}
+
+/// The newest lines, as many as fit once wrapped, so the pane stays scrolled
+/// to the bottom.
+fn last_lines_that_fit(app: &App, width: u16, height: u16) -> Vec<Line<'_>> {
+    let width = width.max(1) as usize;
+    let mut rows = 0;
+    let mut lines: Vec<Line> = app
+        .entries
+        .iter()
+        .rev()
+        .map(|entry| render_entry(app, entry))
+        .take_while(|line| {
+            rows += line.width().max(1).div_ceil(width);
+            rows <= height as usize
+        })
+        .collect();
+    lines.reverse();
+    lines
+}